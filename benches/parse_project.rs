@@ -0,0 +1,131 @@
+//! Parses a generated model the size of a large enterprise schema.
+//!
+//! Run with `cargo bench --bench parse_project`. Criterion isn't a dependency, so this is a
+//! plain binary which reports the fastest and the mean time of a few runs, and the most memory
+//! which was allocated at once during a run.
+
+use std::{
+	alloc::{GlobalAlloc, Layout, System},
+	fmt::Write,
+	hint::black_box,
+	io::Cursor,
+	sync::atomic::{AtomicUsize, Ordering},
+	time::Instant,
+};
+
+use magic_sql_gen::magicdraw_parser::{parse_project_file, ParseOptions};
+
+const TABLE_COUNT: usize = 600;
+const COLUMNS_PER_TABLE: usize = 12;
+const RUNS: u32 = 5;
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+static PEAK_ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+
+// Keeps track of the allocated bytes, so that memory spikes show up next to the times
+struct PeakAllocator;
+
+unsafe impl GlobalAlloc for PeakAllocator {
+	unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+		let ptr = System.alloc(layout);
+		if !ptr.is_null() {
+			let allocated = ALLOCATED.fetch_add(layout.size(), Ordering::Relaxed) + layout.size();
+			PEAK_ALLOCATED.fetch_max(allocated, Ordering::Relaxed);
+		}
+		ptr
+	}
+
+	unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+		System.dealloc(ptr, layout);
+		ALLOCATED.fetch_sub(layout.size(), Ordering::Relaxed);
+	}
+}
+
+#[global_allocator]
+static GLOBAL: PeakAllocator = PeakAllocator;
+
+// Every table has an id, a few typed columns and a foreign key to the table before it
+fn generate_fixture() -> String {
+	let mut xml = String::from(
+		r#"<?xml version="1.0" encoding="UTF-8"?>
+<xmi:XMI xmlns:xmi="http://www.omg.org/spec/XMI/20131001" xmlns:uml="http://www.omg.org/spec/UML/20131001" xmlns:SQLProfile="http://www.magicdraw.com/schemas/SQLProfile.xmi" xmlns:MagicDraw_Profile="http://www.omg.org/spec/UML/20131001/MagicDraw_Profile">
+<uml:Model xmi:id="model" name="Enterprise">
+"#,
+	);
+	let mut modifiers = String::new();
+	for table in 0..TABLE_COUNT {
+		writeln!(
+			xml,
+			r#"<packagedElement xmi:type="uml:Class" xmi:id="c{table}" name="table_{table}">"#
+		)
+		.unwrap();
+		writeln!(
+			xml,
+			r#"<ownedAttribute xmi:type="uml:Property" xmi:id="c{table}_id" name="id" isID="true"><type href="SQL2003.mdzip#INTEGER"/></ownedAttribute>"#
+		)
+		.unwrap();
+		for column in 0..COLUMNS_PER_TABLE {
+			let sql_type = ["VARCHAR", "INTEGER", "DATE", "DECIMAL"][column % 4];
+			writeln!(
+				xml,
+				r#"<ownedAttribute xmi:type="uml:Property" xmi:id="c{table}_p{column}" name="column_{column}"><type href="SQL2003.mdzip#{sql_type}"/></ownedAttribute>"#
+			)
+			.unwrap();
+			if sql_type == "VARCHAR" {
+				writeln!(
+					modifiers,
+					r#"<MagicDraw_Profile:typeModifier xmi:id="c{table}_t{column}" base_Element="c{table}_p{column}" typeModifier="100"/>"#
+				)
+				.unwrap();
+			}
+		}
+		if table > 0 {
+			let parent = table - 1;
+			writeln!(
+				xml,
+				r#"<ownedAttribute xmi:type="uml:Property" xmi:id="c{table}_fk" name="parent_id"><type href="SQL2003.mdzip#INTEGER"/></ownedAttribute>"#
+			)
+			.unwrap();
+			writeln!(
+				modifiers,
+				r#"<SQLProfile:FK xmi:id="c{table}_fk_modifier" members="c{table}_fk" referencedMembers="c{parent}_id"/>"#
+			)
+			.unwrap();
+		}
+		xml.push_str("</packagedElement>\n");
+	}
+	xml.push_str("</uml:Model>\n");
+	xml.push_str(&modifiers);
+	xml.push_str("</xmi:XMI>\n");
+	xml
+}
+
+fn main() {
+	let fixture = generate_fixture();
+	let options = ParseOptions::default();
+
+	let mut times = vec![];
+	let mut peak_memory = 0;
+	for _ in 0..RUNS {
+		let allocated_before = ALLOCATED.load(Ordering::Relaxed);
+		PEAK_ALLOCATED.store(allocated_before, Ordering::Relaxed);
+		let start = Instant::now();
+		let project = parse_project_file(Cursor::new(&fixture), &options).unwrap();
+		times.push(start.elapsed());
+		peak_memory = PEAK_ALLOCATED.load(Ordering::Relaxed) - allocated_before;
+		assert_eq!(project.collections[0].tables.len(), TABLE_COUNT);
+		black_box(project);
+	}
+
+	let fastest = times.iter().min().unwrap();
+	let mean = times.iter().sum::<std::time::Duration>() / RUNS;
+	println!(
+		"parse_project: {} tables, {} KiB, fastest {:?}, mean {:?} over {} runs, peak memory {} KiB",
+		TABLE_COUNT,
+		fixture.len() / 1024,
+		fastest,
+		mean,
+		RUNS,
+		peak_memory / 1024
+	);
+}
@@ -1,32 +1,117 @@
 use gloo::file::callbacks::FileReader;
-use gloo::file::File;
-use gloo::storage::{LocalStorage, Storage};
+use gloo::file::{File, FileReadError};
+use gloo::timers::future::TimeoutFuture;
+use serde::{Deserialize, Serialize};
 use std::cell::RefCell;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::io::Cursor;
+use std::mem;
 use std::rc::Rc;
-use web_sys::{DragEvent, Event, FileList, HtmlInputElement, MouseEvent};
+use web_sys::{DragEvent, Event, FileList, HtmlInputElement, HtmlTextAreaElement, MouseEvent};
 use yew::html::TargetCast;
+use yew::platform::spawn_local;
 use yew::{html, Callback, Component, Context, Html};
 
 use crate::components::sql_column_info::SQLTableColumnInfo;
-use crate::generate_sql::{generate_fake_entries, generate_table_guessess, SQLValueGuess};
-use crate::magicdraw_parser::{parse_project, SQLTable, SQLTableCollection};
+use crate::storage;
+use magic_sql_gen::generate_csv::to_csv;
+use magic_sql_gen::generate_ddl::generate_ddl;
+use magic_sql_gen::generate_json::to_json;
+use magic_sql_gen::generate_sql::{
+	generate_rows, generate_table_guessess, sql_script_end, sql_script_start, table_to_sql,
+	GenerateOptions, SQLDialect, SQLIntValueGuess, SQLOptions, SQLValueGuess,
+};
+use magic_sql_gen::generator_config::{apply_config, export_config, GeneratorConfig};
+use magic_sql_gen::magicdraw_parser::{
+	parse_project_file_with_progress, parse_type_mappings, ParseMode, ParseOptions,
+	ParseProjectError, ParseStage, ParseWarning, ParsedProject, ProjectMetadata, SQLTable,
+	SQLTableCollection, TableKind,
+};
 
+const TABLES_PER_PROGRESS_UPDATE: usize = 20;
+const PROJECT_FILE_EXTENSIONS: [&str; 3] = [".mdzip", ".mdxml", ".xml"];
 const COLLECTION_STORE_KEY: &str = "current_collection";
-const DEFAULT_ROWS_PER_TABLE: u32 = 20;
+const PROJECT_STORE_KEY: &str = "loaded_project";
+const TYPE_MAPPINGS_STORE_KEY: &str = "type_mappings";
+const SQL_OPTIONS_STORE_KEY: &str = "sql_options";
+const GENERATE_OPTIONS_STORE_KEY: &str = "generate_options";
+
+#[derive(Deserialize, Serialize)]
+struct LoadedProject {
+	file_name: String,
+	metadata: ProjectMetadata,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub(crate) enum OutputFormat {
+	#[default]
+	Sql,
+	Csv,
+	Json,
+}
+
+impl OutputFormat {
+	const ALL: [OutputFormat; 3] = [OutputFormat::Sql, OutputFormat::Csv, OutputFormat::Json];
+
+	fn label(&self) -> &'static str {
+		match self {
+			OutputFormat::Sql => "SQL",
+			OutputFormat::Csv => "CSV",
+			OutputFormat::Json => "JSON",
+		}
+	}
+}
+
+/// Output of a single table
+struct GeneratedTableOutput {
+	name: String,
+	row_count: usize,
+	content: String,
+}
+
+enum GeneratedOutput {
+	Sql {
+		/// Transaction and foreign key check statements around the inserts
+		start: String,
+		tables: Vec<GeneratedTableOutput>,
+		end: String,
+	},
+	/// Named after the file for each table
+	Csv(Vec<GeneratedTableOutput>),
+	Json(String),
+}
+
+fn data_url(mime_type: &str, content: &str) -> String {
+	format!(
+		"data:{};charset=utf-8,{}",
+		mime_type,
+		js_sys::encode_uri_component(content)
+	)
+}
 
 pub enum Msg {
 	Noop,
 	Loaded(String, Vec<u8>),
+	ParseProgress(ParseStage),
+	ProjectParsed(String, Result<ParsedProject, ParseProjectError>),
 	UploadProject(File),
 	UpdateCurrentProject(Option<SQLTableCollection>),
 	UpdateGenarator(String, SQLValueGuess),
+	IncludeView(usize, bool),
+	IncludeDDL(bool),
+	UpdateOutputFormat(OutputFormat),
 	ShowNextTable,
 	ShowPrevTable,
 	AllGoodConfirmation,
 	GenerateSQL,
-	UpdateRowsPerTable(u32),
+	ContinueAutoIncrements,
+	UpdateGenerateOptions(GenerateOptions),
+	UpdateSQLOptions(SQLOptions),
+	UpdateParseOptions(ParseOptions),
+	UpdateTypeMappings(String),
+	UploadConfig(File),
+	ConfigLoaded(String, Result<String, FileReadError>),
+	PickCollection(usize),
 }
 
 pub struct App {
@@ -34,9 +119,33 @@ pub struct App {
 	current_collection: Option<Vec<Rc<SQLTable>>>,
 	current_guessess: Vec<Rc<RefCell<HashMap<String, SQLValueGuess>>>>,
 	currently_shown_table: usize,
+	/// Views are left out of generation, unless they were included by hand
+	included_views: HashSet<usize>,
 	all_good_confirmed: bool,
-	generated_sql: Option<String>,
-	rows_per_table: u32,
+	output_format: OutputFormat,
+	generated_output: Option<GeneratedOutput>,
+	/// `CREATE TABLE` statements for the same tables as `generated_output`
+	generated_ddl: Option<String>,
+	include_ddl: bool,
+	generate_options: GenerateOptions,
+	sql_options: SQLOptions,
+	parse_options: ParseOptions,
+	parse_warnings: Vec<ParseWarning>,
+	parse_error: Option<String>,
+	parse_progress: Option<ParseStage>,
+	generate_error: Option<String>,
+	generate_warnings: Vec<String>,
+	/// Seed of the last generation, so that it can be repeated when no seed was given
+	generated_seed: Option<u64>,
+	/// Collection table index and auto-increment start values, which continue after the
+	/// rows of the last generation
+	continued_auto_increments: Vec<(usize, HashMap<String, u32>)>,
+	available_collections: Vec<SQLTableCollection>,
+	loaded_project: Option<LoadedProject>,
+	type_mappings: String,
+	type_mappings_error: Option<String>,
+	config_warnings: Vec<String>,
+	config_error: Option<String>,
 }
 
 impl Component for App {
@@ -46,7 +155,13 @@ impl Component for App {
 	fn create(_ctx: &Context<Self>) -> Self {
 		let mut current_guessess = vec![];
 		let mut current_collection = None;
-		if let Ok(collection) = LocalStorage::get::<SQLTableCollection>("current_collection") {
+		let mut storage_error = None;
+		let stored_collection = storage::load::<SQLTableCollection>(COLLECTION_STORE_KEY)
+			.unwrap_or_else(|e| {
+				storage_error = Some(e.to_string());
+				None
+			});
+		if let Some(collection) = stored_collection {
 			for table in &collection.tables {
 				let guess = generate_table_guessess(table);
 				current_guessess.push(Rc::new(RefCell::new(guess)));
@@ -55,34 +170,131 @@ impl Component for App {
 			current_collection = Some(collection.tables.into_iter().map(Rc::new).collect());
 		}
 
+		let type_mappings: String = storage::load(TYPE_MAPPINGS_STORE_KEY)
+			.ok()
+			.flatten()
+			.unwrap_or_default();
+		// Mappings are stored even when they don't parse, so the error is shown again
+		let (parsed_type_mappings, type_mappings_error) = match parse_type_mappings(&type_mappings)
+		{
+			Ok(parsed) => (parsed, None),
+			Err(e) => (HashMap::new(), Some(e.to_string())),
+		};
+		let parse_options = ParseOptions {
+			type_mappings: parsed_type_mappings,
+			..Default::default()
+		};
+
 		Self {
 			active_readers: HashMap::default(),
 			current_collection,
 			currently_shown_table: 0,
+			included_views: HashSet::new(),
 			all_good_confirmed: true, // TODO: make this false, by default
-			generated_sql: None,
+			output_format: OutputFormat::default(),
+			generated_output: None,
+			generated_ddl: None,
+			include_ddl: false,
 			current_guessess,
-			rows_per_table: DEFAULT_ROWS_PER_TABLE,
+			generate_options: storage::load(GENERATE_OPTIONS_STORE_KEY)
+				.ok()
+				.flatten()
+				.unwrap_or_default(),
+			sql_options: storage::load(SQL_OPTIONS_STORE_KEY)
+				.ok()
+				.flatten()
+				.unwrap_or_default(),
+			parse_options,
+			parse_warnings: vec![],
+			parse_error: storage_error,
+			parse_progress: None,
+			generate_error: None,
+			generate_warnings: vec![],
+			generated_seed: None,
+			continued_auto_increments: vec![],
+			available_collections: vec![],
+			loaded_project: storage::load(PROJECT_STORE_KEY).ok().flatten(),
+			type_mappings,
+			type_mappings_error,
+			config_warnings: vec![],
+			config_error: None,
 		}
 	}
 
 	fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
 		match msg {
 			Msg::Loaded(file_name, data) => {
-				if file_name.ends_with(".mdzip") {
-					let cursor = Cursor::new(&data);
-
-					let mut collections = parse_project(cursor).expect("oops");
-					if collections.len() >= 1 {
-						let msg = Self::update_current_collection(Some(collections.remove(0)));
-						ctx.link().send_message(msg);
-					}
-					// TODO: show error message
+				if PROJECT_FILE_EXTENSIONS
+					.iter()
+					.any(|extension| file_name.ends_with(extension))
+				{
+					let link = ctx.link().clone();
+					let parse_options = self.parse_options.clone();
+					let file_name = file_name.clone();
+					spawn_local(async move {
+						let progress_link = link.clone();
+						let result = parse_project_file_with_progress(
+							Cursor::new(&data),
+							&parse_options,
+							|stage| {
+								// Yielding after every table would add a timer delay per table
+								let should_yield = match stage {
+									ParseStage::BuildingTables { done, .. } => {
+										done % TABLES_PER_PROGRESS_UPDATE == 0
+									}
+									_ => true,
+								};
+								progress_link.send_message(Msg::ParseProgress(stage));
+								async move {
+									if should_yield {
+										TimeoutFuture::new(0).await;
+									}
+								}
+							},
+						)
+						.await;
+						link.send_message(Msg::ProjectParsed(file_name, result));
+					});
 				}
 
 				self.active_readers.remove(&file_name);
 				true
 			}
+			Msg::ParseProgress(stage) => {
+				self.parse_progress = Some(stage);
+				true
+			}
+			Msg::ProjectParsed(file_name, result) => {
+				self.parse_progress = None;
+				match result {
+					Ok(mut project) => {
+						self.parse_error = None;
+						self.parse_warnings = project.warnings;
+						let loaded_project = LoadedProject {
+							file_name,
+							metadata: project.metadata,
+						};
+						storage::store(PROJECT_STORE_KEY, &loaded_project);
+						self.loaded_project = Some(loaded_project);
+						if project.collections.len() == 1 {
+							let msg = Self::update_current_collection(Some(
+								project.collections.remove(0),
+							));
+							ctx.link().send_message(msg);
+						} else if project.collections.len() > 1 {
+							// Let the user pick which DDL script to work with
+							self.available_collections = project.collections;
+							ctx.link()
+								.send_message(Self::update_current_collection(None));
+						}
+					}
+					Err(e) => {
+						self.parse_error = Some(e.to_string());
+						self.parse_warnings = vec![];
+					}
+				}
+				true
+			}
 			Msg::UploadProject(file) => {
 				let file_name = file.name();
 
@@ -99,13 +311,56 @@ impl Component for App {
 				self.active_readers.insert(file_name, task);
 				true
 			}
+			Msg::UploadConfig(file) => {
+				let file_name = file.name();
+				let task = {
+					let link = ctx.link().clone();
+					let file_name = file_name.clone();
+					gloo::file::callbacks::read_as_text(&file, move |res| {
+						link.send_message(Msg::ConfigLoaded(file_name, res))
+					})
+				};
+				self.active_readers.insert(file_name, task);
+				false
+			}
+			Msg::ConfigLoaded(file_name, json) => {
+				self.active_readers.remove(&file_name);
+				self.config_warnings = vec![];
+				let config = json
+					.map_err(|e| e.to_string())
+					.and_then(|json| GeneratorConfig::from_json(&json).map_err(|e| e.to_string()));
+				match config {
+					Ok(config) => {
+						self.config_error = None;
+						let tables = self.current_collection.as_ref().unwrap();
+						let mut guessess = self
+							.current_guessess
+							.iter()
+							.map(|guessess| guessess.borrow_mut())
+							.collect::<Vec<_>>();
+						self.config_warnings = apply_config(
+							&config,
+							tables,
+							&mut guessess,
+							&mut self.generate_options,
+						);
+						storage::store(GENERATE_OPTIONS_STORE_KEY, &self.generate_options);
+					}
+					Err(e) => self.config_error = Some(e),
+				}
+				true
+			}
 			Msg::Noop => false,
 			Msg::UpdateCurrentProject(collection) => {
 				if let Some(collection) = collection {
-					LocalStorage::set(COLLECTION_STORE_KEY, &collection).unwrap();
+					storage::store(COLLECTION_STORE_KEY, &collection);
 					self.currently_shown_table = 0;
+					self.included_views = HashSet::new();
 					self.all_good_confirmed = false;
-					self.generated_sql = None;
+					self.generated_output = None;
+					self.generate_error = None;
+					self.generate_warnings = vec![];
+					self.continued_auto_increments = vec![];
 					self.current_guessess = vec![];
 					for table in &collection.tables {
 						let guess = generate_table_guessess(table);
@@ -114,7 +369,7 @@ impl Component for App {
 					self.current_collection =
 						Some(collection.tables.into_iter().map(Rc::new).collect());
 				} else {
-					LocalStorage::delete(COLLECTION_STORE_KEY);
+					storage::delete(COLLECTION_STORE_KEY);
 					self.current_collection = None
 				}
 
@@ -145,19 +400,134 @@ impl Component for App {
 				*entry = generator;
 				true
 			}
-			Msg::GenerateSQL => {
-				let tables = self.current_collection.as_ref().unwrap();
-				let guessess = self.current_guessess.iter().map(|v| v.borrow()).collect();
-				// TODO: show error message
-				if let Ok(result) = generate_fake_entries(tables, &guessess, self.rows_per_table) {
-					self.generated_sql = Some(result)
+			Msg::IncludeDDL(include_ddl) => {
+				self.include_ddl = include_ddl;
+				true
+			}
+			Msg::IncludeView(idx, included) => {
+				if included {
+					self.included_views.insert(idx);
 				} else {
-					self.generated_sql = None
+					self.included_views.remove(&idx);
+				}
+				true
+			}
+			Msg::GenerateSQL => {
+				let collection = self.current_collection.as_ref().unwrap();
+				let mut tables = vec![];
+				let mut table_idxs = vec![];
+				let mut guessess = vec![];
+				for (i, table) in collection.iter().enumerate() {
+					if table.kind == TableKind::View && !self.included_views.contains(&i) {
+						continue;
+					}
+					tables.push(table.clone());
+					table_idxs.push(i);
+					guessess.push(self.current_guessess[i].borrow());
+				}
+				match generate_rows(&tables, &guessess, &self.generate_options) {
+					Ok(generated) => {
+						let output = match self.output_format {
+							OutputFormat::Sql => GeneratedOutput::Sql {
+								start: sql_script_start(&generated, &self.sql_options),
+								tables: generated
+									.insert_order()
+									.order
+									.into_iter()
+									.map(|table_idx| &generated.tables[table_idx])
+									.map(|table| GeneratedTableOutput {
+										name: table.name(),
+										row_count: table.rows.len(),
+										content: table_to_sql(table, &self.sql_options),
+									})
+									.collect(),
+								end: sql_script_end(&generated, &self.sql_options),
+							},
+							OutputFormat::Csv => GeneratedOutput::Csv(
+								generated
+									.tables
+									.iter()
+									.map(|table| GeneratedTableOutput {
+										name: format!("{}.csv", table.name()),
+										row_count: table.rows.len(),
+										content: to_csv(table),
+									})
+									.collect(),
+							),
+							OutputFormat::Json => GeneratedOutput::Json(to_json(&generated)),
+						};
+						self.generated_seed = Some(generated.seed);
+						self.generate_warnings = generated.warnings.clone();
+						self.continued_auto_increments = table_idxs
+							.into_iter()
+							.zip(generated.continued_auto_increments(&guessess))
+							.filter(|(_, starts)| !starts.is_empty())
+							.collect();
+						self.generated_output = Some(output);
+						self.generated_ddl = Some(generate_ddl(&tables, &self.sql_options));
+						self.generate_error = None;
+					}
+					Err(e) => {
+						self.generated_output = None;
+						self.generate_warnings = vec![];
+						self.generate_error = Some(e.to_string());
+					}
+				}
+				true
+			}
+			Msg::ContinueAutoIncrements => {
+				for (table_idx, starts) in mem::take(&mut self.continued_auto_increments) {
+					let mut guessess = self.current_guessess[table_idx].borrow_mut();
+					for (column, continued_start) in starts {
+						if let Some(SQLValueGuess::Int(SQLIntValueGuess::AutoIncrement {
+							start,
+							..
+						})) = guessess.get_mut(&column)
+						{
+							*start = continued_start;
+						}
+					}
+				}
+				true
+			}
+			Msg::UpdateOutputFormat(output_format) => {
+				self.output_format = output_format;
+				true
+			}
+			Msg::UpdateGenerateOptions(generate_options) => {
+				storage::store(GENERATE_OPTIONS_STORE_KEY, &generate_options);
+				self.generate_options = generate_options;
+				// The config download link contains the options
+				true
+			}
+			Msg::UpdateSQLOptions(sql_options) => {
+				storage::store(SQL_OPTIONS_STORE_KEY, &sql_options);
+				self.sql_options = sql_options;
+				true
+			}
+			Msg::UpdateParseOptions(parse_options) => {
+				self.parse_options = parse_options;
+				true
+			}
+			Msg::UpdateTypeMappings(type_mappings) => {
+				match parse_type_mappings(&type_mappings) {
+					Ok(parsed) => {
+						self.parse_options.type_mappings = parsed;
+						self.type_mappings_error = None;
+					}
+					Err(e) => self.type_mappings_error = Some(e.to_string()),
 				}
+				storage::store(TYPE_MAPPINGS_STORE_KEY, &type_mappings);
+				self.type_mappings = type_mappings;
 				true
 			}
-			Msg::UpdateRowsPerTable(rows_per_table) => {
-				self.rows_per_table = rows_per_table;
+			Msg::PickCollection(idx) => {
+				if idx < self.available_collections.len() {
+					let collection = self.available_collections.remove(idx);
+					self.available_collections = vec![];
+					ctx.link()
+						.send_message(Self::update_current_collection(Some(collection)));
+				}
 				false
 			}
 		}
@@ -168,11 +538,18 @@ impl Component for App {
 			<main class="flex-col 4rem center">
 				<p class="text-3xl text-center">{ "🪄 MagicDraw SQL Data Generator" }</p>
 				{ self.show_step1(ctx) }
+				if !self.parse_warnings.is_empty() {
+					{ self.show_parse_warnings() }
+				}
+				if !self.available_collections.is_empty() {
+					{ self.show_collection_picker(ctx) }
+				}
 				if self.current_collection.is_some() {
+					{ self.show_loaded_project() }
 					{ self.show_step2(ctx) }
 					if self.all_good_confirmed {
 						{ self.show_step3(ctx) }
-						if self.generated_sql.is_some() {
+						if self.generated_output.is_some() {
 							{ self.show_step4(ctx) }
 						}
 					}
@@ -193,6 +570,8 @@ impl App {
 				<p class="text-2xl mt-2rem pb-1rem">
 					<span>{ "1. Upload " }</span>
 					<code class="bg-dark900 p-0.2rem rounded">{".mdzip"}</code>
+					<span>{ " or " }</span>
+					<code class="bg-dark900 p-0.2rem rounded">{".xml"}</code>
 					<span>{ " project" }</span>
 				</p>
 				<label for="file-upload">
@@ -215,19 +594,200 @@ impl App {
 					id="file-upload"
 					type="file"
 					class = "hidden"
-					accept=".mdzip"
+					accept={PROJECT_FILE_EXTENSIONS.join(",")}
 					onchange={ctx.link().callback(move |e: Event| {
 						let input: HtmlInputElement = e.target_unchecked_into();
 						Self::upload_project(input.files())
 					})}
 				/>
-				<p class="text-amber300">{ "NOTE: This relies on the fact, that you have a .dll script configured" }</p>
+				{ self.show_parse_mode_toggle(ctx) }
+				{ self.show_parse_option(
+					ctx,
+					"infer-foreign-keys",
+					"Infer foreign keys from associations",
+					|options| &mut options.infer_foreign_keys,
+				) }
+				{ self.show_parse_option(
+					ctx,
+					"merge-ddl-scripts",
+					"Merge all DDL scripts into one collection",
+					|options| &mut options.merge_ddl_scripts,
+				) }
+				{ self.show_parse_option(
+					ctx,
+					"placeholder-columns",
+					"Keep properties without a type or name as placeholder columns",
+					|options| &mut options.placeholder_columns,
+				) }
+				{ self.show_parse_option(
+					ctx,
+					"non-persistent-classes",
+					"Include abstract and transient classes as tables",
+					|options| &mut options.include_non_persistent_classes,
+				) }
+				{ self.show_type_mappings(ctx) }
+				if let Some(parse_progress) = &self.parse_progress {
+					{ self.show_parse_progress(parse_progress) }
+				}
+				if let Some(parse_error) = &self.parse_error {
+					<p class="text-red400 whitespace-pre-line">{ "Failed to load project: " }{ parse_error }</p>
+				}
+				<p class="text-amber300">{ "NOTE: Tables are taken from the .dll script if one is configured, otherwise from all classes of the model" }</p>
+			</div>
+		}
+	}
+
+	fn show_type_mappings(&self, ctx: &Context<Self>) -> Html {
+		let onchange = ctx.link().callback(|e: Event| {
+			Msg::UpdateTypeMappings(e.target_unchecked_into::<HtmlTextAreaElement>().value())
+		});
+
+		html! {
+			<details class="mt-0.5rem">
+				<summary cursor="pointer">{ "Custom type mappings" }</summary>
+				<p>{ "One per line, for types from custom profiles which aren't standard SQL types" }</p>
+				<textarea
+					class="w-full rounded p-0.3rem bg-dark800 text-light100 b-0"
+					rows="4"
+					placeholder={"Money = DECIMAL(19,4)\nShortText = VARCHAR(50)\nIdentifier = INT"}
+					value={self.type_mappings.clone()}
+					{onchange}
+				/>
+				if let Some(error) = &self.type_mappings_error {
+					<p class="text-red400">{ error }</p>
+				}
+			</details>
+		}
+	}
+
+	fn show_parse_option(
+		&self,
+		ctx: &Context<Self>,
+		id: &'static str,
+		label: &'static str,
+		option: fn(&mut ParseOptions) -> &mut bool,
+	) -> Html {
+		let mut parse_options = self.parse_options.clone();
+		let checked = *option(&mut parse_options);
+		let onchange = ctx.link().callback(move |e: Event| {
+			let mut parse_options = parse_options.clone();
+			*option(&mut parse_options) = e.target_unchecked_into::<HtmlInputElement>().checked();
+			Msg::UpdateParseOptions(parse_options)
+		});
+
+		html! {
+			<div class="mt-0.5rem">
+				<input {id} type="checkbox" {checked} {onchange} />
+				<label for={id}>{ " " }{ label }</label>
+			</div>
+		}
+	}
+
+	fn show_parse_mode_toggle(&self, ctx: &Context<Self>) -> Html {
+		let parse_options = self.parse_options.clone();
+		let checked = parse_options.mode == ParseMode::Strict;
+		let onchange = ctx.link().callback(move |e: Event| {
+			let mut parse_options = parse_options.clone();
+			parse_options.mode = if e.target_unchecked_into::<HtmlInputElement>().checked() {
+				ParseMode::Strict
+			} else {
+				ParseMode::Lenient
+			};
+			Msg::UpdateParseOptions(parse_options)
+		});
+
+		html! {
+			<div class="mt-0.5rem">
+				<input id="strict-mode" type="checkbox" {checked} {onchange} />
+				<label for="strict-mode">{ " Strict mode, fail on any problem instead of warning about it" }</label>
+			</div>
+		}
+	}
+
+	fn show_parse_progress(&self, stage: &ParseStage) -> Html {
+		let (value, max) = match stage {
+			ParseStage::ReadingModel => (0, 3),
+			ParseStage::ResolvingTypes => (1, 3),
+			ParseStage::ReadingDdl => (2, 3),
+			ParseStage::BuildingTables { done, total } => (*done, *total),
+		};
+
+		html! {
+			<div class="mt-0.5rem">
+				<progress class="w-full" value={value.to_string()} max={max.to_string()} />
+				<p>{ stage.to_string() }</p>
+			</div>
+		}
+	}
+
+	fn show_loaded_project(&self) -> Html {
+		let Some(project) = &self.loaded_project else {
+			return html! {};
+		};
+
+		let mut description = format!("Loaded: {}", project.file_name);
+		if let Some(name) = &project.metadata.name {
+			description.push_str(&format!(" — project '{}'", name));
+		}
+		if let Some(tool_version) = &project.metadata.tool_version {
+			description.push_str(&format!(", MagicDraw {}", tool_version));
+		}
+		if let Some(modified) = &project.metadata.modified {
+			description.push_str(&format!(", modified {}", modified));
+		}
+		if let Some(collection) = &self.current_collection {
+			description.push_str(&format!(", {} tables", collection.len()));
+		}
+
+		html! {
+			<p class="mt-2rem">{ description }</p>
+		}
+	}
+
+	fn show_parse_warnings(&self) -> Html {
+		html! {
+			<details class="mt-1rem">
+				<summary class="text-amber300" cursor="pointer">
+					{ format!("⚠️ {} warning(s) while parsing project", self.parse_warnings.len()) }
+				</summary>
+				<ul>
+					{ for self.parse_warnings.iter().map(|warning| html! { <li>{ warning.to_string() }</li> }) }
+				</ul>
+			</details>
+		}
+	}
+
+	fn show_collection_picker(&self, ctx: &Context<Self>) -> Html {
+		let collections = self
+			.available_collections
+			.iter()
+			.enumerate()
+			.map(|(i, collection)| {
+				let name = collection
+					.name
+					.clone()
+					.unwrap_or_else(|| format!("Unnamed script #{}", i + 1));
+				html! {
+					<button
+						class="block mt-0.5rem p-0.5rem btn-white"
+						onclick={ctx.link().callback(move |_: MouseEvent| { Msg::PickCollection(i) })}
+					>
+						{ format!("{} ({} tables)", name, collection.tables.len()) }
+					</button>
+				}
+			});
+
+		html! {
+			<div>
+				<p class="text-2xl mt-2rem">{ "Pick a DDL script" }</p>
+				{ for collections }
 			</div>
 		}
 	}
 
 	fn show_step2(&self, ctx: &Context<Self>) -> Html {
 		let collection = self.current_collection.as_ref().unwrap();
+		let shown_table = self.currently_shown_table;
 
 		html! {
 			<div>
@@ -253,6 +813,10 @@ impl App {
 					onchange={ctx.link().callback(|(column_name, generator)| {
 						Msg::UpdateGenarator(column_name, generator)
 					})}
+					view_included={self.included_views.contains(&shown_table)}
+					on_view_included_change={ctx.link().callback(move |included| {
+						Msg::IncludeView(shown_table, included)
+					})}
 				/>
 				<button
 					class="display-block p-1rem  mt-1rem btn-emerald"
@@ -263,10 +827,159 @@ impl App {
 	}
 
 	fn show_step3(&self, ctx: &Context<Self>) -> Html {
-		let on_rows_changed = ctx.link().callback(|e: Event| {
-			let value_str = e.target_unchecked_into::<HtmlInputElement>().value();
-			let value = value_str.parse().unwrap_or(DEFAULT_ROWS_PER_TABLE);
-			Msg::UpdateRowsPerTable(value)
+		let default_options = GenerateOptions::default();
+
+		let on_seed_changed = {
+			let generate_options = self.generate_options.clone();
+			ctx.link().callback(move |e: Event| {
+				let value_str = e.target_unchecked_into::<HtmlInputElement>().value();
+				let mut generate_options = generate_options.clone();
+				// Blank means a random seed
+				generate_options.seed = value_str.trim().parse().ok();
+				Msg::UpdateGenerateOptions(generate_options)
+			})
+		};
+
+		let on_use_generated_seed = {
+			let mut generate_options = self.generate_options.clone();
+			generate_options.seed = self.generated_seed;
+			ctx.link()
+				.callback(move |_: MouseEvent| Msg::UpdateGenerateOptions(generate_options.clone()))
+		};
+
+		let on_rows_changed = {
+			let generate_options = self.generate_options.clone();
+			ctx.link().callback(move |e: Event| {
+				let value_str = e.target_unchecked_into::<HtmlInputElement>().value();
+				let mut generate_options = generate_options.clone();
+				generate_options.rows_per_table =
+					value_str.parse().unwrap_or(default_options.rows_per_table);
+				Msg::UpdateGenerateOptions(generate_options)
+			})
+		};
+
+		let on_max_rows_per_insert_changed = {
+			let sql_options = self.sql_options.clone();
+			ctx.link().callback(move |e: Event| {
+				let value_str = e.target_unchecked_into::<HtmlInputElement>().value();
+				let mut sql_options = sql_options.clone();
+				sql_options.max_rows_per_insert = value_str
+					.parse()
+					.unwrap_or(SQLOptions::default().max_rows_per_insert);
+				Msg::UpdateSQLOptions(sql_options)
+			})
+		};
+
+		let on_insert_per_row_changed = {
+			let sql_options = self.sql_options.clone();
+			ctx.link().callback(move |e: Event| {
+				let mut sql_options = sql_options.clone();
+				sql_options.insert_per_row =
+					e.target_unchecked_into::<HtmlInputElement>().checked();
+				Msg::UpdateSQLOptions(sql_options)
+			})
+		};
+
+		let on_root_rows_changed = {
+			let generate_options = self.generate_options.clone();
+			ctx.link().callback(move |e: Event| {
+				let value_str = e.target_unchecked_into::<HtmlInputElement>().value();
+				let mut generate_options = generate_options.clone();
+				generate_options.root_row_probability = value_str
+					.parse::<f64>()
+					.map(|percent| (percent / 100.0).clamp(0.0, 1.0))
+					.unwrap_or(default_options.root_row_probability);
+				Msg::UpdateGenerateOptions(generate_options)
+			})
+		};
+
+		let on_quote_identifiers_changed = {
+			let sql_options = self.sql_options.clone();
+			ctx.link().callback(move |e: Event| {
+				let mut sql_options = sql_options.clone();
+				sql_options.quote_identifiers =
+					e.target_unchecked_into::<HtmlInputElement>().checked();
+				Msg::UpdateSQLOptions(sql_options)
+			})
+		};
+
+		let on_transaction_changed = {
+			let sql_options = self.sql_options.clone();
+			ctx.link().callback(move |e: Event| {
+				let mut sql_options = sql_options.clone();
+				sql_options.transaction = e.target_unchecked_into::<HtmlInputElement>().checked();
+				Msg::UpdateSQLOptions(sql_options)
+			})
+		};
+
+		let on_disable_foreign_key_checks_changed = {
+			let sql_options = self.sql_options.clone();
+			ctx.link().callback(move |e: Event| {
+				let mut sql_options = sql_options.clone();
+				sql_options.disable_foreign_key_checks =
+					e.target_unchecked_into::<HtmlInputElement>().checked();
+				Msg::UpdateSQLOptions(sql_options)
+			})
+		};
+
+		let on_cleanup_changed = {
+			let sql_options = self.sql_options.clone();
+			ctx.link().callback(move |e: Event| {
+				let mut sql_options = sql_options.clone();
+				sql_options.cleanup = e.target_unchecked_into::<HtmlInputElement>().checked();
+				Msg::UpdateSQLOptions(sql_options)
+			})
+		};
+
+		let on_deferred_updates_changed = {
+			let sql_options = self.sql_options.clone();
+			ctx.link().callback(move |e: Event| {
+				let mut sql_options = sql_options.clone();
+				sql_options.deferred_updates =
+					e.target_unchecked_into::<HtmlInputElement>().checked();
+				Msg::UpdateSQLOptions(sql_options)
+			})
+		};
+
+		let on_pad_char_columns_changed = {
+			let sql_options = self.sql_options.clone();
+			ctx.link().callback(move |e: Event| {
+				let mut sql_options = sql_options.clone();
+				sql_options.pad_char_columns =
+					e.target_unchecked_into::<HtmlInputElement>().checked();
+				Msg::UpdateSQLOptions(sql_options)
+			})
+		};
+
+		let on_comments_changed = {
+			let sql_options = self.sql_options.clone();
+			ctx.link().callback(move |e: Event| {
+				let mut sql_options = sql_options.clone();
+				sql_options.comments = e.target_unchecked_into::<HtmlInputElement>().checked();
+				Msg::UpdateSQLOptions(sql_options)
+			})
+		};
+
+		let on_dialect_changed = {
+			let sql_options = self.sql_options.clone();
+			ctx.link().callback(move |e: Event| {
+				let value = e.target_unchecked_into::<HtmlInputElement>().value();
+				let mut sql_options = sql_options.clone();
+				sql_options.dialect = SQLDialect::ALL
+					.into_iter()
+					.find(|dialect| dialect.label() == value)
+					.unwrap_or_default();
+				Msg::UpdateSQLOptions(sql_options)
+			})
+		};
+
+		let on_output_format_changed = ctx.link().callback(|e: Event| {
+			let value = e.target_unchecked_into::<HtmlInputElement>().value();
+			let format = OutputFormat::ALL
+				.into_iter()
+				.find(|format| format.label() == value)
+				.unwrap_or_default();
+			Msg::UpdateOutputFormat(format)
 		});
 
 		html! {
@@ -278,10 +991,134 @@ impl App {
 				<input
 					id="gen-amount-input"
 					class="rounded items-center p-0.3rem bg-dark800 text-light100 w-5rem b-0"
-					value={self.rows_per_table.to_string()}
+					value={self.generate_options.rows_per_table.to_string()}
 					type="number"
 					onchange={on_rows_changed}
 				/>
+				<div class="mt-0.5rem">
+					<label for="seed-input">{ "Seed: " }</label>
+					<input
+						id="seed-input"
+						class="rounded items-center p-0.3rem bg-dark800 text-light100 w-12rem b-0"
+						value={self.generate_options.seed.map(|seed| seed.to_string()).unwrap_or_default()}
+						placeholder="random"
+						onchange={on_seed_changed}
+					/>
+					if let (Some(seed), None) = (self.generated_seed, self.generate_options.seed) {
+						<span class="ml-1rem">{ format!("Last generated with seed {} ", seed) }</span>
+						<button class="p-0.3rem btn-white" onclick={on_use_generated_seed}>
+							{ "Use it" }
+						</button>
+					}
+				</div>
+				<div class="mt-0.5rem">
+					<label for="rows-per-insert-input">
+						{ "Rows per INSERT statement: " }
+					</label>
+					<input
+						id="rows-per-insert-input"
+						class="rounded items-center p-0.3rem bg-dark800 text-light100 w-5rem b-0"
+						value={self.sql_options.max_rows_per_insert.to_string()}
+						type="number"
+						disabled={self.sql_options.insert_per_row}
+						onchange={on_max_rows_per_insert_changed}
+					/>
+					<input
+						id="insert-per-row-input"
+						class="ml-1rem"
+						type="checkbox"
+						checked={self.sql_options.insert_per_row}
+						onchange={on_insert_per_row_changed}
+					/>
+					<label for="insert-per-row-input">{ " One INSERT per row" }</label>
+				</div>
+				<div class="mt-0.5rem">
+					<label for="root-rows-input">
+						{ "Rows without a parent in self referencing tables (%): " }
+					</label>
+					<input
+						id="root-rows-input"
+						class="rounded items-center p-0.3rem bg-dark800 text-light100 w-5rem b-0"
+						value={(self.generate_options.root_row_probability * 100.0).to_string()}
+						type="number"
+						onchange={on_root_rows_changed}
+					/>
+				</div>
+				<div class="mt-0.5rem">
+					<label for="dialect-input">{ "Database: " }</label>
+					<select id="dialect-input" onchange={on_dialect_changed}>
+						{ for SQLDialect::ALL.iter().map(|dialect| html! {
+							<option selected={*dialect == self.sql_options.dialect} value={dialect.label()}>
+								{ dialect.label() }
+							</option>
+						}) }
+					</select>
+				</div>
+				<div class="mt-0.5rem">
+					<input
+						id="quote-identifiers-input"
+						type="checkbox"
+						checked={self.sql_options.quote_identifiers}
+						onchange={on_quote_identifiers_changed}
+					/>
+					<label for="quote-identifiers-input">{ " Always quote table and column names" }</label>
+				</div>
+				<div class="mt-0.5rem">
+					<input
+						id="comments-input"
+						type="checkbox"
+						checked={self.sql_options.comments}
+						onchange={on_comments_changed}
+					/>
+					<label for="comments-input">{ " Comment foreign key columns with their constraint names" }</label>
+				</div>
+				<div class="mt-0.5rem">
+					<input
+						id="transaction-input"
+						type="checkbox"
+						checked={self.sql_options.transaction}
+						onchange={on_transaction_changed}
+					/>
+					<label for="transaction-input">{ " Wrap in a transaction" }</label>
+				</div>
+				<div class="mt-0.5rem">
+					<input
+						id="disable-fk-checks-input"
+						type="checkbox"
+						checked={self.sql_options.disable_foreign_key_checks}
+						onchange={on_disable_foreign_key_checks_changed}
+					/>
+					<label for="disable-fk-checks-input">{ " Disable foreign key checks while inserting" }</label>
+				</div>
+				<div class="mt-0.5rem">
+					<input
+						id="cleanup-input"
+						type="checkbox"
+						checked={self.sql_options.cleanup}
+						onchange={on_cleanup_changed}
+					/>
+					<label for="cleanup-input">{ " Delete existing rows before inserting" }</label>
+				</div>
+				<div class="mt-0.5rem">
+					<input
+						id="deferred-updates-input"
+						type="checkbox"
+						checked={self.sql_options.deferred_updates}
+						onchange={on_deferred_updates_changed}
+					/>
+					<label for="deferred-updates-input">
+						{ " Fill in foreign keys left NULL to break cycles with UPDATE statements" }
+					</label>
+				</div>
+				<div class="mt-0.5rem">
+					<input
+						id="pad-char-columns-input"
+						type="checkbox"
+						checked={self.sql_options.pad_char_columns}
+						onchange={on_pad_char_columns_changed}
+					/>
+					<label for="pad-char-columns-input">{ " Pad CHAR values with spaces to the column size" }</label>
+				</div>
 
 				<button
 					class="block mt-1rem p-1rem btn-emerald"
@@ -289,22 +1126,232 @@ impl App {
 				>
 					{ "Generate" }
 				</button>
+				if !self.continued_auto_increments.is_empty() {
+					<div class="mt-0.5rem">
+						{ "Auto-increment columns can continue after the last generation " }
+						<button
+							class="p-0.3rem btn-white"
+							onclick={ctx.link().callback(|_: MouseEvent| Msg::ContinueAutoIncrements)}
+						>
+							{ "Continue from there" }
+						</button>
+					</div>
+				}
+				<div class="mt-0.5rem">
+					<label for="output-format-input">{ "Output format: " }</label>
+					<select id="output-format-input" onchange={on_output_format_changed}>
+						{ for OutputFormat::ALL.iter().map(|format| html! {
+							<option selected={*format == self.output_format} value={format.label()}>
+								{ format.label() }
+							</option>
+						}) }
+					</select>
+				</div>
+				{ self.show_table_rows(ctx) }
+				{ self.show_config_buttons(ctx) }
+				if let Some(generate_error) = &self.generate_error {
+					<p class="text-red400">{ "Failed to generate SQL: " }{ generate_error }</p>
+				}
+				{ for self.generate_warnings.iter().map(|warning| html! {
+					<p class="text-amber300">{ warning }</p>
+				}) }
+			</div>
+		}
+	}
+
+	fn show_table_rows(&self, ctx: &Context<Self>) -> Html {
+		let tables = self.current_collection.as_ref().unwrap();
+
+		html! {
+			<details class="mt-0.5rem">
+				<summary class="cursor-pointer">{ "Entries of each table" }</summary>
+				<table>
+					{ for tables.iter().enumerate().map(|(i, table)| {
+						if table.kind == TableKind::View && !self.included_views.contains(&i) {
+							return html! {};
+						}
+						let name = table.qualified_name();
+						// Lookup tables always have a row for each literal
+						let (rows, disabled) = match &table.literals {
+							Some(literals) => (literals.len() as u32, true),
+							None => (self.generate_options.rows_of(table), false),
+						};
+						let on_change = {
+							let generate_options = self.generate_options.clone();
+							let name = name.clone();
+							ctx.link().callback(move |e: Event| {
+								let value_str = e.target_unchecked_into::<HtmlInputElement>().value();
+								let mut generate_options = generate_options.clone();
+								match value_str.trim().parse::<u32>() {
+									Ok(rows) => generate_options.table_rows.insert(name.clone(), rows),
+									// Blank falls back to the entries per table
+									Err(_) => generate_options.table_rows.remove(&name),
+								};
+								Msg::UpdateGenerateOptions(generate_options)
+							})
+						};
+						html! {
+							<tr>
+								<td class="pr-1rem">{ &name }</td>
+								<td>
+									<input
+										class="rounded items-center p-0.3rem bg-dark800 text-light100 w-5rem b-0"
+										value={rows.to_string()}
+										type="number"
+										disabled={disabled}
+										onchange={on_change}
+									/>
+								</td>
+							</tr>
+						}
+					}) }
+				</table>
+			</details>
+		}
+	}
+
+	fn show_config_buttons(&self, ctx: &Context<Self>) -> Html {
+		let tables = self.current_collection.as_ref().unwrap();
+		let guessess = self
+			.current_guessess
+			.iter()
+			.map(|guessess| guessess.borrow())
+			.collect::<Vec<_>>();
+		let config = export_config(tables, &guessess, &self.generate_options);
+		let config_url = data_url("application/json", &config.to_json());
+
+		let on_config_uploaded = ctx.link().callback(|e: Event| {
+			let input = e.target_unchecked_into::<HtmlInputElement>();
+			match input.files().and_then(|files| files.get(0)) {
+				Some(file) => Msg::UploadConfig(File::from(file)),
+				None => Msg::Noop,
+			}
+		});
+
+		html! {
+			<div>
+			<div class="mt-1rem flex flex-row gap-3 items-center">
+				<a class="p-0.5rem btn-white" href={config_url} download="magic-sql-gen-config.json">
+					{ "Download config" }
+				</a>
+				<label class="p-0.5rem btn-white" for="config-input">{ "Load config" }</label>
+				<input
+					id="config-input"
+					class="hidden"
+					type="file"
+					accept=".json"
+					onchange={on_config_uploaded}
+				/>
+			</div>
+			if let Some(config_error) = &self.config_error {
+				<p class="text-red400">{ "Failed to load config: " }{ config_error }</p>
+			}
+			{ for self.config_warnings.iter().map(|warning| html! {
+				<p class="text-amber300">{ warning }</p>
+			}) }
 			</div>
 		}
 	}
 
 	fn show_step4(&self, ctx: &Context<Self>) -> Html {
-		let sql = self.generated_sql.as_ref().unwrap();
+		let (start, tables, end) = match self.generated_output.as_ref().unwrap() {
+			GeneratedOutput::Sql { start, tables, end } => (start, tables, end),
+			GeneratedOutput::Csv(files) => return self.show_csv_files(files),
+			GeneratedOutput::Json(json) => return self.show_json(json),
+		};
+		let on_include_ddl_changed = ctx.link().callback(|e: Event| {
+			Msg::IncludeDDL(e.target_unchecked_into::<HtmlInputElement>().checked())
+		});
+		let ddl = self.generated_ddl.as_ref().filter(|_| self.include_ddl);
+		let sql = ddl
+			.into_iter()
+			.chain(Some(start))
+			.chain(tables.iter().map(|table| &table.content))
+			.chain(Some(end))
+			.filter(|sql| !sql.is_empty())
+			.map(String::as_str)
+			.collect::<Vec<_>>()
+			.join("\n");
+
 		html! {
 			<div>
 				<p class="text-2xl mt-2rem">{ "4. Copy & Paste" }</p>
+				<div class="mb-0.5rem">
+					<input
+						id="include-ddl-input"
+						type="checkbox"
+						checked={self.include_ddl}
+						onchange={on_include_ddl_changed}
+					/>
+					<label for="include-ddl-input">{ " Include CREATE TABLE statements" }</label>
+				</div>
+				<a class="p-0.5rem btn-white" href={data_url("application/sql", &sql)} download="generated.sql">
+					{ "Download" }
+				</a>
+				if let Some(ddl) = ddl {
+					<details class="mt-0.5rem" open=true>
+						<summary class="cursor-pointer">{ "CREATE TABLE statements" }</summary>
+						<pre class="bg-dark900 p-0.5rem rounded">
+							{ ddl }
+						</pre>
+					</details>
+				}
+				if !start.is_empty() {
+					<pre class="mt-0.5rem bg-dark900 p-0.5rem rounded">
+						{ start }
+					</pre>
+				}
+				{ for tables.iter().map(|table| html! {
+					<details class="mt-0.5rem" open=true>
+						<summary class="cursor-pointer">
+							{ format!("{} ({} rows)", table.name, table.row_count) }
+						</summary>
+						<pre class="bg-dark900 p-0.5rem rounded">
+							{ &table.content }
+						</pre>
+					</details>
+				}) }
+				if !end.is_empty() {
+					<pre class="mt-0.5rem bg-dark900 p-0.5rem rounded">
+						{ end }
+					</pre>
+				}
+			</div>
+		}
+	}
+
+	fn show_json(&self, json: &str) -> Html {
+		html! {
+			<div>
+				<p class="text-2xl mt-2rem">{ "4. Copy & Paste" }</p>
+				<a class="p-0.5rem btn-white" href={data_url("application/json", json)} download="generated.json">
+					{ "Download" }
+				</a>
 				<pre class="bg-dark900 p-0.5rem rounded">
-					{ sql }
+					{ json }
 				</pre>
 			</div>
 		}
 	}
 
+	fn show_csv_files(&self, files: &[GeneratedTableOutput]) -> Html {
+		html! {
+			<div>
+				<p class="text-2xl mt-2rem">{ "4. Download" }</p>
+				<div class="flex flex-col gap-2">
+					{ for files.iter().map(|file| {
+						let url = data_url("text/csv", &file.content);
+						html! {
+							<a class="p-0.5rem btn-white" href={url} download={file.name.clone()}>
+								{ format!("{} ({} rows)", file.name, file.row_count) }
+							</a>
+						}
+					}) }
+				</div>
+			</div>
+		}
+	}
+
 	fn upload_project(files: Option<FileList>) -> Msg {
 		if let Some(files) = files {
 			let file = js_sys::try_iter(&files)
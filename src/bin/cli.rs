@@ -0,0 +1,234 @@
+use std::{
+	collections::HashMap,
+	fs::{self, File},
+	io::{self, BufReader, Write},
+	process::ExitCode,
+	rc::Rc,
+};
+
+use anyhow::{anyhow, bail, Context, Result};
+
+use magic_sql_gen::{
+	generate_ddl::generate_ddl,
+	generate_json::to_json,
+	generate_sql::{generate_rows, generate_table_guessess, to_sql, GenerateOptions, SQLOptions},
+	generator_config::{apply_config, GeneratorConfig},
+	magicdraw_parser::{parse_project_file, ParseOptions, SQLTableCollection},
+};
+
+const USAGE: &str = "Usage: magic-sql-gen generate <project> [options]
+
+Generates INSERT statements with fake data for a MagicDraw project (.mdzip, .mdxml or .xml)
+
+Options:
+  --rows <count>            Rows in each table, defaults to 20
+  --rows <table>=<count>    Rows in a single table, can be repeated
+  --collection <index|name> Which DDL script to use, when a project has more than one
+  --seed <number>           Makes the output reproducible
+  --config <path>           Generator settings saved from the app, other options override it
+  --create-tables           Start with CREATE TABLE statements
+  --json                    Write rows as JSON instead of SQL
+  --out <path>              Where to write the SQL, defaults to stdout
+  -h, --help                Show this message";
+
+struct GenerateArgs {
+	project: String,
+	collection: Option<String>,
+	out: Option<String>,
+	config: Option<String>,
+	rows_per_table: Option<u32>,
+	table_rows: HashMap<String, u32>,
+	seed: Option<u64>,
+	create_tables: bool,
+	json: bool,
+}
+
+fn next_value(args: &mut impl Iterator<Item = String>, flag: &str) -> Result<String> {
+	args.next()
+		.ok_or_else(|| anyhow!("{} requires a value", flag))
+}
+
+fn parse_generate_args(mut args: impl Iterator<Item = String>) -> Result<GenerateArgs> {
+	let mut project = None;
+	let mut collection = None;
+	let mut out = None;
+	let mut config = None;
+	let mut rows_per_table = None;
+	let mut table_rows = HashMap::new();
+	let mut seed = None;
+	let mut create_tables = false;
+	let mut json = false;
+
+	while let Some(arg) = args.next() {
+		match arg.as_str() {
+			"--rows" => {
+				let value = next_value(&mut args, &arg)?;
+				if let Some((table, count)) = value.split_once('=') {
+					let count = count
+						.parse()
+						.with_context(|| format!("invalid row count for table {}", table))?;
+					table_rows.insert(table.to_string(), count);
+				} else {
+					rows_per_table = Some(value.parse().context("invalid row count")?);
+				}
+			}
+			"--collection" => collection = Some(next_value(&mut args, &arg)?),
+			"--seed" => {
+				seed = Some(
+					next_value(&mut args, &arg)?
+						.parse()
+						.context("invalid seed")?,
+				)
+			}
+			"--create-tables" => create_tables = true,
+			"--json" => json = true,
+			"--config" => config = Some(next_value(&mut args, &arg)?),
+			"--out" | "-o" => out = Some(next_value(&mut args, &arg)?),
+			_ if arg.starts_with('-') => bail!("unknown option {}", arg),
+			_ if project.is_none() => project = Some(arg),
+			_ => bail!("unexpected argument {}", arg),
+		}
+	}
+
+	if create_tables && json {
+		bail!("--create-tables can't be used with --json");
+	}
+
+	Ok(GenerateArgs {
+		project: project.ok_or_else(|| anyhow!("missing project file"))?,
+		collection,
+		out,
+		config,
+		rows_per_table,
+		table_rows,
+		seed,
+		create_tables,
+		json,
+	})
+}
+
+// Collections can be picked by their position, starting from 1, or by the DDL script name
+fn pick_collection(
+	mut collections: Vec<SQLTableCollection>,
+	picked: Option<&str>,
+) -> Result<SQLTableCollection> {
+	let Some(picked) = picked else {
+		if collections.len() > 1 {
+			let names = collections
+				.iter()
+				.enumerate()
+				.map(|(i, collection)| {
+					format!(
+						"{}: {}",
+						i + 1,
+						collection.name.as_deref().unwrap_or("Unnamed")
+					)
+				})
+				.collect::<Vec<_>>();
+			bail!(
+				"project has {} collections, pick one with --collection\n{}",
+				collections.len(),
+				names.join("\n")
+			);
+		}
+		return collections
+			.pop()
+			.ok_or_else(|| anyhow!("project has no tables"));
+	};
+
+	let position = match picked.parse::<usize>() {
+		Ok(number) => number.checked_sub(1).filter(|idx| *idx < collections.len()),
+		Err(_) => collections
+			.iter()
+			.position(|collection| collection.name.as_deref() == Some(picked)),
+	};
+	let idx = position.ok_or_else(|| anyhow!("collection {} not found", picked))?;
+	Ok(collections.swap_remove(idx))
+}
+
+fn generate(args: GenerateArgs) -> Result<()> {
+	let file =
+		File::open(&args.project).with_context(|| format!("failed to open {}", args.project))?;
+	let project = parse_project_file(BufReader::new(file), &ParseOptions::default())?;
+	for warning in &project.warnings {
+		eprintln!("warning: {}", warning);
+	}
+
+	let collection = pick_collection(project.collections, args.collection.as_deref())?;
+	let tables = collection
+		.tables
+		.into_iter()
+		.map(Rc::new)
+		.collect::<Vec<_>>();
+	let mut guessess = tables
+		.iter()
+		.map(|table| generate_table_guessess(table))
+		.collect::<Vec<_>>();
+
+	let mut options = GenerateOptions::default();
+	if let Some(path) = &args.config {
+		let json = fs::read_to_string(path).with_context(|| format!("failed to read {}", path))?;
+		let config = GeneratorConfig::from_json(&json)
+			.with_context(|| format!("invalid config {}", path))?;
+		let mut guessess = guessess.iter_mut().collect::<Vec<_>>();
+		for warning in apply_config(&config, &tables, &mut guessess, &mut options) {
+			eprintln!("warning: {}", warning);
+		}
+	}
+	if let Some(rows_per_table) = args.rows_per_table {
+		options.rows_per_table = rows_per_table;
+	}
+	options.table_rows.extend(args.table_rows);
+	if args.seed.is_some() {
+		options.seed = args.seed;
+	}
+
+	let sql_options = SQLOptions::default();
+
+	let guessess = guessess.iter().collect::<Vec<_>>();
+	let generated = generate_rows(&tables, &guessess, &options)?;
+	for warning in &generated.warnings {
+		eprintln!("warning: {}", warning);
+	}
+	if options.seed.is_none() {
+		eprintln!("seed: {}", generated.seed);
+	}
+	let mut output = if args.json {
+		to_json(&generated)
+	} else {
+		to_sql(&generated, &sql_options)
+	};
+	if args.create_tables {
+		output = format!("{}\n{}", generate_ddl(&tables, &sql_options), output);
+	}
+
+	match &args.out {
+		Some(path) => {
+			fs::write(path, output).with_context(|| format!("failed to write {}", path))?
+		}
+		None => io::stdout().write_all(output.as_bytes())?,
+	}
+	Ok(())
+}
+
+fn main() -> ExitCode {
+	let mut args = std::env::args().skip(1);
+	let result = match args.next().as_deref() {
+		Some("generate") => parse_generate_args(args).and_then(generate),
+		Some("-h" | "--help") => {
+			println!("{}", USAGE);
+			return ExitCode::SUCCESS;
+		}
+		Some(command) => Err(anyhow!("unknown command {}", command)),
+		None => Err(anyhow!("missing command")),
+	};
+
+	match result {
+		Ok(()) => ExitCode::SUCCESS,
+		Err(e) => {
+			eprintln!("error: {:#}", e);
+			eprintln!("Run with --help for usage");
+			ExitCode::FAILURE
+		}
+	}
+}
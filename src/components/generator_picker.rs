@@ -1,15 +1,21 @@
-use std::str::FromStr;
+use std::{mem, str::FromStr};
 
-use web_sys::{Event, HtmlInputElement};
+use chrono::{Datelike, Local, NaiveDate, NaiveDateTime, NaiveTime};
+use web_sys::{Event, HtmlInputElement, HtmlTextAreaElement};
 use yew::{html, AttrValue, Callback, Html, TargetCast};
 
-use crate::{
+use magic_sql_gen::{
 	generate_sql::{
-		SQLBoolValueGuess, SQLIntValueGuess, SQLStringValueGuess, SQLTimeValueGuess, SQLValueGuess,
+		check_constraint_range, check_time_range, constraint_range, find_name_columns,
+		generate_value_guess, parse_fixed_value, parse_value_list, SQLBoolValueGuess, SQLChecksum,
+		SQLDistribution, SQLForeignKeyValueGuess, SQLIntValueGuess, SQLLoremValueGuess,
+		SQLStringValueGuess, SQLTimeValueGuess, SQLValueGuess, IBAN_COUNTRIES, IPV6_MAX_LENGTH,
 	},
-	magicdraw_parser::{SQLCheckConstraint, SQLColumn},
+	magicdraw_parser::{SQLCheckConstraint, SQLColumn, SQLTable, SQLType},
 };
 
+const DEFAULT_PATTERN: &str = "???-####";
+
 fn show_dropdown_picker(selected: &str, options: &[AttrValue], onchange: Callback<String>) -> Html {
 	html! {
 		<select onchange={onchange.reform(move |e: Event| {
@@ -25,12 +31,15 @@ fn show_dropdown_picker(selected: &str, options: &[AttrValue], onchange: Callbac
 	}
 }
 
-fn show_enum_dropdown< T: PartialEq + Clone + 'static>(
+fn show_enum_dropdown<T: PartialEq + Clone + 'static>(
 	selected: &T,
 	options: &Vec<(AttrValue, T)>,
 	onchange: Callback<T>,
 ) -> Html {
-	let keys = options.iter().map(|(opt, _)| opt.clone()).collect::<Vec<_>>();
+	let keys = options
+		.iter()
+		.map(|(opt, _)| opt.clone())
+		.collect::<Vec<_>>();
 	let guess_str = options
 		.iter()
 		.find(|(_, v)| v.eq(&selected))
@@ -43,12 +52,9 @@ fn show_enum_dropdown< T: PartialEq + Clone + 'static>(
 		&guess_str,
 		&keys,
 		onchange.reform(move |value_str: String| {
-			let enum_value = &options.iter()
-				.find(|(v, _)| v.eq(&value_str))
-				.unwrap()
-				.1;
+			let enum_value = &options.iter().find(|(v, _)| v.eq(&value_str)).unwrap().1;
 			enum_value.clone()
-		})
+		}),
 	)
 }
 
@@ -102,80 +108,763 @@ fn show_range_picker<T: FromStr + ToString + Clone + 'static>(
 	}
 }
 
+fn show_text_picker(value: &str, placeholder: &str, onchange: Callback<String>) -> Html {
+	html! {
+		<input
+			value={value.to_string()}
+			class="ml-1"
+			type="text"
+			placeholder={placeholder.to_string()}
+			onchange={onchange.reform(|e: Event| e.target_unchecked_into::<HtmlInputElement>().value())}
+		/>
+	}
+}
+
+fn show_distribution_picker(
+	distribution: &SQLDistribution,
+	min: f64,
+	max: f64,
+	onchange: Callback<SQLDistribution>,
+) -> Html {
+	// A normal distribution starts out centered, with nearly all values inside of the range
+	let options = vec![("Uniform".into(), false), ("Normal".into(), true)];
+	let is_normal = matches!(distribution, SQLDistribution::Normal { .. });
+	let mode_picker = show_enum_dropdown(
+		&is_normal,
+		&options,
+		onchange.reform(move |is_normal| {
+			if is_normal {
+				SQLDistribution::Normal {
+					mean: (min + max) / 2.0,
+					stddev: (max - min).abs() / 6.0,
+				}
+			} else {
+				SQLDistribution::Uniform
+			}
+		}),
+	);
+
+	let SQLDistribution::Normal { mean, stddev } = distribution else {
+		return html! { <div class="ml-1">{ mode_picker }</div> };
+	};
+	let (mean, stddev) = (*mean, *stddev);
+	let on_mean_change = onchange.reform(move |e: Event| {
+		let value = e.target_unchecked_into::<HtmlInputElement>().value();
+		SQLDistribution::Normal {
+			mean: value.parse().unwrap_or(mean),
+			stddev,
+		}
+	});
+	let on_stddev_change = onchange.reform(move |e: Event| {
+		let value = e.target_unchecked_into::<HtmlInputElement>().value();
+		SQLDistribution::Normal {
+			mean,
+			stddev: value.parse().unwrap_or(stddev),
+		}
+	});
+
+	html! {
+		<div class="flex flex-row ml-1">
+			{ mode_picker }
+			<input
+				value={mean.to_string()}
+				class="w-5rem ml-1"
+				type="number"
+				title="Mean"
+				onchange={on_mean_change}
+			/>
+			<div class="ml-1 mr-1">{ "±" }</div>
+			<input
+				value={stddev.to_string()}
+				class="w-5rem"
+				type="number"
+				min="0"
+				title="Standard deviation"
+				onchange={on_stddev_change}
+			/>
+		</div>
+	}
+}
+
+fn show_null_probability_picker(null_probability: f32, onchange: Callback<f32>) -> Html {
+	let onchange = onchange.reform(move |e: Event| {
+		let value = e.target_unchecked_into::<HtmlInputElement>().value();
+		value
+			.parse::<f32>()
+			.map(|percent| (percent / 100.0).clamp(0.0, 1.0))
+			.unwrap_or(null_probability)
+	});
+
+	html! {
+		<div class="flex flex-row ml-1" title="Chance of NULL">
+			<input
+				value={(null_probability * 100.0).to_string()}
+				class="w-3rem"
+				type="number"
+				min="0"
+				max="100"
+				onchange={onchange}
+			/>
+			<div class="ml-1">{ "% NULL" }</div>
+		</div>
+	}
+}
+
+fn show_enum_weights_picker(
+	options: &[(String, f32)],
+	onchange: Callback<Vec<(String, f32)>>,
+) -> Html {
+	let variants = options
+		.iter()
+		.enumerate()
+		.map(|(i, (variant, weight))| {
+			let onchange = {
+				let options = options.to_vec();
+				onchange.reform(move |e: Event| {
+					let value = e.target_unchecked_into::<HtmlInputElement>().value();
+					let mut options = options.clone();
+					if let Ok(weight) = value.parse::<f32>() {
+						options[i].1 = weight.max(0.0);
+					}
+					options
+				})
+			};
+
+			html! {
+				<div class="flex flex-row mr-1" title="Weight of the variant">
+					<div class="mr-1">{ variant }</div>
+					<input
+						value={weight.to_string()}
+						class="w-3rem"
+						type="number"
+						min="0"
+						onchange={onchange}
+					/>
+				</div>
+			}
+		})
+		.collect::<Html>();
+
+	html! {
+		<div class="flex flex-row flex-wrap">{ variants }</div>
+	}
+}
+
+/// Nullable columns also get an input for how many of their values are NULL
 pub fn generator_picker(
+	table: &SQLTable,
 	column: &SQLColumn,
 	value: &SQLValueGuess,
 	onchange: Callback<SQLValueGuess>,
 ) -> Html {
-	// TODO: Refacotr 'time', 'datetime', and 'date'. They are very similar
-	match value {
-		SQLValueGuess::Int(guess) => {
-			if column.primary_key {
-				return html!("Auto increment");
-			}
+	if !column.nullable || column.primary_key {
+		return value_picker(table, column, value, onchange);
+	}
 
-			let mut min = 0;
-			let mut max = 0;
-			if let SQLIntValueGuess::Range(range_min, range_max) = guess {
-				min = *range_min;
-				max = *range_max;
-			}
+	let (null_probability, guess) = match value {
+		SQLValueGuess::Nullable(null_probability, guess) => (*null_probability, guess.as_ref()),
+		guess => (0.0, guess),
+	};
+	let on_guess_change =
+		onchange.reform(move |guess| SQLValueGuess::Nullable(null_probability, Box::new(guess)));
+	let on_null_probability_change = {
+		let guess = guess.clone();
+		onchange.reform(move |null_probability| {
+			SQLValueGuess::Nullable(null_probability, Box::new(guess.clone()))
+		})
+	};
 
-			// TODO: Disallow entering floating point numbers
-			show_range_picker(
-				min,
-				max,
-				0,
-				100,
-				onchange.reform(|(min, max)| SQLValueGuess::Int(SQLIntValueGuess::Range(min, max))),
-			)
-		}
-		SQLValueGuess::Float(min, max) => show_range_picker(
+	html! {
+		<div class="flex flex-row">
+			{ value_picker(table, column, guess, on_guess_change) }
+			{ show_null_probability_picker(null_probability, on_null_probability_change) }
+		</div>
+	}
+}
+
+fn show_lorem_picker(guess: &SQLLoremValueGuess, onchange: Callback<SQLLoremValueGuess>) -> Html {
+	let options = vec![
+		("Words".into(), SQLLoremValueGuess::default()),
+		("Sentences".into(), SQLLoremValueGuess::Sentences(5, 20)),
+		("Paragraph".into(), SQLLoremValueGuess::Paragraph),
+	];
+	let selected = options
+		.iter()
+		.map(|(_, option)| option)
+		.find(|option| mem::discriminant(*option) == mem::discriminant(guess))
+		.unwrap_or(guess);
+	let mode_picker = show_enum_dropdown(selected, &options, onchange.clone());
+
+	let word_count_picker = match guess {
+		SQLLoremValueGuess::Words(min, max) => show_range_picker(
 			*min,
 			*max,
-			0.0,
-			100.0,
-			onchange.reform(|(min, max)| SQLValueGuess::Float(min, max)),
+			3,
+			10,
+			onchange.reform(|(min, max)| SQLLoremValueGuess::Words(min, max)),
 		),
-		SQLValueGuess::Date(guess) => {
-			let options = vec![
-				("Now".into(), SQLTimeValueGuess::Now),
-				("Future".into(), SQLTimeValueGuess::Future),
-				("Past".into(), SQLTimeValueGuess::Past),
-			];
+		SQLLoremValueGuess::Sentences(min, max) => show_range_picker(
+			*min,
+			*max,
+			5,
+			20,
+			onchange.reform(|(min, max)| SQLLoremValueGuess::Sentences(min, max)),
+		),
+		SQLLoremValueGuess::Paragraph => return mode_picker,
+	};
 
-			show_enum_dropdown(
-				guess,
-				&options,
-				onchange.reform(|enum_value| SQLValueGuess::Date(enum_value)),
-			)
+	html! {
+		<div class="flex flex-row" title="Amount of words">
+			{ mode_picker }
+			<div class="ml-1">{ word_count_picker }</div>
+		</div>
+	}
+}
+
+// Values given by the user replace the generator of any column type
+#[derive(PartialEq, Clone)]
+enum GuessMode {
+	Generated,
+	Fixed,
+	FromList,
+}
+
+fn value_picker(
+	table: &SQLTable,
+	column: &SQLColumn,
+	value: &SQLValueGuess,
+	onchange: Callback<SQLValueGuess>,
+) -> Html {
+	let options = vec![
+		("Generated".into(), GuessMode::Generated),
+		("Fixed value".into(), GuessMode::Fixed),
+		("From list".into(), GuessMode::FromList),
+	];
+	let mode = match value {
+		SQLValueGuess::Fixed(_) => GuessMode::Fixed,
+		SQLValueGuess::FromList(_) => GuessMode::FromList,
+		_ => GuessMode::Generated,
+	};
+	let default_guess = match column.foreign_key {
+		Some(_) => SQLValueGuess::ForeignKey(SQLForeignKeyValueGuess::Uniform),
+		None => generate_value_guess(column),
+	};
+	let mode_picker = show_enum_dropdown(
+		&mode,
+		&options,
+		onchange.reform(move |mode| match mode {
+			GuessMode::Generated => default_guess.clone(),
+			GuessMode::Fixed => SQLValueGuess::Fixed(String::new()),
+			GuessMode::FromList => SQLValueGuess::FromList(vec![]),
+		}),
+	);
+
+	html! {
+		<div class="flex flex-row">
+			{ mode_picker }
+			<div class="ml-1">{ guess_picker(table, column, value, onchange) }</div>
+		</div>
+	}
+}
+
+// Ranges derived from a check constraint say so, and ranges edited past it are warned about
+fn show_constraint_hint(column: &SQLColumn, guess: &SQLValueGuess) -> Html {
+	let Some((lower, upper)) = constraint_range(column) else {
+		return html! {};
+	};
+	if lower.is_none() && upper.is_none() {
+		return html! {};
+	}
+	match check_constraint_range(column, guess) {
+		Some(problem) => html! {
+			<div class="ml-1 text-amber300">{ format!("⚠️ The {}", problem) }</div>
+		},
+		None => html! {
+			<div class="ml-1">{ format!("From check {}", SQLCheckConstraint::Range(lower, upper)) }</div>
+		},
+	}
+}
+
+// Start and end inputs of a custom range, values are in the format of the `input_type`
+fn show_time_range_inputs(
+	start: String,
+	end: String,
+	input_type: &'static str,
+	onchange: Callback<(String, String)>,
+) -> Html {
+	let on_start_change = {
+		let (onchange, end) = (onchange.clone(), end.clone());
+		Callback::from(move |e: Event| {
+			let value = e.target_unchecked_into::<HtmlInputElement>().value();
+			onchange.emit((value, end.clone()))
+		})
+	};
+	let on_end_change = {
+		let start = start.clone();
+		Callback::from(move |e: Event| {
+			let value = e.target_unchecked_into::<HtmlInputElement>().value();
+			onchange.emit((start.clone(), value))
+		})
+	};
+
+	html! {
+		<div class="flex flex-row">
+			<input type={input_type} value={start} onchange={on_start_change} />
+			<div class="ml-1 mr-1">{ ".." }</div>
+			<input type={input_type} value={end} onchange={on_end_change} />
+		</div>
+	}
+}
+
+fn show_time_range(kind_picker: Html, range_inputs: Html, guess: &SQLTimeValueGuess) -> Html {
+	html! {
+		<div class="flex flex-row">
+			{ kind_picker }
+			<div class="ml-1">{ range_inputs }</div>
+			if let Some(problem) = check_time_range(guess) {
+				<div class="ml-1 text-red400">{ format!("⚠️ The {}", problem) }</div>
+			}
+		</div>
+	}
+}
+
+// Shared by dates, times and datetimes
+fn show_time_picker(
+	table: &SQLTable,
+	column: &SQLColumn,
+	guess: &SQLTimeValueGuess,
+	onchange: Callback<SQLTimeValueGuess>,
+) -> Html {
+	// Only dates can follow each other, times alone don't say which one is later
+	let earlier_columns = table
+		.columns
+		.iter()
+		.filter(|other| other.name != column.name)
+		.filter(|other| matches!(other.sql_type, SQLType::Date | SQLType::Datetime))
+		.map(|other| AttrValue::from(other.name.clone()))
+		.collect::<Vec<_>>();
+
+	let year = Local::now().year();
+	let mut options = vec![
+		("Now".into(), SQLTimeValueGuess::Now),
+		("Future".into(), SQLTimeValueGuess::future()),
+		("Past".into(), SQLTimeValueGuess::past()),
+		(
+			"Years ago".into(),
+			SQLTimeValueGuess::YearsAgo { min: 18, max: 80 },
+		),
+		(
+			"Custom range".into(),
+			SQLTimeValueGuess::Between(
+				NaiveDate::from_ymd_opt(year, 1, 1).unwrap(),
+				NaiveDate::from_ymd_opt(year, 12, 31).unwrap(),
+			),
+		),
+	];
+	let after_option = match guess {
+		SQLTimeValueGuess::After { .. } => Some(guess.clone()),
+		_ => earlier_columns
+			.first()
+			.filter(|_| column.sql_type != SQLType::Time)
+			.map(|earlier_column| SQLTimeValueGuess::After {
+				column: earlier_column.to_string(),
+				min_days: 0,
+				max_days: 30,
+			}),
+	};
+	if let Some(after_option) = after_option {
+		options.push(("After column".into(), after_option));
+	}
+	if column.sql_type == SQLType::Time || matches!(guess, SQLTimeValueGuess::TimeWindow { .. }) {
+		options.push((
+			"Time window".into(),
+			SQLTimeValueGuess::TimeWindow {
+				from: NaiveTime::from_hms_opt(8, 0, 0).unwrap(),
+				to: NaiveTime::from_hms_opt(17, 0, 0).unwrap(),
+				step_minutes: Some(15),
+			},
+		));
+	}
+
+	// Variants with settings are matched by kind, their settings are shown separately.
+	// Ranges with times are only set in configs, they are edited like the ones without.
+	let selected = match guess {
+		SQLTimeValueGuess::BetweenDatetimes(..) => &options[4].1,
+		_ => options
+			.iter()
+			.map(|(_, option)| option)
+			.find(|option| mem::discriminant(*option) == mem::discriminant(guess))
+			.unwrap_or(guess),
+	};
+	let kind_picker = show_enum_dropdown(selected, &options, onchange.clone());
+
+	match guess {
+		SQLTimeValueGuess::After {
+			column,
+			min_days,
+			max_days,
+		} => {
+			let (min_days, max_days) = (*min_days, *max_days);
+			let on_column_change = onchange.reform(move |column| SQLTimeValueGuess::After {
+				column,
+				min_days,
+				max_days,
+			});
+			let on_days_change = {
+				let column = column.clone();
+				onchange.reform(move |(min_days, max_days)| SQLTimeValueGuess::After {
+					column: column.clone(),
+					min_days,
+					max_days,
+				})
+			};
+			html! {
+				<div class="flex flex-row">
+					{ kind_picker }
+					<div class="ml-1">
+						{ show_range_picker(min_days, max_days, 0, 30, on_days_change) }
+					</div>
+					<div class="ml-1 mr-1">{ "days after" }</div>
+					{ show_dropdown_picker(column, &earlier_columns, on_column_change) }
+				</div>
+			}
 		}
-		SQLValueGuess::Time(guess) => {
-			let options = vec![
-				("Now".into(), SQLTimeValueGuess::Now),
-				("Future".into(), SQLTimeValueGuess::Future),
-				("Past".into(), SQLTimeValueGuess::Past),
-			];
+		SQLTimeValueGuess::TimeWindow {
+			from,
+			to,
+			step_minutes,
+		} => {
+			let (from, to, step_minutes) = (*from, *to, *step_minutes);
+			// Browsers leave out the seconds when they are zero
+			let parse = |value: &str| {
+				NaiveTime::parse_from_str(value, "%H:%M:%S")
+					.or_else(|_| NaiveTime::parse_from_str(value, "%H:%M"))
+					.ok()
+			};
+			let range_inputs = show_time_range_inputs(
+				from.format("%H:%M").to_string(),
+				to.format("%H:%M").to_string(),
+				"time",
+				onchange.reform(move |(new_from, new_to): (String, String)| {
+					SQLTimeValueGuess::TimeWindow {
+						from: parse(&new_from).unwrap_or(from),
+						to: parse(&new_to).unwrap_or(to),
+						step_minutes,
+					}
+				}),
+			);
+			let on_step_change = onchange.reform(move |e: Event| {
+				let value = e.target_unchecked_into::<HtmlInputElement>().value();
+				SQLTimeValueGuess::TimeWindow {
+					from,
+					to,
+					step_minutes: value.parse().ok().filter(|step| *step > 0),
+				}
+			});
+			html! {
+				<div class="flex flex-row">
+					{ kind_picker }
+					<div class="ml-1">{ range_inputs }</div>
+					<input
+						value={step_minutes.map(|step| step.to_string()).unwrap_or_default()}
+						class="w-3rem ml-1"
+						type="number"
+						min="1"
+						max="255"
+						placeholder="any"
+						title="Step in minutes"
+						onchange={on_step_change}
+					/>
+					<div class="ml-1">{ "minute steps" }</div>
+				</div>
+			}
+		}
+		SQLTimeValueGuess::Future { min_days, max_days } => html! {
+			<div class="flex flex-row">
+				{ kind_picker }
+				<div class="ml-1">
+					{ show_range_picker(
+						*min_days,
+						*max_days,
+						1,
+						30,
+						onchange.reform(|(min_days, max_days)| SQLTimeValueGuess::Future { min_days, max_days }),
+					) }
+				</div>
+				<div class="ml-1">{ "days ahead" }</div>
+			</div>
+		},
+		SQLTimeValueGuess::Past { min_days, max_days } => html! {
+			<div class="flex flex-row">
+				{ kind_picker }
+				<div class="ml-1">
+					{ show_range_picker(
+						*min_days,
+						*max_days,
+						7,
+						365,
+						onchange.reform(|(min_days, max_days)| SQLTimeValueGuess::Past { min_days, max_days }),
+					) }
+				</div>
+				<div class="ml-1">{ "days ago" }</div>
+			</div>
+		},
+		SQLTimeValueGuess::YearsAgo { min, max } => html! {
+			<div class="flex flex-row">
+				{ kind_picker }
+				<div class="ml-1">
+					{ show_range_picker(
+						*min,
+						*max,
+						18,
+						80,
+						onchange.reform(|(min, max)| SQLTimeValueGuess::YearsAgo { min, max }),
+					) }
+				</div>
+				<div class="ml-1">{ "years ago" }</div>
+			</div>
+		},
+		SQLTimeValueGuess::Between(start, end) => {
+			let (start, end) = (*start, *end);
+			let parse = |value: &str| NaiveDate::parse_from_str(value, "%Y-%m-%d").ok();
+			let range_inputs = show_time_range_inputs(
+				start.format("%Y-%m-%d").to_string(),
+				end.format("%Y-%m-%d").to_string(),
+				"date",
+				onchange.reform(move |(new_start, new_end): (String, String)| {
+					SQLTimeValueGuess::Between(
+						parse(&new_start).unwrap_or(start),
+						parse(&new_end).unwrap_or(end),
+					)
+				}),
+			);
+			show_time_range(kind_picker, range_inputs, guess)
+		}
+		SQLTimeValueGuess::BetweenDatetimes(start, end) => {
+			let (start, end) = (*start, *end);
+			// Browsers leave out the seconds when they are zero
+			let parse = |value: &str| {
+				NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S")
+					.or_else(|_| NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M"))
+					.ok()
+			};
+			let range_inputs = show_time_range_inputs(
+				start.format("%Y-%m-%dT%H:%M:%S").to_string(),
+				end.format("%Y-%m-%dT%H:%M:%S").to_string(),
+				"datetime-local",
+				onchange.reform(move |(new_start, new_end): (String, String)| {
+					SQLTimeValueGuess::BetweenDatetimes(
+						parse(&new_start).unwrap_or(start),
+						parse(&new_end).unwrap_or(end),
+					)
+				}),
+			);
+			show_time_range(kind_picker, range_inputs, guess)
+		}
+		_ => kind_picker,
+	}
+}
 
-			show_enum_dropdown(
-				guess,
+fn guess_picker(
+	table: &SQLTable,
+	column: &SQLColumn,
+	value: &SQLValueGuess,
+	onchange: Callback<SQLValueGuess>,
+) -> Html {
+	match value {
+		SQLValueGuess::Int(guess) => {
+			let options = vec![("Range".into(), false), ("Auto increment".into(), true)];
+			let is_auto_increment = matches!(guess, SQLIntValueGuess::AutoIncrement { .. });
+			let mode_picker = show_enum_dropdown(
+				&is_auto_increment,
 				&options,
-				onchange.reform(|enum_value| SQLValueGuess::Time(enum_value)),
-			)
+				onchange.reform(move |auto_increment| {
+					if auto_increment {
+						SQLValueGuess::Int(SQLIntValueGuess::auto_increment())
+					} else {
+						SQLValueGuess::Int(SQLIntValueGuess::range(0, 100))
+					}
+				}),
+			);
+
+			let (min, max, step, distribution) = match guess {
+				SQLIntValueGuess::Range {
+					min,
+					max,
+					step,
+					distribution,
+				} => (min, max, step, distribution),
+				SQLIntValueGuess::AutoIncrement {
+					start,
+					step,
+					omit_from_insert,
+				} => {
+					let (start, step, omit_from_insert) = (*start, *step, *omit_from_insert);
+					let on_start_change = onchange.reform(move |e: Event| {
+						let value = e.target_unchecked_into::<HtmlInputElement>().value();
+						SQLValueGuess::Int(SQLIntValueGuess::AutoIncrement {
+							start: value.parse().unwrap_or(start),
+							step,
+							omit_from_insert,
+						})
+					});
+					let on_step_change = onchange.reform(move |e: Event| {
+						let value = e.target_unchecked_into::<HtmlInputElement>().value();
+						SQLValueGuess::Int(SQLIntValueGuess::AutoIncrement {
+							start,
+							step: value.parse().ok().filter(|step| *step > 0).unwrap_or(step),
+							omit_from_insert,
+						})
+					});
+					let on_omit_change = onchange.reform(move |e: Event| {
+						let checked = e.target_unchecked_into::<HtmlInputElement>().checked();
+						SQLValueGuess::Int(SQLIntValueGuess::AutoIncrement {
+							start,
+							step,
+							omit_from_insert: checked,
+						})
+					});
+					return html! {
+						<div class="flex flex-row">
+							{ mode_picker }
+							<input
+								value={start.to_string()}
+								class="w-5rem ml-1"
+								type="number"
+								min="0"
+								title="Start"
+								onchange={on_start_change}
+							/>
+							<input
+								value={step.to_string()}
+								class="w-3rem ml-1"
+								type="number"
+								min="1"
+								title="Step"
+								onchange={on_step_change}
+							/>
+							<label class="ml-1" title="Let the database assign the values">
+								<input
+									type="checkbox"
+									checked={omit_from_insert}
+									onchange={on_omit_change}
+								/>
+								{ " Omit from INSERT" }
+							</label>
+						</div>
+					};
+				}
+			};
+
+			let (min, max, step) = (*min, *max, *step);
+			let on_step_change = {
+				let distribution = distribution.clone();
+				onchange.reform(move |e: Event| {
+					let value = e.target_unchecked_into::<HtmlInputElement>().value();
+					SQLValueGuess::Int(SQLIntValueGuess::Range {
+						min,
+						max,
+						step: value.parse().ok().filter(|step| *step > 1),
+						distribution: distribution.clone(),
+					})
+				})
+			};
+			let on_range_change = {
+				let distribution = distribution.clone();
+				onchange.reform(move |(min, max)| {
+					SQLValueGuess::Int(SQLIntValueGuess::Range {
+						min,
+						max,
+						step,
+						distribution: distribution.clone(),
+					})
+				})
+			};
+
+			// TODO: Disallow entering floating point numbers
+			html! {
+				<div class="flex flex-row">
+					{ mode_picker }
+					{ show_range_picker(min, max, 0, 100, on_range_change) }
+					<input
+						value={step.map(|step| step.to_string()).unwrap_or_default()}
+						class="w-3rem ml-1"
+						type="number"
+						min="1"
+						placeholder="1"
+						title="Step"
+						onchange={on_step_change}
+					/>
+					{ show_distribution_picker(
+						distribution,
+						min as f64,
+						max as f64,
+						onchange.reform(move |distribution| SQLValueGuess::Int(SQLIntValueGuess::Range {
+							min,
+							max,
+							step,
+							distribution,
+						})),
+					) }
+					{ show_constraint_hint(column, value) }
+				</div>
+			}
 		}
-		SQLValueGuess::Datetime(guess) => {
-			let options = vec![
-				("Now".into(), SQLTimeValueGuess::Now),
-				("Future".into(), SQLTimeValueGuess::Future),
-				("Past".into(), SQLTimeValueGuess::Past),
-			];
+		SQLValueGuess::Float(min, max, decimals, distribution) => {
+			let (min, max, decimals) = (*min, *max, *decimals);
+			let on_decimals_change = {
+				let distribution = distribution.clone();
+				onchange.reform(move |e: Event| {
+					let value = e.target_unchecked_into::<HtmlInputElement>().value();
+					SQLValueGuess::Float(
+						min,
+						max,
+						value.parse().unwrap_or(decimals),
+						distribution.clone(),
+					)
+				})
+			};
+			let on_range_change = {
+				let distribution = distribution.clone();
+				onchange.reform(move |(min, max)| {
+					SQLValueGuess::Float(min, max, decimals, distribution.clone())
+				})
+			};
 
-			show_enum_dropdown(
-				guess,
-				&options,
-				onchange.reform(|enum_value| SQLValueGuess::Datetime(enum_value)),
-			)
+			html! {
+				<div class="flex flex-row">
+					{ show_range_picker(min, max, 0.0, 100.0, on_range_change) }
+					<input
+						value={decimals.to_string()}
+						class="w-3rem ml-1"
+						type="number"
+						min="0"
+						max="10"
+						title="Decimal places"
+						onchange={on_decimals_change}
+					/>
+					{ show_distribution_picker(
+						distribution,
+						min as f64,
+						max as f64,
+						onchange.reform(move |distribution| SQLValueGuess::Float(min, max, decimals, distribution)),
+					) }
+					{ show_constraint_hint(column, value) }
+				</div>
+			}
 		}
+		SQLValueGuess::Date(guess) => {
+			show_time_picker(table, column, guess, onchange.reform(SQLValueGuess::Date))
+		}
+		SQLValueGuess::Time(guess) => {
+			show_time_picker(table, column, guess, onchange.reform(SQLValueGuess::Time))
+		}
+		SQLValueGuess::Datetime(guess) => show_time_picker(
+			table,
+			column,
+			guess,
+			onchange.reform(SQLValueGuess::Datetime),
+		),
 		SQLValueGuess::Bool(guess) => {
 			let options = vec![
 				("Random".into(), SQLBoolValueGuess::Random),
@@ -189,32 +878,358 @@ pub fn generator_picker(
 				onchange.reform(|enum_value| SQLValueGuess::Bool(enum_value)),
 			)
 		}
+		SQLValueGuess::ForeignKey(guess) => {
+			let options = vec![
+				("Uniform".into(), SQLForeignKeyValueGuess::Uniform),
+				("One to one".into(), SQLForeignKeyValueGuess::OneToOne),
+				("Skewed".into(), SQLForeignKeyValueGuess::Skewed),
+				("Cover all".into(), SQLForeignKeyValueGuess::CoverAll),
+			];
+
+			show_enum_dropdown(guess, &options, onchange.reform(SQLValueGuess::ForeignKey))
+		}
+		SQLValueGuess::Nullable(null_probability, guess) => {
+			let null_probability = *null_probability;
+			guess_picker(
+				table,
+				column,
+				guess,
+				onchange.reform(move |guess| {
+					SQLValueGuess::Nullable(null_probability, Box::new(guess))
+				}),
+			)
+		}
+		SQLValueGuess::Fixed(value) => {
+			let problem = match parse_fixed_value(column, value) {
+				Err(problem) if !value.is_empty() => Some(problem),
+				_ => None,
+			};
+			html! {
+				<div class="flex flex-row">
+					{ show_text_picker(value, "value", onchange.reform(SQLValueGuess::Fixed)) }
+					if let Some(problem) = problem {
+						<div class="ml-1 text-amber300">{ format!("⚠️ {}", problem) }</div>
+					}
+				</div>
+			}
+		}
+		SQLValueGuess::FromList(values) => {
+			let problem = values
+				.iter()
+				.find_map(|value| parse_fixed_value(column, value).err());
+			let onchange = onchange.reform(|e: Event| {
+				let text = e.target_unchecked_into::<HtmlTextAreaElement>().value();
+				SQLValueGuess::FromList(parse_value_list(&text))
+			});
+			html! {
+				<div class="flex flex-row">
+					<textarea
+						value={values.join("\n")}
+						rows="3"
+						placeholder="One value per line, or separated by commas"
+						onchange={onchange}
+					/>
+					if let Some(problem) = problem {
+						<div class="ml-1 text-amber300">{ format!("⚠️ {}", problem) }</div>
+					}
+				</div>
+			}
+		}
 		SQLValueGuess::String(max_size, guess) => {
-			if let Some(constraint) = &column.check_constraint {
-				if let SQLCheckConstraint::OneOf(_) = constraint {
-					return html!("Random Enum");
-				}
+			if let SQLStringValueGuess::RandomEnum(options) = guess {
+				let max_size = *max_size;
+				return show_enum_weights_picker(
+					options,
+					onchange.reform(move |options| {
+						SQLValueGuess::String(max_size, SQLStringValueGuess::RandomEnum(options))
+					}),
+				);
 			}
 
-			let options = vec![
-				("Lorem Ipsum".into(), SQLStringValueGuess::LoremIpsum),
+			let mut options = vec![
+				(
+					"Lorem Ipsum".into(),
+					SQLStringValueGuess::LoremIpsum(SQLLoremValueGuess::default()),
+				),
 				("Empty".into(), SQLStringValueGuess::Empty),
 				("First Name".into(), SQLStringValueGuess::FirstName),
 				("Last Name".into(), SQLStringValueGuess::LastName),
 				("Full Name".into(), SQLStringValueGuess::FullName),
 				("Phone number".into(), SQLStringValueGuess::PhoneNumber),
 				("City name".into(), SQLStringValueGuess::CityName),
+				("Street name".into(), SQLStringValueGuess::StreetName),
 				("Address".into(), SQLStringValueGuess::Address),
+				("Full address".into(), SQLStringValueGuess::FullAddress),
+				("Country".into(), SQLStringValueGuess::CountryName),
+				("Country code".into(), SQLStringValueGuess::CountryCode),
+				("Postal code".into(), SQLStringValueGuess::PostalCode),
+				("Company name".into(), SQLStringValueGuess::CompanyName),
+				("Job title".into(), SQLStringValueGuess::JobTitle),
+				("Username".into(), SQLStringValueGuess::Username),
+				("Password hash".into(), SQLStringValueGuess::PasswordHash),
+				(
+					"Credit card number".into(),
+					SQLStringValueGuess::CreditCardNumber,
+				),
+				(
+					"National ID".into(),
+					SQLStringValueGuess::NationalId {
+						digits: 11,
+						checksum: None,
+					},
+				),
+				("IBAN".into(), SQLStringValueGuess::Iban(None)),
+				("BIC".into(), SQLStringValueGuess::Bic),
+				("Currency code".into(), SQLStringValueGuess::CurrencyCode),
+				("IPv4 address".into(), SQLStringValueGuess::IPv4),
+				("IPv6 address".into(), SQLStringValueGuess::IPv6),
+				("MAC address".into(), SQLStringValueGuess::MacAddress),
+				("Hostname".into(), SQLStringValueGuess::Hostname),
+				("Color hex".into(), SQLStringValueGuess::ColorHex),
+				("Color name".into(), SQLStringValueGuess::ColorName),
+				("File name".into(), SQLStringValueGuess::FileName),
+				("File path".into(), SQLStringValueGuess::FilePath),
 				("Email".into(), SQLStringValueGuess::Email),
 				("URL".into(), SQLStringValueGuess::URL),
+				(
+					"Pattern".into(),
+					SQLStringValueGuess::Pattern(DEFAULT_PATTERN.into()),
+				),
+				(
+					"Sequential".into(),
+					SQLStringValueGuess::Sequential {
+						prefix: "".into(),
+						start: 1,
+						pad: 4,
+					},
+				),
 			];
+			if find_name_columns(table) != (None, None)
+				|| *guess == SQLStringValueGuess::EmailFromName
+			{
+				options.push(("Email from name".into(), SQLStringValueGuess::EmailFromName));
+			}
+
+			let string_columns = table
+				.columns
+				.iter()
+				.filter(|other| other.name != column.name)
+				.filter(|other| {
+					matches!(
+						other.sql_type,
+						SQLType::Char(_) | SQLType::Varchar(_) | SQLType::Text
+					)
+				})
+				.map(|other| AttrValue::from(other.name.clone()))
+				.collect::<Vec<_>>();
+			let full_name_option = match guess {
+				SQLStringValueGuess::FullNameFromColumns { .. } => Some(guess.clone()),
+				_ => match find_name_columns(table) {
+					(Some(first), Some(last)) => Some((first.name.clone(), last.name.clone())),
+					_ if string_columns.len() >= 2 => {
+						Some((string_columns[0].to_string(), string_columns[1].to_string()))
+					}
+					_ => None,
+				}
+				.map(|(first, last)| SQLStringValueGuess::FullNameFromColumns { first, last }),
+			};
+			if let Some(full_name_option) = full_name_option {
+				options.push(("Full name from columns".into(), full_name_option));
+			}
+
+			// Variants with settings are matched by kind, their settings are shown separately
+			let selected = options
+				.iter()
+				.map(|(_, option)| option)
+				.find(|option| mem::discriminant(*option) == mem::discriminant(guess))
+				.unwrap_or(guess);
 
 			let max_size = *max_size;
-			show_enum_dropdown(
-				guess,
+			let kind_picker = show_enum_dropdown(
+				selected,
 				&options,
 				onchange.reform(move |enum_value| SQLValueGuess::String(max_size, enum_value)),
-			)
+			);
+
+			match guess {
+				SQLStringValueGuess::FullNameFromColumns { first, last } => {
+					let on_first_change = {
+						let last = last.clone();
+						onchange.reform(move |first| {
+							SQLValueGuess::String(
+								max_size,
+								SQLStringValueGuess::FullNameFromColumns {
+									first,
+									last: last.clone(),
+								},
+							)
+						})
+					};
+					let on_last_change = {
+						let first = first.clone();
+						onchange.reform(move |last| {
+							SQLValueGuess::String(
+								max_size,
+								SQLStringValueGuess::FullNameFromColumns {
+									first: first.clone(),
+									last,
+								},
+							)
+						})
+					};
+					html! {
+						<div class="flex flex-row">
+							{ kind_picker }
+							<div class="ml-1">{ show_dropdown_picker(first, &string_columns, on_first_change) }</div>
+							<div class="ml-1">{ show_dropdown_picker(last, &string_columns, on_last_change) }</div>
+						</div>
+					}
+				}
+				SQLStringValueGuess::Pattern(pattern) => html! {
+					<div class="flex flex-row">
+						{ kind_picker }
+						{ show_text_picker(
+							pattern,
+							DEFAULT_PATTERN,
+							onchange.reform(move |pattern| {
+								SQLValueGuess::String(max_size, SQLStringValueGuess::Pattern(pattern))
+							}),
+						) }
+					</div>
+				},
+				SQLStringValueGuess::LoremIpsum(lorem_guess) => html! {
+					<div class="flex flex-row">
+						{ kind_picker }
+						<div class="ml-1">
+							{ show_lorem_picker(
+								lorem_guess,
+								onchange.reform(move |lorem_guess| {
+									SQLValueGuess::String(max_size, SQLStringValueGuess::LoremIpsum(lorem_guess))
+								}),
+							) }
+						</div>
+					</div>
+				},
+				SQLStringValueGuess::NationalId { digits, checksum } => {
+					let on_digits_change = {
+						let (digits, checksum) = (*digits, checksum.clone());
+						onchange.reform(move |e: Event| {
+							let value = e.target_unchecked_into::<HtmlInputElement>().value();
+							SQLValueGuess::String(
+								max_size,
+								SQLStringValueGuess::NationalId {
+									digits: value.parse().unwrap_or(digits),
+									checksum: checksum.clone(),
+								},
+							)
+						})
+					};
+					let checksums = vec![
+						("No checksum".into(), None),
+						("Luhn".into(), Some(SQLChecksum::Luhn)),
+						("Mod 11".into(), Some(SQLChecksum::Mod11)),
+					];
+					let digits = *digits;
+					html! {
+						<div class="flex flex-row">
+							{ kind_picker }
+							<input
+								value={digits.to_string()}
+								class="w-3rem ml-1"
+								type="number"
+								min="1"
+								max="30"
+								title="Digits"
+								onchange={on_digits_change}
+							/>
+							<div class="ml-1">
+								{ show_enum_dropdown(
+									checksum,
+									&checksums,
+									onchange.reform(move |checksum| {
+										SQLValueGuess::String(max_size, SQLStringValueGuess::NationalId { digits, checksum })
+									}),
+								) }
+							</div>
+						</div>
+					}
+				}
+				SQLStringValueGuess::Iban(country) => {
+					let mut countries = vec![("Any country".into(), None)];
+					countries.extend(
+						IBAN_COUNTRIES
+							.iter()
+							.map(|(code, _)| (AttrValue::from(*code), Some(code.to_string()))),
+					);
+					// Countries from imported configs might not be in the list
+					let selected = countries
+						.iter()
+						.map(|(_, option)| option)
+						.find(|option| *option == country)
+						.unwrap_or(&None);
+					html! {
+						<div class="flex flex-row">
+							{ kind_picker }
+							<div class="ml-1">
+								{ show_enum_dropdown(
+									selected,
+									&countries,
+									onchange.reform(move |country| {
+										SQLValueGuess::String(max_size, SQLStringValueGuess::Iban(country))
+									}),
+								) }
+							</div>
+						</div>
+					}
+				}
+				SQLStringValueGuess::Sequential { prefix, start, pad } => {
+					let on_prefix_change = {
+						let (start, pad) = (*start, *pad);
+						onchange.reform(move |prefix| {
+							SQLValueGuess::String(
+								max_size,
+								SQLStringValueGuess::Sequential { prefix, start, pad },
+							)
+						})
+					};
+					let on_start_change = {
+						let (prefix, start, pad) = (prefix.clone(), *start, *pad);
+						onchange.reform(move |e: Event| {
+							let value = e.target_unchecked_into::<HtmlInputElement>().value();
+							SQLValueGuess::String(
+								max_size,
+								SQLStringValueGuess::Sequential {
+									prefix: prefix.clone(),
+									start: value.parse().unwrap_or(start),
+									pad,
+								},
+							)
+						})
+					};
+
+					html! {
+						<div class="flex flex-row">
+							{ kind_picker }
+							{ show_text_picker(prefix, "prefix_", on_prefix_change) }
+							<input
+								value={start.to_string()}
+								class="w-5rem ml-1"
+								type="number"
+								min="0"
+								title="First number"
+								onchange={on_start_change}
+							/>
+						</div>
+					}
+				}
+				SQLStringValueGuess::IPv6 if max_size < IPV6_MAX_LENGTH => html! {
+					<div class="flex flex-row">
+						{ kind_picker }
+						<div class="ml-1 text-amber300">{ "⚠️ Too short for IPv6, IPv4 is used" }</div>
+					</div>
+				},
+				_ => kind_picker,
+			}
 		}
 	}
 }
@@ -1,17 +1,23 @@
 use std::{cell::RefCell, collections::HashMap, rc::Rc};
 
-use yew::{function_component, html, Callback, Html, Properties};
+use web_sys::{Event, HtmlInputElement};
+use yew::{function_component, html, Callback, Html, Properties, TargetCast};
 
-use crate::{
-	components::generator_picker::generator_picker, generate_sql::SQLValueGuess,
-	magicdraw_parser::SQLTable,
+use magic_sql_gen::{
+	generate_sql::SQLValueGuess,
+	magicdraw_parser::{SQLIndex, SQLTable, TableKind},
 };
 
+use crate::components::generator_picker::generator_picker;
+
 #[derive(Properties, PartialEq)]
 pub struct SQLTableColumnInfoProps {
 	pub table: Rc<SQLTable>,
 	pub guessess: Rc<RefCell<HashMap<String, SQLValueGuess>>>,
 	pub onchange: Callback<(String, SQLValueGuess)>,
+	/// Only used for views, tables are always generated
+	pub view_included: bool,
+	pub on_view_included_change: Callback<bool>,
 }
 
 const CHECK_MARK: &str = "✔️";
@@ -25,32 +31,66 @@ fn bool_to_mark(value: bool) -> &'static str {
 	}
 }
 
+fn format_index(index: &SQLIndex) -> String {
+	let mut text = format!("({})", index.columns.join(", "));
+	if let Some(name) = &index.name {
+		text = format!("{} {}", name, text);
+	}
+	if index.unique {
+		text += " unique";
+	}
+	text
+}
+
 #[function_component]
 pub fn SQLTableColumnInfo(props: &SQLTableColumnInfoProps) -> Html {
 	let table = &props.table;
+	let is_view = table.kind == TableKind::View;
+	let is_skipped = is_view && !props.view_included;
 
 	let rows = table.columns.iter().map(|col| {
 		let guessess = &props.guessess.borrow();
 		let generator = guessess.get(&col.name);
 
-		let foreign_key;
-		if let Some((table_name, prop_name)) = &col.foreign_key {
-			foreign_key = format!("{} {}", table_name, prop_name);
+		let mut foreign_key;
+		if let Some(fk) = &col.foreign_key {
+			if let Some(cardinality) = &fk.cardinality {
+				foreign_key = format!("{} {} ({})", fk.qualified_table(), fk.column, cardinality);
+			} else {
+				foreign_key = format!("{} {}", fk.qualified_table(), fk.column);
+			}
+			if let Some(name) = &fk.name {
+				foreign_key = format!("{}: {}", name, foreign_key);
+			}
 		} else {
 			foreign_key = CROSS_MARK.into();
 		}
 
+		let check_constraints = if col.check_constraints.is_empty() {
+			html!(CROSS_MARK)
+		} else {
+			col.check_constraints
+				.iter()
+				.map(|constraint| html! { <div> { constraint.to_string() } </div> })
+				.collect::<Html>()
+		};
+
 		let name = col.name.clone();
 		let onchange = props
 			.onchange
 			.reform(move |value: SQLValueGuess| (name.clone(), value));
 		html! {
 			<tr>
+				<td> { col.ordinal + 1 } </td>
 				<td> { &col.name } </td>
 				<td> { &col.sql_type } </td>
 				<td> {
-					if let Some(generator) = generator {
-						generator_picker(col, generator, onchange)
+					if table.literals.is_some() {
+						html!("Enumeration literal")
+					} else if is_skipped {
+						html!("View")
+					} else if let Some(generator) = generator {
+						generator_picker(table, col, generator, onchange)
 					} else {
 						html!(CROSS_MARK)
 					}
@@ -58,27 +98,61 @@ pub fn SQLTableColumnInfo(props: &SQLTableColumnInfoProps) -> Html {
 				<td> { bool_to_mark(col.primary_key) } </td>
 				<td> { bool_to_mark(col.nullable) } </td>
 				<td> { foreign_key } </td>
+				<td> { check_constraints } </td>
 			</tr>
 		}
 	});
 
+	let on_view_included_change = props
+		.on_view_included_change
+		.reform(|e: Event| e.target_unchecked_into::<HtmlInputElement>().checked());
+
 	html! {
 		<div
-			class="table-column-info flex-column inline-block"
+			class={if is_skipped { "table-column-info flex-column inline-block opacity-50" } else { "table-column-info flex-column inline-block" }}
 			border="solid dark100 0.2rem collapse"
 		>
-			<p class="text-center"> { &table.name } </p>
+			<p class="text-center">
+				{ table.qualified_name() }
+				if is_view {
+					{ " (view)" }
+				}
+			</p>
+			if is_view {
+				<label class="text-center">
+					<input
+						type="checkbox"
+						checked={props.view_included}
+						onchange={on_view_included_change}
+					/>
+					{ " Generate rows for this view anyway" }
+				</label>
+			}
 			<table border="solid dark100 t-0.2rem collapse">
 				<tr>
+					<th> { "#" } </th>
 					<th> { "Column" } </th>
 					<th> { "Type" } </th>
 					<th> { "Generator" } </th>
 					<th> { "Primary?" } </th>
 					<th> { "Nullable?" } </th>
 					<th> { "Foreign key?" } </th>
+					<th> { "Check" } </th>
 				</tr>
 				{ for rows }
 			</table>
+			if !table.unique_constraints.is_empty() {
+				<p class="text-center">
+					{ "Unique: " }
+					{ table.unique_constraints.iter().map(|columns| format!("({})", columns.join(", "))).collect::<Vec<_>>().join(", ") }
+				</p>
+			}
+			if !table.indexes.is_empty() {
+				<p class="text-center">
+					{ "Indexes: " }
+					{ table.indexes.iter().map(format_index).collect::<Vec<_>>().join(", ") }
+				</p>
+			}
 		</div>
 	}
 }
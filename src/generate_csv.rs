@@ -0,0 +1,40 @@
+use crate::generate_sql::{GeneratedTable, SQLValue};
+
+// RFC 4180 line endings, which bulk loaders expect
+const LINE_ENDING: &str = "\r\n";
+
+fn escape_field(value: &str) -> String {
+	if value.contains([',', '"', '\n', '\r']) {
+		format!("\"{}\"", value.replace('"', "\"\""))
+	} else {
+		value.to_string()
+	}
+}
+
+fn format_field(value: &SQLValue) -> String {
+	match value {
+		SQLValue::Null => String::new(),
+		// Same as in the SQL output, booleans are stored as numbers by most databases
+		SQLValue::Bool(true) => "1".into(),
+		SQLValue::Bool(false) => "0".into(),
+		value => escape_field(&value.to_string()),
+	}
+}
+
+/// Header with the column names followed by a line per row. NULLs are left empty.
+pub fn to_csv(generated_table: &GeneratedTable) -> String {
+	let mut csv = generated_table
+		.columns
+		.iter()
+		.map(|column| escape_field(&column.name))
+		.collect::<Vec<_>>()
+		.join(",");
+	csv.push_str(LINE_ENDING);
+
+	for row in &generated_table.rows {
+		let fields = row.iter().map(format_field).collect::<Vec<_>>();
+		csv.push_str(&fields.join(","));
+		csv.push_str(LINE_ENDING);
+	}
+	csv
+}
@@ -0,0 +1,155 @@
+use std::rc::Rc;
+
+use crate::{
+	generate_sql::{
+		format_identifier, format_table_name, quote_string, sort_by_dependencies, SQLOptions,
+	},
+	magicdraw_parser::{SQLCheckConstraint, SQLColumn, SQLForeignKey, SQLIndex, SQLTable},
+};
+
+const INDENT: &str = "  ";
+
+fn format_foreign_table(foreign_key: &SQLForeignKey, options: &SQLOptions) -> String {
+	match &foreign_key.schema {
+		Some(schema) => format!(
+			"{}.{}",
+			format_identifier(schema, options),
+			format_identifier(&foreign_key.table, options)
+		),
+		None => format_identifier(&foreign_key.table, options),
+	}
+}
+
+fn format_column_list(columns: &[String], options: &SQLOptions) -> String {
+	columns
+		.iter()
+		.map(|column| format_identifier(column, options))
+		.collect::<Vec<_>>()
+		.join(", ")
+}
+
+fn format_check_constraint(
+	column: &SQLColumn,
+	constraint: &SQLCheckConstraint,
+	options: &SQLOptions,
+) -> Option<String> {
+	let column_name = format_identifier(&column.name, options);
+	let condition = match constraint {
+		SQLCheckConstraint::OneOf(variants) => {
+			let variants = variants
+				.iter()
+				.map(|variant| quote_string(variant, options))
+				.collect::<Vec<_>>();
+			format!("{} IN ({})", column_name, variants.join(", "))
+		}
+		SQLCheckConstraint::Range(Some(min), Some(max)) => {
+			format!("{} BETWEEN {} AND {}", column_name, min, max)
+		}
+		SQLCheckConstraint::Range(Some(min), None) => format!("{} >= {}", column_name, min),
+		SQLCheckConstraint::Range(None, Some(max)) => format!("{} <= {}", column_name, max),
+		SQLCheckConstraint::Range(None, None) => return None,
+		// Written out in full in the model
+		SQLCheckConstraint::Freeform(body) => body.clone(),
+	};
+	Some(format!("CHECK ({})", condition))
+}
+
+fn generate_create_table(table: &SQLTable, options: &SQLOptions) -> String {
+	let mut columns = table.columns.iter().collect::<Vec<_>>();
+	columns.sort_by_key(|column| column.ordinal);
+
+	let mut definitions = vec![];
+	for column in &columns {
+		let mut definition = format!(
+			"{} {}",
+			format_identifier(&column.name, options),
+			column.sql_type
+		);
+		if !column.nullable {
+			definition.push_str(" NOT NULL");
+		}
+		definitions.push(definition);
+	}
+
+	let primary_key = columns
+		.iter()
+		.filter(|column| column.primary_key)
+		.map(|column| column.name.clone())
+		.collect::<Vec<_>>();
+	if !primary_key.is_empty() {
+		definitions.push(format!(
+			"PRIMARY KEY ({})",
+			format_column_list(&primary_key, options)
+		));
+	}
+
+	for column in &columns {
+		let Some(foreign_key) = &column.foreign_key else {
+			continue;
+		};
+		let mut definition = String::new();
+		if let Some(name) = &foreign_key.name {
+			definition = format!("CONSTRAINT {} ", format_identifier(name, options));
+		}
+		definition.push_str(&format!(
+			"FOREIGN KEY ({}) REFERENCES {} ({})",
+			format_identifier(&column.name, options),
+			format_foreign_table(foreign_key, options),
+			format_identifier(&foreign_key.column, options)
+		));
+		definitions.push(definition);
+	}
+
+	for constraint in &table.unique_constraints {
+		definitions.push(format!(
+			"UNIQUE ({})",
+			format_column_list(constraint, options)
+		));
+	}
+
+	for column in &columns {
+		for constraint in &column.check_constraints {
+			definitions.extend(format_check_constraint(column, constraint, options));
+		}
+	}
+
+	let definitions = definitions
+		.iter()
+		.map(|definition| format!("{}{}", INDENT, definition))
+		.collect::<Vec<_>>();
+	let mut statement = format!(
+		"CREATE TABLE {} (\n{}\n);\n",
+		format_table_name(table, options),
+		definitions.join(",\n")
+	);
+	for index in &table.indexes {
+		statement.push_str(&generate_create_index(table, index, options));
+	}
+	statement
+}
+
+fn generate_create_index(table: &SQLTable, index: &SQLIndex, options: &SQLOptions) -> String {
+	// Index names have to be unique, so unnamed ones are named after their columns
+	let name = match &index.name {
+		Some(name) => name.clone(),
+		None => format!("{}_{}_idx", table.name, index.columns.join("_")),
+	};
+	format!(
+		"CREATE {}INDEX {} ON {} ({});\n",
+		if index.unique { "UNIQUE " } else { "" },
+		format_identifier(&name, options),
+		format_table_name(table, options),
+		format_column_list(&index.columns, options)
+	)
+}
+
+/// `CREATE TABLE` and `CREATE INDEX` statements for the tables, with referenced tables
+/// created first
+pub fn generate_ddl(tables: &[Rc<SQLTable>], options: &SQLOptions) -> String {
+	sort_by_dependencies(tables)
+		.order
+		.into_iter()
+		.map(|table_idx| generate_create_table(&tables[table_idx], options))
+		.collect::<Vec<_>>()
+		.join("\n")
+}
@@ -0,0 +1,58 @@
+use serde::{ser::SerializeMap, Serialize, Serializer};
+
+use crate::generate_sql::{GeneratedData, GeneratedTable, SQLValue};
+
+impl Serialize for SQLValue {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		match self {
+			SQLValue::Null => serializer.serialize_none(),
+			SQLValue::Int(value) => serializer.serialize_i64(*value),
+			SQLValue::Float(value) | SQLValue::Decimal(value, _) => {
+				serializer.serialize_f64(*value)
+			}
+			SQLValue::Bool(value) => serializer.serialize_bool(*value),
+			SQLValue::String(value) => serializer.serialize_str(value),
+			SQLValue::Date(value) => serializer.collect_str(&value.format("%Y-%m-%d")),
+			SQLValue::Time(value) => serializer.collect_str(&value.format("%H:%M:%S")),
+			SQLValue::Datetime(value) => serializer.collect_str(&value.format("%Y-%m-%dT%H:%M:%S")),
+		}
+	}
+}
+
+// Maps are written by hand, so that tables and columns keep their order
+struct JsonRow<'a> {
+	table: &'a GeneratedTable,
+	row: &'a [SQLValue],
+}
+
+impl Serialize for JsonRow<'_> {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		let mut map = serializer.serialize_map(Some(self.row.len()))?;
+		for (column, value) in self.table.columns.iter().zip(self.row) {
+			map.serialize_entry(&column.name, value)?;
+		}
+		map.end()
+	}
+}
+
+struct JsonTables<'a>(&'a [GeneratedTable]);
+
+impl Serialize for JsonTables<'_> {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		let mut map = serializer.serialize_map(Some(self.0.len()))?;
+		for table in self.0 {
+			let rows = table
+				.rows
+				.iter()
+				.map(|row| JsonRow { table, row })
+				.collect::<Vec<_>>();
+			map.serialize_entry(&table.name(), &rows)?;
+		}
+		map.end()
+	}
+}
+
+/// An object with an array of rows for each table, dates are written as ISO 8601 strings
+pub fn to_json(generated: &GeneratedData) -> String {
+	serde_json::to_string_pretty(&JsonTables(&generated.tables)).unwrap()
+}
@@ -1,288 +1,2753 @@
 use std::{
-	cell::Ref,
-	collections::{HashMap, HashSet},
+	collections::{BTreeSet, HashMap, HashSet},
+	fmt::Display,
+	hash::{Hash, Hasher},
+	mem,
+	ops::Deref,
 	rc::Rc,
 };
 
-use anyhow::{bail, Result};
-use chrono::{Days, Local, NaiveDateTime};
+use anyhow::{anyhow, bail, Result};
+use chrono::{Duration, Local, Months, NaiveDate, NaiveDateTime, NaiveTime, Timelike};
 use fake::{
 	faker::{
-		address::en::{CityName, StreetName},
-		company::en::BsNoun,
-		internet::en::{DomainSuffix, FreeEmail},
+		address::en::{
+			BuildingNumber, CityName, CountryCode, CountryName, SecondaryAddress, StreetName,
+			ZipCode,
+		},
+		company::en::{BsNoun, CompanyName},
+		currency::en::CurrencyCode,
+		finance::en::Bic,
+		internet::en::{DomainSuffix, FreeEmail, IPv4, IPv6, MACAddress, Username},
+		job::en::Title as JobTitle,
 		lorem::en::*,
 		name::en::{FirstName, LastName, Name},
 		phone_number::en::PhoneNumber,
 	},
 	Fake,
 };
-use rand::{rngs::ThreadRng, seq::SliceRandom, Rng};
+use rand::{
+	distributions::{Distribution, WeightedIndex},
+	rngs::StdRng,
+	seq::{index, SliceRandom},
+	Rng, SeedableRng,
+};
+use serde::{Deserialize, Serialize};
 
-use crate::magicdraw_parser::{SQLCheckConstraint, SQLColumn, SQLTable, SQLType};
+use crate::magicdraw_parser::{
+	find_foreign_column, find_foreign_table, names_match, SQLCheckConstraint, SQLColumn,
+	SQLForeignKey, SQLTable, SQLType,
+};
+use crate::reserved_words::is_reserved_word;
 
 const INDENT: &str = "  ";
+/// How many times a row is regenerated before giving up on a unique constraint
+const UNIQUE_RETRIES: usize = 100;
+/// Nullable columns are NULL this often, unless the guess is changed
+const DEFAULT_NULL_PROBABILITY: f32 = 0.1;
+/// Same as `DEFAULT_NULL_PROBABILITY`, but for optional relationships
+const DEFAULT_FOREIGN_KEY_NULL_PROBABILITY: f32 = 0.2;
+/// Length of an IPv6 address with all of its groups written out
+pub const IPV6_MAX_LENGTH: usize = 39;
+const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+/// Reserved for examples, so generated addresses never reach anyone
+const EMAIL_DOMAIN: &str = "example.com";
+/// Longest value generated for `TEXT` columns, the size of a MySQL `TEXT`
+const TEXT_MAX_SIZE: usize = u16::MAX as usize;
 
-#[derive(Debug, PartialEq, Clone)]
+/// Foreign key column of a table, and where its values come from
+#[derive(Debug, Clone)]
+struct ForeignColumn {
+	column_idx: usize,
+	foreign_table_idx: usize,
+	foreign_column_idx: usize,
+	/// How many times a referenced row may be used
+	max_uses: Option<usize>,
+	/// Only nullable columns are ever left NULL
+	null_probability: f64,
+	distribution: SQLForeignKeyValueGuess,
+}
+
+/// How numbers are spread out over their range
+#[derive(Debug, Default, PartialEq, Clone, Deserialize, Serialize)]
+pub enum SQLDistribution {
+	#[default]
+	Uniform,
+	/// Bell curve around the mean, values outside of the range are clamped to it
+	Normal { mean: f64, stddev: f64 },
+}
+
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
 pub enum SQLIntValueGuess {
-	Range(i32, i32),
-	AutoIncrement,
+	Range {
+		min: i32,
+		max: i32,
+		/// Only multiples of the step are added to the minimum
+		#[serde(default)]
+		step: Option<i32>,
+		#[serde(default)]
+		distribution: SQLDistribution,
+	},
+	/// `start`, `start + step`, `start + 2 * step`, ...
+	AutoIncrement {
+		#[serde(default = "default_auto_increment")]
+		start: u32,
+		#[serde(default = "default_auto_increment")]
+		step: u32,
+		/// Leave the column out of the `INSERT`s, so that the database assigns the values.
+		/// They are still generated, foreign keys expect the database to assign the same ones.
+		#[serde(default)]
+		omit_from_insert: bool,
+	},
 }
 
-#[derive(Debug, PartialEq, Clone)]
+fn default_auto_increment() -> u32 {
+	1
+}
+
+impl SQLIntValueGuess {
+	pub fn range(min: i32, max: i32) -> Self {
+		SQLIntValueGuess::Range {
+			min,
+			max,
+			step: None,
+			distribution: SQLDistribution::Uniform,
+		}
+	}
+
+	pub fn auto_increment() -> Self {
+		SQLIntValueGuess::AutoIncrement {
+			start: 1,
+			step: 1,
+			omit_from_insert: false,
+		}
+	}
+}
+
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
 pub enum SQLTimeValueGuess {
 	Now,
-	Future,
-	Past,
+	/// Between `min_days` and `max_days` days from now
+	Future {
+		min_days: u32,
+		max_days: u32,
+	},
+	/// Between `min_days` and `max_days` days ago
+	Past {
+		min_days: u32,
+		max_days: u32,
+	},
+	/// Any day between `min` and `max` years ago, like birthdates of adults
+	YearsAgo {
+		min: u32,
+		max: u32,
+	},
+	/// Any time from the start of the first day until the end of the last one
+	Between(NaiveDate, NaiveDate),
+	BetweenDatetimes(NaiveDateTime, NaiveDateTime),
+	/// Between `min_days` and `max_days` days after the value of another column in the same
+	/// row, like `updated_at` after `created_at`
+	After {
+		column: String,
+		min_days: u32,
+		max_days: u32,
+	},
+	/// Time of day between `from` and `to`, on a grid of `step_minutes` if it's given.
+	/// Windows where `to` is before `from` go over midnight.
+	TimeWindow {
+		from: NaiveTime,
+		to: NaiveTime,
+		step_minutes: Option<u8>,
+	},
 }
 
-#[derive(Debug, PartialEq, Clone)]
+impl SQLTimeValueGuess {
+	pub fn future() -> Self {
+		SQLTimeValueGuess::Future {
+			min_days: 1,
+			max_days: 30,
+		}
+	}
+
+	pub fn past() -> Self {
+		SQLTimeValueGuess::Past {
+			min_days: 7,
+			max_days: 365,
+		}
+	}
+
+	pub fn whole_day() -> Self {
+		SQLTimeValueGuess::TimeWindow {
+			from: NaiveTime::from_hms_opt(0, 0, 0).unwrap(),
+			to: NaiveTime::from_hms_opt(23, 59, 59).unwrap(),
+			step_minutes: None,
+		}
+	}
+}
+
+/// Ranges which end before they start can't be generated
+pub fn check_time_range(guess: &SQLTimeValueGuess) -> Option<String> {
+	match guess {
+		SQLTimeValueGuess::Between(start, end) if end < start => Some(format!(
+			"date range ends on {} before it starts on {}",
+			end, start
+		)),
+		SQLTimeValueGuess::BetweenDatetimes(start, end) if end < start => Some(format!(
+			"date range ends at {} before it starts at {}",
+			end, start
+		)),
+		_ => None,
+	}
+}
+
+/// How values of a foreign key column are picked from the referenced column
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
+pub enum SQLForeignKeyValueGuess {
+	Uniform,
+	/// Each referenced row is used at most once, needed when the column is also unique
+	OneToOne,
+	/// A few of the referenced rows get most of the references, following Zipf's law
+	Skewed,
+	/// Every referenced row is used once before any of them are repeated
+	CoverAll,
+}
+
+/// Check digit algorithms of generated codes
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
+pub enum SQLChecksum {
+	Luhn,
+	/// Weighted sum modulo 11, used by Lithuanian and Estonian personal codes
+	Mod11,
+}
+
+/// Shape of generated placeholder text
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
+pub enum SQLLoremValueGuess {
+	/// Lowercase words, between the minimum and maximum amount of them
+	Words(usize, usize),
+	/// Capitalized sentences, with about as many words in total
+	Sentences(usize, usize),
+	/// A few sentences
+	Paragraph,
+}
+
+impl Default for SQLLoremValueGuess {
+	fn default() -> Self {
+		SQLLoremValueGuess::Words(3, 10)
+	}
+}
+
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
 pub enum SQLStringValueGuess {
-	LoremIpsum,
+	LoremIpsum(SQLLoremValueGuess),
 	FirstName,
 	LastName,
 	FullName,
+	/// First and last name columns of the same row joined together
+	FullNameFromColumns {
+		first: String,
+		last: String,
+	},
 	Empty,
 	PhoneNumber,
 	CityName,
+	StreetName,
+	/// Building number, street and sometimes an apartment
 	Address,
+	/// `Address` followed by the city and postal code
+	FullAddress,
 	Email,
+	/// Email made of the first and last name columns of the same row, like
+	/// `alice.nguyen42@example.com`
+	EmailFromName,
 	URL,
-	RandomEnum(Vec<String>),
+	CountryName,
+	/// Two letter ISO 3166 code
+	CountryCode,
+	PostalCode,
+	CompanyName,
+	JobTitle,
+	/// Lowercase and without spaces, so it can be used to log in
+	Username,
+	/// Looks like a bcrypt hash, or a hex SHA-256 digest when that doesn't fit
+	PasswordHash,
+	/// 16 digits passing the Luhn check
+	CreditCardNumber,
+	/// With valid check digits, for the given country from `IBAN_COUNTRIES` or any of them
+	Iban(Option<String>),
+	Bic,
+	/// Three letter ISO 4217 code
+	CurrencyCode,
+	IPv4,
+	/// Falls back to IPv4 in columns too short for a full IPv6 address
+	IPv6,
+	MacAddress,
+	Hostname,
+	/// Like `#a3f2c1`, always 7 characters
+	ColorHex,
+	ColorName,
+	/// A word with a file extension, like `report.pdf`
+	FileName,
+	/// A few lowercase directories followed by a file name
+	FilePath,
+	/// A personal code or social security number made of digits, the last of which is the
+	/// check digit when a checksum is set
+	NationalId {
+		digits: u8,
+		checksum: Option<SQLChecksum>,
+	},
+	/// Each `#` is replaced with a digit, `?` with an uppercase letter and `*` with either of
+	/// them, other characters are kept as is
+	Pattern(String),
+	/// The prefix followed by a counter, zero padded to `pad` digits. Every row gets a
+	/// different value, so it is fit for unique columns.
+	Sequential {
+		prefix: String,
+		start: u32,
+		pad: u8,
+	},
+	/// One of the variants, picked with a chance proportional to its weight
+	RandomEnum(Vec<(String, f32)>),
 }
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
 pub enum SQLBoolValueGuess {
 	True,
 	False,
 	Random,
 }
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
 pub enum SQLValueGuess {
 	Int(SQLIntValueGuess),
 	Date(SQLTimeValueGuess),
 	Time(SQLTimeValueGuess),
 	Datetime(SQLTimeValueGuess),
-	Float(f32, f32),
+	/// Minimum, maximum, the amount of decimal places and how values are spread out
+	Float(f32, f32, u8, SQLDistribution),
 	Bool(SQLBoolValueGuess),
 	String(usize, SQLStringValueGuess),
+	/// NULL with the given probability, otherwise a value of the inner guess
+	Nullable(f32, Box<SQLValueGuess>),
+	ForeignKey(SQLForeignKeyValueGuess),
+	/// The same value in every row, read as a value of the column's type
+	Fixed(String),
+	/// One of the given values, each read the same way as `Fixed`
+	FromList(Vec<String>),
+}
+
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct GenerateOptions {
+	pub rows_per_table: u32,
+	/// Row counts of specific tables, by name or schema qualified name
+	pub table_rows: HashMap<String, u32>,
+	/// Chance that a row of a self referencing table has no parent, when the column is nullable.
+	/// Has to be between 0 and 1, `generate_rows` fails otherwise.
+	pub root_row_probability: f64,
+	/// Makes random values reproducible, uses entropy when not set
+	pub seed: Option<u64>,
+}
+
+impl Default for GenerateOptions {
+	fn default() -> Self {
+		Self {
+			rows_per_table: 20,
+			table_rows: HashMap::new(),
+			root_row_probability: 0.2,
+			seed: None,
+		}
+	}
+}
+
+impl GenerateOptions {
+	/// Options which can't be generated with, like a probability outside of 0..=1
+	pub fn check(&self) -> Option<String> {
+		if !(0.0..=1.0).contains(&self.root_row_probability) {
+			return Some(format!(
+				"root row probability has to be between 0 and 1, but is {}",
+				self.root_row_probability
+			));
+		}
+		None
+	}
+
+	/// Row count of a table from `table_rows`, or `rows_per_table` if it has none
+	pub fn rows_of(&self, table: &SQLTable) -> u32 {
+		self.table_rows
+			.get(&table.qualified_name())
+			.or_else(|| self.table_rows.get(&table.name))
+			.copied()
+			.unwrap_or(self.rows_per_table)
+	}
+}
+
+/// Database the SQL is written for, decides how literals and identifiers look
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy, Deserialize, Serialize)]
+pub enum SQLDialect {
+	#[default]
+	MySQL,
+	Postgres,
+	SQLite,
+	SQLServer,
+	Oracle,
+}
+
+impl SQLDialect {
+	pub const ALL: [SQLDialect; 5] = [
+		SQLDialect::MySQL,
+		SQLDialect::Postgres,
+		SQLDialect::SQLite,
+		SQLDialect::SQLServer,
+		SQLDialect::Oracle,
+	];
+
+	pub fn label(&self) -> &'static str {
+		match self {
+			SQLDialect::MySQL => "MySQL",
+			SQLDialect::Postgres => "PostgreSQL",
+			SQLDialect::SQLite => "SQLite",
+			SQLDialect::SQLServer => "SQL Server",
+			SQLDialect::Oracle => "Oracle",
+		}
+	}
+}
+
+/// How generated rows are written out as SQL, also used for `CREATE TABLE` statements
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct SQLOptions {
+	pub dialect: SQLDialect,
+	/// Wrap all table and column names in the quotes of the dialect, needed for names with
+	/// non-ASCII letters or spaces on some databases. Reserved words are always quoted.
+	pub quote_identifiers: bool,
+	/// List columns one per line, with the foreign key constraint name after each foreign key
+	pub comments: bool,
+	/// Rows are split into multiple `INSERT` statements, because some databases limit the
+	/// size of a single statement
+	pub max_rows_per_insert: u32,
+	/// Separate `INSERT` statement for each row, for tools which don't support multi-row ones
+	pub insert_per_row: bool,
+	/// Wrap the inserts in a transaction, so a failed script doesn't leave half of the rows
+	pub transaction: bool,
+	/// Turn off foreign key checks while inserting. Postgres and Oracle can only defer
+	/// constraints which were declared as `DEFERRABLE`, and only inside of a transaction.
+	pub disable_foreign_key_checks: bool,
+	/// Delete the existing rows of the tables before inserting, so the script can be run again
+	pub cleanup: bool,
+	/// Fill in nullable foreign keys which were left NULL to break a cycle with `UPDATE`s
+	/// after the inserts
+	pub deferred_updates: bool,
+	/// Right pad values of `CHAR` columns with spaces to the size of the column, the same as
+	/// the database stores them
+	pub pad_char_columns: bool,
+}
+
+impl Default for SQLOptions {
+	fn default() -> Self {
+		Self {
+			dialect: SQLDialect::default(),
+			quote_identifiers: false,
+			comments: false,
+			max_rows_per_insert: 500,
+			insert_per_row: false,
+			transaction: false,
+			disable_foreign_key_checks: false,
+			cleanup: false,
+			deferred_updates: true,
+			pad_char_columns: false,
+		}
+	}
+}
+
+/// A generated value, before it is formatted for some output
+#[derive(Debug, Default, PartialEq, Clone)]
+pub enum SQLValue {
+	#[default]
+	Null,
+	Int(i64),
+	Float(f64),
+	/// Written with exactly this many decimals, so `12.50` doesn't turn into `12.5`
+	Decimal(f64, u8),
+	Bool(bool),
+	String(String),
+	Date(NaiveDate),
+	Time(NaiveTime),
+	Datetime(NaiveDateTime),
+}
+
+impl SQLValue {
+	pub fn non_null(&self) -> Option<&SQLValue> {
+		match self {
+			SQLValue::Null => None,
+			value => Some(value),
+		}
+	}
+}
+
+// Needed for comparing values of unique constraints. Generated floats are never NaN.
+impl Eq for SQLValue {}
+
+impl Hash for SQLValue {
+	fn hash<H: Hasher>(&self, state: &mut H) {
+		mem::discriminant(self).hash(state);
+		match self {
+			SQLValue::Null => {}
+			SQLValue::Int(value) => value.hash(state),
+			SQLValue::Float(value) => value.to_bits().hash(state),
+			SQLValue::Decimal(value, decimals) => (value.to_bits(), decimals).hash(state),
+			SQLValue::Bool(value) => value.hash(state),
+			SQLValue::String(value) => value.hash(state),
+			SQLValue::Date(value) => value.hash(state),
+			SQLValue::Time(value) => value.hash(state),
+			SQLValue::Datetime(value) => value.hash(state),
+		}
+	}
+}
+
+/// Values without any quoting, booleans as `true` or `false` and NULL as `NULL`
+impl Display for SQLValue {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			SQLValue::Null => write!(f, "NULL"),
+			SQLValue::Int(value) => write!(f, "{}", value),
+			SQLValue::Float(value) => write!(f, "{}", value),
+			SQLValue::Decimal(value, decimals) => write!(f, "{:.*}", *decimals as usize, value),
+			SQLValue::Bool(value) => write!(f, "{}", value),
+			SQLValue::String(value) => write!(f, "{}", value),
+			SQLValue::Date(value) => write!(f, "{}", value.format("%Y-%m-%d")),
+			SQLValue::Time(value) => write!(f, "{}", value.format("%H:%M:%S")),
+			SQLValue::Datetime(value) => write!(f, "{}", value.format("%Y-%m-%d %H:%M:%S")),
+		}
+	}
+}
+
+/// Generated rows of a single table, before they are written out in some format
+#[derive(Debug, PartialEq, Clone)]
+pub struct GeneratedTable {
+	pub table: Rc<SQLTable>,
+	/// Columns of the table ordered by their ordinals, values of rows follow the same order
+	pub columns: Vec<SQLColumn>,
+	pub rows: Vec<Vec<SQLValue>>,
+	/// Row index, column index and value of foreign keys which are NULL in `rows`, because
+	/// they reference a table inserted later on a foreign key cycle
+	pub deferred_values: Vec<(usize, usize, SQLValue)>,
+	/// Indices of columns which are left out of the `INSERT`s
+	pub omitted_columns: Vec<usize>,
+}
+
+impl GeneratedTable {
+	/// Table name prefixed with its schema, if it has one
+	pub fn name(&self) -> String {
+		self.table.qualified_name()
+	}
+}
+
+/// Rows of each table, in the same order as the tables were given
+#[derive(Debug, PartialEq, Clone)]
+pub struct GeneratedData {
+	pub tables: Vec<GeneratedTable>,
+	/// Seed the rows were generated with, the same as `GenerateOptions::seed` if it was given
+	pub seed: u64,
+	/// Problems which don't stop the generation, but may make the rows fail to insert
+	pub warnings: Vec<String>,
+}
+
+impl GeneratedData {
+	/// Start values which continue each auto-increment column of the `tables` after the
+	/// generated rows, so that the next generation doesn't repeat them
+	pub fn continued_auto_increments(
+		&self,
+		guessess: &[impl Deref<Target = HashMap<String, SQLValueGuess>>],
+	) -> Vec<HashMap<String, u32>> {
+		self.tables
+			.iter()
+			.zip(guessess)
+			.map(|(generated_table, guessess)| {
+				let mut continued = HashMap::new();
+				for (column_idx, column) in generated_table.columns.iter().enumerate() {
+					let Some(SQLValueGuess::Int(SQLIntValueGuess::AutoIncrement { step, .. })) =
+						guessess.get(&column.name)
+					else {
+						continue;
+					};
+					let last = generated_table
+						.rows
+						.iter()
+						.filter_map(|row| match row[column_idx] {
+							SQLValue::Int(value) => Some(value),
+							_ => None,
+						})
+						.max();
+					if let Some(last) = last {
+						continued.insert(
+							column.name.clone(),
+							(last + (*step).max(1) as i64).clamp(0, u32::MAX as i64) as u32,
+						);
+					}
+				}
+				continued
+			})
+			.collect()
+	}
+
+	/// Order in which rows of the tables can be inserted without breaking foreign keys
+	pub fn insert_order(&self) -> DependencyOrder {
+		let tables = self
+			.tables
+			.iter()
+			.map(|generated_table| generated_table.table.clone())
+			.collect::<Vec<_>>();
+		sort_by_dependencies(&tables)
+	}
 }
 
-// TODO: Check primary key constraint
-pub fn generate_fake_entries(
+/// Same as `generate_rows` followed by `to_sql` with the default SQL options
+pub fn generate_fake_entries<G: Deref<Target = HashMap<String, SQLValueGuess>>>(
 	tables: &[Rc<SQLTable>],
-	value_guessess: &Vec<Ref<HashMap<String, SQLValueGuess>>>,
-	rows_per_table: u32,
+	value_guessess: &[G],
+	options: &GenerateOptions,
 ) -> Result<String> {
-	let mut lines = vec![];
+	let generated = generate_rows(tables, value_guessess, options)?;
+	Ok(to_sql(&generated, &SQLOptions::default()))
+}
 
-	let mut rng = rand::thread_rng();
+/// Values of primary keys and unique columns are regenerated until they don't repeat
+pub fn generate_rows<G: Deref<Target = HashMap<String, SQLValueGuess>>>(
+	tables: &[Rc<SQLTable>],
+	value_guessess: &[G],
+	options: &GenerateOptions,
+) -> Result<GeneratedData> {
+	if let Some(problem) = options.check() {
+		bail!(problem);
+	}
+	// A random seed is still picked, so that the run can be repeated
+	let seed = options.seed.unwrap_or_else(|| StdRng::from_entropy().gen());
+	let mut rng = StdRng::seed_from_u64(seed);
+
+	// Lookup tables always contain exactly their enumeration literals
+	let row_counts = tables
+		.iter()
+		.map(|table| match &table.literals {
+			Some(literals) => literals.len(),
+			None => options.rows_of(table) as usize,
+		})
+		.collect::<Vec<_>>();
+	check_values_fit(tables, value_guessess)?;
 
 	let mut all_foreign_columns = vec![];
 	let mut all_entries = vec![];
-	for table in tables {
+	for (table_idx, table) in tables.iter().enumerate() {
 		let mut entries = vec![];
-		for _ in 0..rows_per_table {
+		for _ in 0..row_counts[table_idx] {
 			entries.push(vec![]);
 		}
 		all_entries.push(entries);
 
 		let mut foreign_columns = vec![];
 		for (i, column) in table.columns.iter().enumerate() {
-			if let Some((table_name, column_name)) = &column.foreign_key {
-				let (table_idx, table) = tables
-					.iter()
-					.enumerate()
-					.find(|(_, table)| table.name.eq(table_name))
-					.expect("Foreign table not found");
-				let (column_idx, _) = table
-					.columns
-					.iter()
-					.enumerate()
-					.find(|(_, column)| column.name.eq(column_name))
-					.expect("Foreign column not found");
-				foreign_columns.push((i, table_idx, column_idx));
+			if overrides_foreign_key(value_guessess[table_idx].get(&column.name)) {
+				continue;
+			}
+			if let Some(foreign_key) = &column.foreign_key {
+				let (foreign_table_idx, foreign_table) = find_foreign_table(tables, foreign_key)
+					.ok_or_else(|| {
+						anyhow!(
+							"{}.{} references table {} which is not part of the collection",
+							table.qualified_name(),
+							column.name,
+							foreign_key.qualified_table()
+						)
+					})?;
+				let foreign_column_idx = find_foreign_column(foreign_table, &foreign_key.column)
+					.ok_or_else(|| {
+						anyhow!(
+							"{}.{} references column {}.{} which does not exist",
+							table.qualified_name(),
+							column.name,
+							foreign_key.qualified_table(),
+							foreign_key.column
+						)
+					})?;
+
+				let (null_probability, distribution) = match value_guessess[table_idx]
+					.get(&column.name)
+				{
+					Some(SQLValueGuess::Nullable(null_probability, guess)) => {
+						match guess.as_ref() {
+							SQLValueGuess::ForeignKey(distribution) if column.nullable => (
+								null_probability.clamp(0.0, 1.0) as f64,
+								distribution.clone(),
+							),
+							_ => (0.0, SQLForeignKeyValueGuess::Uniform),
+						}
+					}
+					Some(SQLValueGuess::ForeignKey(distribution)) => (0.0, distribution.clone()),
+					_ => (0.0, SQLForeignKeyValueGuess::Uniform),
+				};
+
+				// Referenced rows can be used any number of times, unless the key is one-to-one
+				// or the model limits it
+				let max_uses = match (&distribution, foreign_key.cardinality) {
+					(SQLForeignKeyValueGuess::OneToOne, _) => Some(1),
+					(_, Some(cardinality)) => cardinality.upper.map(|upper| upper as usize),
+					// The other distributions only change which rows are picked more often
+					(_, None) => None,
+				};
+				if distribution == SQLForeignKeyValueGuess::OneToOne
+					&& row_counts[table_idx] > row_counts[foreign_table_idx]
+				{
+					bail!(
+						"{}.{}: one-to-one foreign key needs a different row of {} for each row, but {} rows were requested and {} has {}",
+						table.qualified_name(), column.name, foreign_table.qualified_name(),
+						row_counts[table_idx], foreign_table.qualified_name(), row_counts[foreign_table_idx]
+					);
+				}
+				foreign_columns.push(ForeignColumn {
+					column_idx: i,
+					foreign_table_idx,
+					foreign_column_idx,
+					max_uses,
+					null_probability,
+					distribution,
+				});
 			}
 		}
 		all_foreign_columns.push(foreign_columns);
 	}
+	check_unique_constraints_possible(tables, value_guessess, &all_foreign_columns, &row_counts)?;
+
+	// Ids assigned by the database can't be known beforehand, so foreign keys keep the
+	// generated ones and are only correct if the database counts the same way
+	let mut warnings = vec![];
+	for (table_idx, table) in tables.iter().enumerate() {
+		for foreign_column in &all_foreign_columns[table_idx] {
+			let foreign_table = &tables[foreign_column.foreign_table_idx];
+			let foreign_column_name =
+				&foreign_table.columns[foreign_column.foreign_column_idx].name;
+			if is_omitted_from_insert(
+				value_guessess[foreign_column.foreign_table_idx].get(foreign_column_name),
+			) {
+				warnings.push(format!(
+					"{}.{} is left out of the INSERTs, {}.{} assumes the database assigns the same values as were generated",
+					foreign_table.qualified_name(), foreign_column_name,
+					table.qualified_name(), table.columns[foreign_column.column_idx].name
+				));
+			}
+		}
+		for column in &table.columns {
+			let guess = value_guessess[table_idx].get(&column.name);
+			if let Some((max_size, SQLStringValueGuess::IPv6)) = guess.and_then(string_guess) {
+				if max_size < IPV6_MAX_LENGTH {
+					warnings.push(format!(
+						"{}.{}: IPv6 addresses don't fit into {} characters, IPv4 addresses are generated instead",
+						table.qualified_name(), column.name, max_size
+					));
+				}
+			}
+		}
+	}
 
-	let mut entries_with_foreign_keys = HashSet::new();
+	// Rows whose foreign keys are not filled in yet
+	let mut pending = row_counts
+		.iter()
+		.map(|row_count| vec![false; *row_count])
+		.collect::<Vec<_>>();
 	for (table_idx, table) in tables.iter().enumerate() {
 		let entries = &mut all_entries[table_idx];
 
+		if let Some(literals) = &table.literals {
+			for (entry_idx, literal) in literals.iter().enumerate() {
+				entries[entry_idx].push(SQLValue::Int(entry_idx as i64 + 1));
+				entries[entry_idx].push(SQLValue::String(literal.clone()));
+			}
+			continue;
+		}
+
 		for column in &table.columns {
-			if column.foreign_key.is_some() {
-				for entry_idx in 0..(rows_per_table as usize) {
-					entries_with_foreign_keys.insert((table_idx, entry_idx));
-					entries[entry_idx].push("".into());
+			let value_guess = value_guessess[table_idx].get(column.name.as_str());
+			if column.foreign_key.is_some() && !overrides_foreign_key(value_guess) {
+				for entry_idx in 0..row_counts[table_idx] {
+					pending[table_idx][entry_idx] = true;
+					entries[entry_idx].push(SQLValue::Null);
 				}
 			} else {
 				let mut auto_increment_counter = 0;
-				let value_guess = value_guessess[table_idx]
-					.get(column.name.as_str())
-					.expect("Failed to get column guess");
-				for entry_idx in 0..(rows_per_table as usize) {
-					let value = generate_value(&mut rng, &value_guess, &mut auto_increment_counter);
+				let Some(value_guess) = value_guess else {
+					bail!(
+						"{}.{}: no generator is picked for the column",
+						table.qualified_name(),
+						column.name
+					);
+				};
+				for entry_idx in 0..row_counts[table_idx] {
+					let value =
+						generate_value(&mut rng, column, &value_guess, &mut auto_increment_counter);
 					entries[entry_idx].push(value);
 				}
 			}
 		}
 	}
 
-	while !entries_with_foreign_keys.is_empty() {
-		let entries_with_foreign_keys_copy = entries_with_foreign_keys.clone();
-		let before_retain = entries_with_foreign_keys.len();
+	apply_row_values(&mut rng, tables, value_guessess, &mut all_entries);
 
-		entries_with_foreign_keys.retain(|(table_idx, entry_idx)| {
-			for (column_idx, foreign_table_idx, foreign_column_idx) in &all_foreign_columns[*table_idx]
-			{
-				let mut available_values: Vec<&str>;
+	// Columns referenced by foreign keys must not change after they are copied,
+	// so constraints without foreign keys are made unique first
+	enforce_unique_constraints(&mut rng, tables, value_guessess, &mut all_entries, false)?;
+	// Regenerating unique values would break the order, so it's applied to the final ones
+	apply_time_offsets(&mut rng, tables, value_guessess, &mut all_entries)?;
 
-				// If the foreign column, is also a foreign of the other table, ...
-				// Then we need to filter out available options which have not been filled in
-				let is_foreign_column_also_foreign = all_foreign_columns[*foreign_table_idx]
-					.iter()
-					.find(|(idx, _, _)| idx == foreign_column_idx)
-					.is_some();
-				if is_foreign_column_also_foreign
+	// Referenced tables are filled first, so most rows are resolved on the first pass. Only
+	// tables on a cycle need more of them.
+	let insert_order = sort_by_dependencies(tables).order;
+
+	// Foreign keys to tables which are inserted later break the cycle. Nullable ones are left
+	// NULL, and filled in afterwards with `GeneratedTable::deferred_values`.
+	let mut insert_positions = vec![0; tables.len()];
+	for (position, table_idx) in insert_order.iter().enumerate() {
+		insert_positions[*table_idx] = position;
+	}
+	let deferred_columns = all_foreign_columns
+		.iter()
+		.enumerate()
+		.flat_map(|(table_idx, foreign_columns)| {
+			foreign_columns
+				.iter()
+				.map(move |foreign_column| (table_idx, foreign_column))
+		})
+		.filter(|(table_idx, foreign_column)| {
+			insert_positions[foreign_column.foreign_table_idx] > insert_positions[*table_idx]
+				&& tables[*table_idx].columns[foreign_column.column_idx].nullable
+		})
+		.map(|(table_idx, foreign_column)| (table_idx, foreign_column.clone()))
+		.collect::<Vec<_>>();
+
+	// Unique column sets made only of foreign keys, like the primary key of a junction table.
+	// Picking their values one at a time repeats pairs, so whole combinations are picked
+	// once everything else is resolved.
+	let mut junction_constraints = vec![];
+	for (table_idx, table) in tables.iter().enumerate() {
+		for constraint in &table.unique_column_sets() {
+			if constraint.len() < 2 {
+				continue;
+			}
+			let foreign_columns = get_column_indices(table, constraint)?
+				.into_iter()
+				.map(|column_idx| {
+					all_foreign_columns[table_idx]
+						.iter()
+						.find(|foreign_column| foreign_column.column_idx == column_idx)
+						.cloned()
+				})
+				.collect::<Option<Vec<_>>>();
+			if let Some(foreign_columns) = foreign_columns {
+				junction_constraints.push((table_idx, foreign_columns));
+			}
+		}
+	}
+
+	loop {
+		let mut resolved_count = 0;
+		let mut pending_count = 0;
+		let mut unresolved_columns = BTreeSet::new();
+		for &table_idx in &insert_order {
+			for entry_idx in 0..row_counts[table_idx] {
+				if !pending[table_idx][entry_idx] {
+					continue;
+				}
+
+				let mut resolved = true;
+				for ForeignColumn {
+					column_idx,
+					foreign_table_idx,
+					foreign_column_idx,
+					max_uses,
+					null_probability,
+					distribution,
+				} in &all_foreign_columns[table_idx]
 				{
-					available_values = all_entries[*foreign_table_idx]
+					if deferred_columns.iter().any(|(idx, deferred)| {
+						*idx == table_idx && deferred.column_idx == *column_idx
+					}) {
+						continue;
+					}
+					let is_junction_column =
+						junction_constraints.iter().any(|(idx, foreign_columns)| {
+							*idx == table_idx
+								&& foreign_columns
+									.iter()
+									.any(|foreign_column| foreign_column.column_idx == *column_idx)
+						});
+					if is_junction_column {
+						continue;
+					}
+					if rng.gen_bool(*null_probability) {
+						all_entries[table_idx][entry_idx][*column_idx] = SQLValue::Null;
+						continue;
+					}
+					let mut available_values: Vec<&SQLValue>;
+
+					// If the foreign column, is also a foreign of the other table, ...
+					// Then we need to filter out available options which have not been filled in
+					let is_foreign_column_also_foreign = all_foreign_columns[*foreign_table_idx]
+						.iter()
+						.find(|foreign_column| foreign_column.column_idx == *foreign_column_idx)
+						.is_some();
+					let is_self_reference = table_idx == *foreign_table_idx;
+					if is_self_reference {
+						// Rows only reference rows before them, so the hierarchy can't contain cycles
+						available_values = all_entries[table_idx][..entry_idx]
+							.iter()
+							.filter_map(|entry| entry[*foreign_column_idx].non_null())
+							.collect();
+					} else if is_foreign_column_also_foreign {
+						available_values = all_entries[*foreign_table_idx]
+							.iter()
+							.enumerate()
+							.filter(|(i, _)| !pending[*foreign_table_idx][*i])
+							.filter_map(|(_, entry)| entry[*foreign_column_idx].non_null())
+							.collect();
+					} else {
+						available_values = all_entries[*foreign_table_idx]
+							.iter()
+							.filter_map(|entry| entry[*foreign_column_idx].non_null())
+							.collect();
+					}
+
+					let mut used_counts = HashMap::new();
+					for value in all_entries[table_idx]
 						.iter()
 						.enumerate()
-						.filter(|(i, _)| {
-							entries_with_foreign_keys_copy.contains(&(*foreign_table_idx, *i))
-						})
-						.map(|(_, entry)| entry[*foreign_column_idx].as_str())
-						.collect();
+						.filter(|(i, _)| *i != entry_idx)
+						.filter_map(|(_, entry)| entry[*column_idx].non_null())
+					{
+						*used_counts.entry(value).or_insert(0) += 1;
+					}
+					if let Some(max_uses) = max_uses {
+						available_values
+							.retain(|value| used_counts.get(value).unwrap_or(&0) < max_uses);
+					}
+					if *distribution == SQLForeignKeyValueGuess::CoverAll {
+						let least_used = available_values
+							.iter()
+							.map(|value| used_counts.get(value).unwrap_or(&0))
+							.min()
+							.copied();
+						available_values.retain(|value| {
+							Some(*used_counts.get(value).unwrap_or(&0)) == least_used
+						});
+					}
+
+					if is_self_reference {
+						let column = &tables[table_idx].columns[*column_idx];
+						let is_root = available_values.is_empty()
+							|| rng.gen_bool(options.root_row_probability);
+						if column.nullable && is_root {
+							all_entries[table_idx][entry_idx][*column_idx] = SQLValue::Null;
+							continue;
+						} else if available_values.is_empty() {
+							// The first row of a non nullable hierarchy can only reference itself
+							available_values.extend(
+								all_entries[table_idx][entry_idx][*foreign_column_idx].non_null(),
+							);
+						}
+					}
+
+					let chosen_value = match distribution {
+						SQLForeignKeyValueGuess::Skewed => {
+							choose_skewed(&mut rng, &available_values)
+						}
+						_ => available_values.choose(&mut rng),
+					};
+					if let Some(chosen_value) = chosen_value {
+						all_entries[table_idx][entry_idx][*column_idx] = (*chosen_value).clone();
+					} else {
+						// There are no available options yet, try again on the next pass
+						unresolved_columns.insert((table_idx, *column_idx));
+						resolved = false;
+						break;
+					}
+				}
+
+				if resolved {
+					pending[table_idx][entry_idx] = false;
+					resolved_count += 1;
 				} else {
-					available_values = all_entries[*foreign_table_idx]
+					pending_count += 1;
+				}
+			}
+		}
+
+		if pending_count == 0 {
+			break;
+		}
+		// This is to stop infnite loop, where during a pass nothing gets resolved
+		if resolved_count == 0 {
+			bail!(
+				"Failed to resolve foreign keys: {}",
+				describe_unresolved_columns(
+					tables,
+					&all_foreign_columns,
+					&unresolved_columns,
+					&row_counts
+				)
+			)
+		}
+	}
+	for (table_idx, foreign_columns) in &junction_constraints {
+		let choices = foreign_columns
+			.iter()
+			.map(|foreign_column| {
+				let mut seen = HashSet::new();
+				all_entries[foreign_column.foreign_table_idx]
+					.iter()
+					.filter_map(|entry| entry[foreign_column.foreign_column_idx].non_null())
+					.filter(|value| seen.insert(*value))
+					.cloned()
+					.collect::<Vec<_>>()
+			})
+			.collect::<Vec<_>>();
+		let combinations = sample_combinations(&mut rng, &choices, row_counts[*table_idx])
+			.ok_or_else(|| {
+				let sizes = choices
+					.iter()
+					.map(|values| values.len().to_string())
+					.collect::<Vec<_>>();
+				let names = foreign_columns
+					.iter()
+					.map(|foreign_column| {
+						tables[*table_idx].columns[foreign_column.column_idx]
+							.name
+							.clone()
+					})
+					.collect::<Vec<_>>();
+				anyhow!(
+					"{}: ({}) have to be unique, but only {} combinations exist for {} rows",
+					tables[*table_idx].qualified_name(),
+					names.join(", "),
+					sizes.join("×"),
+					row_counts[*table_idx]
+				)
+			})?;
+		for (entry, combination) in all_entries[*table_idx].iter_mut().zip(combinations) {
+			for (foreign_column, value) in foreign_columns.iter().zip(combination) {
+				entry[foreign_column.column_idx] = value;
+			}
+		}
+	}
+	enforce_unique_constraints(&mut rng, tables, value_guessess, &mut all_entries, true)?;
+
+	let mut all_deferred_values = vec![vec![]; tables.len()];
+	for (
+		table_idx,
+		ForeignColumn {
+			column_idx,
+			foreign_table_idx,
+			foreign_column_idx,
+			max_uses,
+			..
+		},
+	) in &deferred_columns
+	{
+		let available_values = all_entries[*foreign_table_idx]
+			.iter()
+			.filter_map(|entry| entry[*foreign_column_idx].non_null())
+			.collect::<Vec<_>>();
+		let mut used_counts = HashMap::new();
+		for entry_idx in 0..row_counts[*table_idx] {
+			let unused_values = available_values
+				.iter()
+				.filter(|value| {
+					max_uses
+						.is_none_or(|max_uses| used_counts.get(*value).unwrap_or(&0) < &max_uses)
+				})
+				.collect::<Vec<_>>();
+			// Rows which don't get a value stay NULL
+			if let Some(chosen_value) = unused_values.choose(&mut rng) {
+				*used_counts.entry(**chosen_value).or_insert(0) += 1;
+				all_deferred_values[*table_idx].push((
+					entry_idx,
+					*column_idx,
+					(**chosen_value).clone(),
+				));
+			}
+		}
+	}
+
+	let generated_tables = tables
+		.iter()
+		.zip(all_entries)
+		.zip(all_deferred_values)
+		.zip(value_guessess)
+		.map(|(((table, entries), deferred_values), guessess)| {
+			let mut column_order = (0..table.columns.len()).collect::<Vec<_>>();
+			column_order.sort_by_key(|column_idx| table.columns[*column_idx].ordinal);
+			let omitted_columns = column_order
+				.iter()
+				.enumerate()
+				.filter(|(_, column_idx)| {
+					is_omitted_from_insert(guessess.get(&table.columns[**column_idx].name))
+				})
+				.map(|(position, _)| position)
+				.collect();
+			GeneratedTable {
+				table: table.clone(),
+				columns: column_order
+					.iter()
+					.map(|column_idx| table.columns[*column_idx].clone())
+					.collect(),
+				rows: entries
+					.into_iter()
+					.map(|mut entry| {
+						column_order
+							.iter()
+							.map(|column_idx| mem::take(&mut entry[*column_idx]))
+							.collect()
+					})
+					.collect(),
+				deferred_values: deferred_values
+					.into_iter()
+					.map(|(entry_idx, column_idx, value)| {
+						let position = column_order
+							.iter()
+							.position(|idx| *idx == column_idx)
+							.unwrap();
+						(entry_idx, position, value)
+					})
+					.collect(),
+				omitted_columns,
+			}
+		})
+		.collect();
+
+	Ok(GeneratedData {
+		tables: generated_tables,
+		seed,
+		warnings,
+	})
+}
+
+fn format_value(value: &SQLValue, options: &SQLOptions) -> String {
+	match (value, options.dialect) {
+		(SQLValue::Null, _) => "NULL".into(),
+		// Only Postgres has a real boolean type, the others store them as numbers
+		(SQLValue::Bool(value), SQLDialect::Postgres) => {
+			if *value {
+				"TRUE".into()
+			} else {
+				"FALSE".into()
+			}
+		}
+		(SQLValue::Bool(value), _) => {
+			if *value {
+				"1".into()
+			} else {
+				"0".into()
+			}
+		}
+		(SQLValue::Int(_) | SQLValue::Float(_) | SQLValue::Decimal(..), _) => value.to_string(),
+		// Oracle does not convert strings to dates without a format, unless NLS settings
+		// happen to match
+		(SQLValue::Date(_), SQLDialect::Oracle) => format!(
+			"TO_DATE({}, 'YYYY-MM-DD')",
+			quote_string(&value.to_string(), options)
+		),
+		(SQLValue::Datetime(_), SQLDialect::Oracle) => format!(
+			"TO_TIMESTAMP({}, 'YYYY-MM-DD HH24:MI:SS')",
+			quote_string(&value.to_string(), options)
+		),
+		(
+			SQLValue::String(_) | SQLValue::Date(_) | SQLValue::Time(_) | SQLValue::Datetime(_),
+			_,
+		) => quote_string(&value.to_string(), options),
+	}
+}
+
+fn format_column_value(value: &SQLValue, column: &SQLColumn, options: &SQLOptions) -> String {
+	match (value, &column.sql_type) {
+		(SQLValue::String(str), SQLType::Char(size)) if options.pad_char_columns => {
+			let padded = format!("{:<width$}", str, width = *size as usize);
+			format_value(&SQLValue::String(padded), options)
+		}
+		_ => format_value(value, options),
+	}
+}
+
+// Row of a table whose columns are all assigned by the database. Only MySQL accepts an
+// empty column list, and only Oracle lacks `DEFAULT VALUES`.
+fn default_values_insert(table: &SQLTable, options: &SQLOptions) -> String {
+	let values = match options.dialect {
+		SQLDialect::MySQL => "() VALUES ()".to_string(),
+		SQLDialect::Oracle => format!(
+			"VALUES ({})",
+			vec!["DEFAULT"; table.columns.len()].join(", ")
+		),
+		SQLDialect::Postgres | SQLDialect::SQLite | SQLDialect::SQLServer => {
+			"DEFAULT VALUES".into()
+		}
+	};
+	format!(
+		"INSERT INTO {} {};\n",
+		format_table_name(table, options),
+		values
+	)
+}
+
+/// Multi-row `INSERT` statements for a single table, split by `SQLOptions::max_rows_per_insert`
+pub fn table_to_sql(generated_table: &GeneratedTable, options: &SQLOptions) -> String {
+	let table = &generated_table.table;
+	let is_included = |column_idx: &usize| !generated_table.omitted_columns.contains(column_idx);
+	let columns = generated_table
+		.columns
+		.iter()
+		.enumerate()
+		.filter(|(column_idx, _)| is_included(column_idx))
+		.map(|(_, column)| column)
+		.collect::<Vec<_>>();
+	if columns.is_empty() {
+		return default_values_insert(table, options).repeat(generated_table.rows.len());
+	}
+	let column_names = columns
+		.iter()
+		.map(|column| format_identifier(&column.name, options))
+		.collect::<Vec<_>>();
+
+	let mut header = vec![format!("INSERT INTO {}", format_table_name(table, options))];
+	if options.comments {
+		header.push(format!("{}(", INDENT));
+		for (i, column) in columns.iter().enumerate() {
+			let separator = if i + 1 < columns.len() { "," } else { "" };
+			let mut line = format!("{}{}{}{}", INDENT, INDENT, column_names[i], separator);
+			if let Some(name) = column.foreign_key.as_ref().and_then(|fk| fk.name.as_ref()) {
+				line.push_str(&format!(" -- {}", name));
+			}
+			header.push(line);
+		}
+		header.push(format!("{})", INDENT));
+	} else {
+		header.push(format!("{}({})", INDENT, column_names.join(", ")));
+	}
+	header.push("VALUES".into());
+	let header = header.join("\n");
+
+	let rows_per_insert = if options.insert_per_row {
+		1
+	} else {
+		options.max_rows_per_insert.max(1) as usize
+	};
+	generated_table
+		.rows
+		.chunks(rows_per_insert)
+		.map(|rows| {
+			let entries_str = rows
+				.iter()
+				.map(|row| {
+					let values = row
 						.iter()
-						.map(|entry| entry[*foreign_column_idx].as_str())
-						.collect();
+						.enumerate()
+						.filter(|(column_idx, _)| is_included(column_idx))
+						.map(|(_, value)| value)
+						.zip(&columns)
+						.map(|(value, column)| format_column_value(value, column, options))
+						.collect::<Vec<_>>();
+					format!("{}({})", INDENT, values.join(", "))
+				})
+				.collect::<Vec<_>>()
+				.join(",\n");
+			format!("{}\n{};\n", header, entries_str)
+		})
+		.collect::<Vec<_>>()
+		.join("\n")
+}
+
+/// Statements which go before the inserts, for starting a transaction, turning off foreign
+/// key checks and deleting existing rows
+pub fn sql_script_start(generated: &GeneratedData, options: &SQLOptions) -> String {
+	let insert_order = generated.insert_order();
+	let mut lines = vec![];
+	// Cycles broken by nullable foreign keys are filled in with `UPDATE`s after the inserts
+	let tables = generated
+		.tables
+		.iter()
+		.map(|generated_table| generated_table.table.clone())
+		.collect::<Vec<_>>();
+	let references_later_table = |table_idx: &usize| {
+		let position = insert_order.order.iter().position(|idx| idx == table_idx);
+		tables[*table_idx]
+			.columns
+			.iter()
+			.filter(|column| !column.nullable)
+			.filter_map(|column| column.foreign_key.as_ref())
+			.filter_map(|foreign_key| find_foreign_table(&tables, foreign_key))
+			.any(|(foreign_table_idx, _)| {
+				insert_order
+					.order
+					.iter()
+					.position(|idx| *idx == foreign_table_idx)
+					> position
+			})
+	};
+	if insert_order.cyclic.iter().any(references_later_table) {
+		let names = insert_order
+			.cyclic
+			.iter()
+			.map(|table_idx| generated.tables[*table_idx].name())
+			.collect::<Vec<_>>();
+		lines.push(format!("-- Foreign keys of {} form a cycle, so their rows are inserted in the original order and foreign key checks may need to be disabled", names.join(", ")));
+	}
+	// MySQL doesn't allow changing the foreign key checks inside of a transaction
+	if options.disable_foreign_key_checks && options.dialect == SQLDialect::MySQL {
+		lines.push("SET FOREIGN_KEY_CHECKS = 0;".into());
+	}
+	if options.transaction {
+		match options.dialect {
+			SQLDialect::MySQL => lines.push("START TRANSACTION;".into()),
+			SQLDialect::Postgres => lines.push("BEGIN;".into()),
+			SQLDialect::SQLite | SQLDialect::SQLServer => lines.push("BEGIN TRANSACTION;".into()),
+			// Oracle starts a transaction with the first statement
+			SQLDialect::Oracle => {}
+		}
+	}
+	if options.disable_foreign_key_checks {
+		match options.dialect {
+			SQLDialect::MySQL => {}
+			SQLDialect::Postgres => lines.push("SET CONSTRAINTS ALL DEFERRED;".into()),
+			// Turning foreign keys off with `PRAGMA foreign_keys` does nothing inside of a transaction
+			SQLDialect::SQLite => lines.push("PRAGMA defer_foreign_keys = ON;".into()),
+			SQLDialect::SQLServer => {
+				for generated_table in &generated.tables {
+					lines.push(format!(
+						"ALTER TABLE {} NOCHECK CONSTRAINT ALL;",
+						format_table_name(&generated_table.table, options)
+					));
 				}
+			}
+			SQLDialect::Oracle => lines.push("ALTER SESSION SET CONSTRAINTS = DEFERRED;".into()),
+		}
+	}
+	if options.cleanup {
+		// Tables referencing others are emptied first. TRUNCATE is not used, because it fails
+		// on referenced tables and commits the transaction in MySQL and Oracle.
+		for table_idx in insert_order.order.iter().rev() {
+			lines.push(format!(
+				"DELETE FROM {};",
+				format_table_name(&generated.tables[*table_idx].table, options)
+			));
+		}
+	}
+	lines.into_iter().map(|line| line + "\n").collect()
+}
+
+/// `UPDATE` statements which fill in foreign keys left NULL to break a cycle, rows are found
+/// by their primary key
+fn deferred_value_updates(generated_table: &GeneratedTable, options: &SQLOptions) -> Vec<String> {
+	if generated_table.deferred_values.is_empty() {
+		return vec![];
+	}
+	let primary_key = generated_table
+		.columns
+		.iter()
+		.enumerate()
+		.filter(|(_, column)| column.primary_key)
+		.map(|(column_idx, _)| column_idx)
+		.collect::<Vec<_>>();
+	if primary_key.is_empty() {
+		return vec![format!("-- {} has no primary key, so foreign keys left NULL to break a cycle can't be filled in", generated_table.name())];
+	}
+
+	let table_name = format_table_name(&generated_table.table, options);
+	generated_table
+		.deferred_values
+		.iter()
+		.map(|(row_idx, column_idx, value)| {
+			let row = &generated_table.rows[*row_idx];
+			let condition = primary_key
+				.iter()
+				.map(|key_idx| {
+					let column = &generated_table.columns[*key_idx];
+					format!(
+						"{} = {}",
+						format_identifier(&column.name, options),
+						format_column_value(&row[*key_idx], column, options)
+					)
+				})
+				.collect::<Vec<_>>();
+			format!(
+				"UPDATE {} SET {} = {} WHERE {};",
+				table_name,
+				format_identifier(&generated_table.columns[*column_idx].name, options),
+				format_column_value(value, &generated_table.columns[*column_idx], options),
+				condition.join(" AND ")
+			)
+		})
+		.collect()
+}
+
+/// Statements which go after the inserts, `UPDATE`s for foreign keys on a cycle and the
+/// counterpart of `sql_script_start`
+pub fn sql_script_end(generated: &GeneratedData, options: &SQLOptions) -> String {
+	let mut lines = vec![];
+	if options.deferred_updates {
+		for generated_table in &generated.tables {
+			lines.extend(deferred_value_updates(generated_table, options));
+		}
+	}
+	if options.disable_foreign_key_checks && options.dialect == SQLDialect::SQLServer {
+		for generated_table in &generated.tables {
+			lines.push(format!(
+				"ALTER TABLE {} WITH CHECK CHECK CONSTRAINT ALL;",
+				format_table_name(&generated_table.table, options)
+			));
+		}
+	}
+	if options.transaction {
+		lines.push("COMMIT;".into());
+	}
+	if options.disable_foreign_key_checks && options.dialect == SQLDialect::MySQL {
+		lines.push("SET FOREIGN_KEY_CHECKS = 1;".into());
+	}
+	lines.into_iter().map(|line| line + "\n").collect()
+}
+
+/// `INSERT` statements of every table with referenced tables first, tables without rows
+/// are left out
+pub fn to_sql(generated: &GeneratedData, options: &SQLOptions) -> String {
+	let start = sql_script_start(generated, options);
+	let end = sql_script_end(generated, options);
+	let inserts = generated
+		.insert_order()
+		.order
+		.into_iter()
+		.map(|table_idx| table_to_sql(&generated.tables[table_idx], options))
+		.filter(|sql| !sql.is_empty());
+
+	Some(start)
+		.into_iter()
+		.chain(inserts)
+		.chain(Some(end))
+		.filter(|sql| !sql.is_empty())
+		.collect::<Vec<_>>()
+		.join("\n")
+}
+
+fn is_omitted_from_insert(guess: Option<&SQLValueGuess>) -> bool {
+	matches!(
+		guess,
+		Some(SQLValueGuess::Int(SQLIntValueGuess::AutoIncrement {
+			omit_from_insert: true,
+			..
+		}))
+	)
+}
+
+// Foreign key columns with values given by the user are generated like any other column,
+// instead of copying values from the referenced rows
+fn overrides_foreign_key(guess: Option<&SQLValueGuess>) -> bool {
+	match guess {
+		Some(SQLValueGuess::Nullable(_, guess)) => overrides_foreign_key(Some(guess)),
+		Some(SQLValueGuess::Fixed(_) | SQLValueGuess::FromList(_)) => true,
+		_ => false,
+	}
+}
+
+// Returns `None` when the amount of distinct values is practically unlimited
+fn count_possible_values(guess: &SQLValueGuess) -> Option<u64> {
+	match guess {
+		SQLValueGuess::Int(SQLIntValueGuess::Range { min, max, step, .. }) => {
+			let step = step.unwrap_or(1).max(1) as u64;
+			Some((*max as i64 - *min as i64).unsigned_abs() / step + 1)
+		}
+		SQLValueGuess::Bool(SQLBoolValueGuess::Random) => Some(2),
+		SQLValueGuess::Bool(_) | SQLValueGuess::Fixed(_) => Some(1),
+		SQLValueGuess::FromList(values) => Some(values.iter().collect::<HashSet<_>>().len() as u64),
+		SQLValueGuess::String(_, SQLStringValueGuess::RandomEnum(options)) => {
+			Some(options.iter().filter(|(_, weight)| *weight > 0.0).count() as u64)
+		}
+		SQLValueGuess::String(_, SQLStringValueGuess::Empty) => Some(1),
+		// The check digit follows from the others
+		SQLValueGuess::String(max_size, SQLStringValueGuess::NationalId { digits, checksum }) => {
+			let digits = *digits as usize;
+			let random_digits = if checksum.is_some() && digits <= *max_size {
+				digits.saturating_sub(1)
+			} else {
+				digits.min(*max_size)
+			};
+			10u64.checked_pow(random_digits as u32)
+		}
+		// Past `u64::MAX` the amount is as good as unlimited
+		SQLValueGuess::String(max_size, SQLStringValueGuess::Pattern(pattern)) => pattern
+			.chars()
+			.take(*max_size)
+			.map(|c| match c {
+				'#' => 10,
+				'?' => 26,
+				'*' => 36,
+				_ => 1,
+			})
+			.try_fold(1u64, |total, count| total.checked_mul(count)),
+		SQLValueGuess::Time(SQLTimeValueGuess::Now) => Some(1),
+		SQLValueGuess::Time(SQLTimeValueGuess::TimeWindow {
+			from,
+			to,
+			step_minutes,
+		}) => {
+			let step = step_minutes
+				.filter(|step| *step > 0)
+				.map_or(1, |step| step as u64 * 60);
+			Some(window_seconds(*from, *to) as u64 / step + 1)
+		}
+		SQLValueGuess::Time(_) => Some(SECONDS_PER_DAY as u64),
+		// NULLs don't break unique constraints, so they don't count
+		SQLValueGuess::Nullable(_, guess) => count_possible_values(guess),
+		SQLValueGuess::Date(time_guess) | SQLValueGuess::Datetime(time_guess) => match time_guess {
+			SQLTimeValueGuess::Now => Some(1),
+			SQLTimeValueGuess::Future { min_days, max_days }
+			| SQLTimeValueGuess::Past { min_days, max_days } => {
+				Some(min_days.abs_diff(*max_days) as u64 + 1)
+			}
+			SQLTimeValueGuess::YearsAgo { min, max } => Some((min.abs_diff(*max) as u64 + 1) * 365),
+			SQLTimeValueGuess::Between(start, end) => {
+				Some((*end - *start).num_days().max(0) as u64 + 1)
+			}
+			SQLTimeValueGuess::BetweenDatetimes(start, end) => {
+				Some((end.date() - start.date()).num_days().max(0) as u64 + 1)
+			}
+			SQLTimeValueGuess::After { .. } => None,
+			SQLTimeValueGuess::TimeWindow { .. } => Some(1),
+		},
+		_ => None,
+	}
+}
+
+/// Order in which tables can be filled, from `sort_by_dependencies`
+#[derive(Debug, PartialEq, Clone)]
+pub struct DependencyOrder {
+	pub order: Vec<usize>,
+	/// Tables on a foreign key cycle, which could only be placed in their original order
+	pub cyclic: Vec<usize>,
+}
+
+fn is_on_cycle(dependencies: &[HashSet<usize>], placed: &[bool], table_idx: usize) -> bool {
+	let mut visited = HashSet::new();
+	let mut stack = vec![table_idx];
+	while let Some(idx) = stack.pop() {
+		for dependency in &dependencies[idx] {
+			if placed[*dependency] {
+				continue;
+			}
+			if *dependency == table_idx {
+				return true;
+			}
+			if visited.insert(*dependency) {
+				stack.push(*dependency);
+			}
+		}
+	}
+	false
+}
+
+/// Indices of tables ordered so that referenced tables come before the tables referencing
+/// them. Independent tables keep their order, and tables on a cycle are placed in their
+/// original order once nothing else can be placed.
+pub fn sort_by_dependencies(tables: &[Rc<SQLTable>]) -> DependencyOrder {
+	let dependencies = tables
+		.iter()
+		.enumerate()
+		.map(|(table_idx, table)| {
+			table
+				.columns
+				.iter()
+				.filter_map(|column| column.foreign_key.as_ref())
+				.filter_map(|foreign_key| find_foreign_table(tables, foreign_key))
+				.map(|(foreign_table_idx, _)| foreign_table_idx)
+				.filter(|foreign_table_idx| *foreign_table_idx != table_idx)
+				.collect::<HashSet<_>>()
+		})
+		.collect::<Vec<_>>();
+
+	let mut order = vec![];
+	let mut cyclic = vec![];
+	let mut placed = vec![false; tables.len()];
+	while order.len() < tables.len() {
+		let ready = (0..tables.len()).filter(|idx| !placed[*idx]).find(|idx| {
+			dependencies[*idx]
+				.iter()
+				.all(|dependency| placed[*dependency])
+		});
+		let next = match ready {
+			Some(idx) => idx,
+			None => {
+				let on_cycle = (0..tables.len())
+					.filter(|idx| !placed[*idx] && !cyclic.contains(idx))
+					.filter(|idx| is_on_cycle(&dependencies, &placed, *idx))
+					.collect::<Vec<_>>();
+				cyclic.extend(on_cycle);
+				// Tables which only depend on a cycle wait for it to be placed
+				(0..tables.len())
+					.find(|idx| !placed[*idx] && cyclic.contains(idx))
+					.or_else(|| (0..tables.len()).find(|idx| !placed[*idx]))
+					.unwrap()
+			}
+		};
+		placed[next] = true;
+		order.push(next);
+	}
+	cyclic.sort();
+	DependencyOrder { order, cyclic }
+}
+
+fn get_column_indices(table: &SQLTable, column_names: &[String]) -> Result<Vec<usize>> {
+	column_names
+		.iter()
+		.map(|name| {
+			table
+				.columns
+				.iter()
+				.position(|column| column.name.eq(name))
+				.ok_or_else(|| {
+					anyhow!(
+						"{}: unique constraint column {} does not exist",
+						table.qualified_name(),
+						name
+					)
+				})
+		})
+		.collect()
+}
+
+/// Lists the foreign keys which could not be filled in, following references between
+/// them to find out which of them form a cycle
+fn describe_unresolved_columns(
+	tables: &[Rc<SQLTable>],
+	all_foreign_columns: &[Vec<ForeignColumn>],
+	unresolved_columns: &BTreeSet<(usize, usize)>,
+	row_counts: &[usize],
+) -> String {
+	let column_name = |(table_idx, column_idx): (usize, usize)| {
+		format!(
+			"{}.{}",
+			tables[table_idx].qualified_name(),
+			tables[table_idx].columns[column_idx].name
+		)
+	};
+	let find_foreign_column = |(table_idx, column_idx): (usize, usize)| {
+		all_foreign_columns[table_idx]
+			.iter()
+			.find(|foreign_column| foreign_column.column_idx == column_idx)
+	};
+
+	let mut descriptions = vec![];
+	let mut described = HashSet::new();
+	for &start in unresolved_columns {
+		if described.contains(&start) {
+			continue;
+		}
+
+		let mut path = vec![start];
+		let mut is_cycle = false;
+		while let Some(foreign_column) = find_foreign_column(*path.last().unwrap()) {
+			let next = (
+				foreign_column.foreign_table_idx,
+				foreign_column.foreign_column_idx,
+			);
+			if next == start {
+				is_cycle = true;
+				break;
+			}
+			if path.contains(&next) || !unresolved_columns.contains(&next) {
+				break;
+			}
+			path.push(next);
+		}
+
+		if is_cycle {
+			described.extend(path.iter().copied());
+			let names = path
+				.iter()
+				.chain(Some(&start))
+				.map(|column| column_name(*column))
+				.collect::<Vec<_>>();
+			descriptions.push(format!("circular foreign keys {}", names.join(" → ")));
+			continue;
+		}
+
+		described.insert(start);
+		let ForeignColumn {
+			foreign_table_idx,
+			foreign_column_idx,
+			max_uses,
+			..
+		} = find_foreign_column(start).unwrap();
+		let mut description = format!(
+			"{} → {}",
+			column_name(start),
+			column_name((*foreign_table_idx, *foreign_column_idx))
+		);
+		let foreign_row_count = row_counts[*foreign_table_idx];
+		if foreign_row_count == 0 {
+			description.push_str(&format!(
+				" ({} has no rows)",
+				tables[*foreign_table_idx].qualified_name()
+			));
+		} else if let Some(max_uses) = max_uses {
+			if row_counts[start.0] > foreign_row_count * max_uses {
+				let times = if *max_uses == 1 {
+					"once".to_string()
+				} else {
+					format!("{} times", max_uses)
+				};
+				description.push_str(&format!(
+					" ({} rows, but each of the {} referenced rows can only be used {})",
+					row_counts[start.0], foreign_row_count, times
+				));
+			}
+		}
+		descriptions.push(description);
+	}
+
+	descriptions.join("; ")
+}
+
+// Values given by the user are never cut, as a cut enum value would break its check
+// constraint, and a cut fixed value is not what was asked for
+fn check_values_fit<G: Deref<Target = HashMap<String, SQLValueGuess>>>(
+	tables: &[Rc<SQLTable>],
+	value_guessess: &[G],
+) -> Result<()> {
+	for (table, guessess) in tables.iter().zip(value_guessess) {
+		if table.literals.is_some() {
+			continue;
+		}
+
+		for column in &table.columns {
+			let column_size = match column.sql_type {
+				SQLType::Char(size) => Some(size as usize),
+				SQLType::Varchar(size) => Some(size as usize),
+				_ => None,
+			};
+			let mut guess = guessess.get(&column.name);
+			if let Some(SQLValueGuess::Nullable(_, inner)) = guess {
+				guess = Some(inner);
+			}
+			if let Some(SQLValueGuess::String(
+				_,
+				SQLStringValueGuess::FullNameFromColumns { first, last },
+			)) = guess
+			{
+				if let Some(missing) = [first, last].into_iter().find(|name| {
+					!table
+						.columns
+						.iter()
+						.any(|column| names_match(&column.name, name))
+				}) {
+					bail!(
+						"{}.{}: name column {} does not exist",
+						table.qualified_name(),
+						column.name,
+						missing
+					);
+				}
+				continue;
+			}
+			if let Some(
+				SQLValueGuess::Date(time_guess)
+				| SQLValueGuess::Time(time_guess)
+				| SQLValueGuess::Datetime(time_guess),
+			) = guess
+			{
+				if let Some(problem) = check_time_range(time_guess) {
+					bail!("{}.{}: {}", table.qualified_name(), column.name, problem);
+				}
+				continue;
+			}
+			let (max_size, values) = match (guess, column_size) {
+				(
+					Some(SQLValueGuess::String(max_size, SQLStringValueGuess::RandomEnum(options))),
+					_,
+				) => (
+					*max_size,
+					options
+						.iter()
+						.map(|(variant, _)| variant)
+						.collect::<Vec<_>>(),
+				),
+				(Some(SQLValueGuess::Fixed(value)), Some(size)) => (size, vec![value]),
+				(Some(SQLValueGuess::FromList(values)), Some(size)) => {
+					(size, values.iter().collect())
+				}
+				_ => continue,
+			};
+			if let Some(value) = values.iter().find(|value| value.chars().count() > max_size) {
+				bail!(
+					"{}.{}: value '{}' is longer than {} characters, change the value or the column size",
+					table.qualified_name(), column.name, value, max_size
+				);
+			}
+		}
+	}
+	Ok(())
+}
+
+fn string_guess(guess: &SQLValueGuess) -> Option<(usize, &SQLStringValueGuess)> {
+	match guess {
+		SQLValueGuess::Nullable(_, guess) => string_guess(guess),
+		SQLValueGuess::String(max_size, guess) => Some((*max_size, guess)),
+		_ => None,
+	}
+}
+
+// Values made from other columns of the same row, `None` when the guess isn't one of them
+// or the other columns don't have anything to use
+fn derive_row_value(
+	rng: &mut impl Rng,
+	table: &SQLTable,
+	guess: &SQLValueGuess,
+	entry: &[SQLValue],
+) -> Option<SQLValue> {
+	// Non-empty values of the named columns in the row
+	let names = |columns: &[&str]| {
+		columns
+			.iter()
+			.filter_map(|name| {
+				let column_idx = table
+					.columns
+					.iter()
+					.position(|column| names_match(&column.name, name))?;
+				match &entry[column_idx] {
+					SQLValue::String(value) if !value.is_empty() => Some(value.as_str()),
+					_ => None,
+				}
+			})
+			.collect::<Vec<_>>()
+	};
+
+	let (max_size, string_guess) = string_guess(guess)?;
+	match string_guess {
+		SQLStringValueGuess::EmailFromName => {
+			let (first_name, last_name) = find_name_columns(table);
+			let columns = [first_name, last_name]
+				.into_iter()
+				.flatten()
+				.map(|column| column.name.as_str())
+				.collect::<Vec<_>>();
+			let parts = names(&columns)
+				.into_iter()
+				.map(|name| {
+					name.to_lowercase()
+						.chars()
+						.filter(char::is_ascii_alphanumeric)
+						.collect::<String>()
+				})
+				.filter(|part| !part.is_empty())
+				.collect::<Vec<_>>();
+			if parts.is_empty() {
+				return None;
+			}
+			let mut email = format!(
+				"{}{}@{}",
+				parts.join("."),
+				rng.gen_range(1..100),
+				EMAIL_DOMAIN
+			);
+			truncate_chars(&mut email, max_size);
+			Some(SQLValue::String(email))
+		}
+		SQLStringValueGuess::FullNameFromColumns { first, last } => {
+			let parts = names(&[first, last]);
+			if parts.is_empty() {
+				return None;
+			}
+			let mut full_name = parts.join(" ");
+			truncate_words(&mut full_name, max_size);
+			Some(SQLValue::String(full_name))
+		}
+		_ => None,
+	}
+}
+
+// Fills in values made from other columns, once the whole row is generated
+fn apply_row_values<G: Deref<Target = HashMap<String, SQLValueGuess>>>(
+	rng: &mut impl Rng,
+	tables: &[Rc<SQLTable>],
+	value_guessess: &[G],
+	all_entries: &mut [Vec<Vec<SQLValue>>],
+) {
+	for (table_idx, table) in tables.iter().enumerate() {
+		if table.literals.is_some() {
+			continue;
+		}
+		for (column_idx, column) in table.columns.iter().enumerate() {
+			let Some(guess) = value_guessess[table_idx].get(&column.name) else {
+				continue;
+			};
+			for entry in all_entries[table_idx].iter_mut() {
+				if entry[column_idx] == SQLValue::Null {
+					continue;
+				}
+				if let Some(value) = derive_row_value(rng, table, guess, entry) {
+					entry[column_idx] = value;
+				}
+			}
+		}
+	}
+}
+
+fn time_guess(guess: &SQLValueGuess) -> Option<&SQLTimeValueGuess> {
+	match guess {
+		SQLValueGuess::Nullable(_, guess) => time_guess(guess),
+		SQLValueGuess::Date(guess)
+		| SQLValueGuess::Time(guess)
+		| SQLValueGuess::Datetime(guess) => Some(guess),
+		_ => None,
+	}
+}
+
+fn to_datetime(value: &SQLValue) -> Option<NaiveDateTime> {
+	match value {
+		SQLValue::Date(date) => date.and_hms_opt(0, 0, 0),
+		SQLValue::Datetime(datetime) => Some(*datetime),
+		SQLValue::Time(time) => Some(Local::now().date_naive().and_time(*time)),
+		_ => None,
+	}
+}
+
+// Values of `SQLTimeValueGuess::After` columns are offsets from the other column of the same
+// row, so columns following another such column wait until it is filled in
+fn apply_time_offsets<G: Deref<Target = HashMap<String, SQLValueGuess>>>(
+	rng: &mut impl Rng,
+	tables: &[Rc<SQLTable>],
+	value_guessess: &[G],
+	all_entries: &mut [Vec<Vec<SQLValue>>],
+) -> Result<()> {
+	for (table_idx, table) in tables.iter().enumerate() {
+		if table.literals.is_some() {
+			continue;
+		}
+
+		let mut pending = vec![];
+		for (column_idx, column) in table.columns.iter().enumerate() {
+			let guess = value_guessess[table_idx]
+				.get(&column.name)
+				.and_then(time_guess);
+			let Some(SQLTimeValueGuess::After {
+				column: base_column,
+				min_days,
+				max_days,
+			}) = guess
+			else {
+				continue;
+			};
+			let base_idx = table
+				.columns
+				.iter()
+				.position(|other| names_match(&other.name, base_column))
+				.ok_or_else(|| {
+					anyhow!(
+						"{}.{} is generated after column {} which does not exist",
+						table.qualified_name(),
+						column.name,
+						base_column
+					)
+				})?;
+			pending.push((
+				column_idx,
+				base_idx,
+				*min_days.min(max_days),
+				*min_days.max(max_days),
+			));
+		}
+
+		while !pending.is_empty() {
+			let ready = pending
+				.iter()
+				.position(|(_, base_idx, ..)| {
+					!pending
+						.iter()
+						.any(|(column_idx, ..)| column_idx == base_idx)
+				})
+				.ok_or_else(|| {
+					anyhow!(
+						"{}: columns which are generated after each other form a cycle",
+						table.qualified_name()
+					)
+				})?;
+			let (column_idx, base_idx, min_days, max_days) = pending.swap_remove(ready);
+			for entry in all_entries[table_idx].iter_mut() {
+				// NULLs from nullable guesses stay NULL, and there is nothing to follow after a NULL
+				let Some(base) = to_datetime(&entry[base_idx]) else {
+					continue;
+				};
+				let seconds = rng.gen_range(
+					min_days as i64 * SECONDS_PER_DAY..=max_days as i64 * SECONDS_PER_DAY,
+				);
+				let datetime = base + Duration::seconds(seconds);
+				entry[column_idx] = match entry[column_idx] {
+					SQLValue::Null => continue,
+					SQLValue::Date(_) => SQLValue::Date(datetime.date()),
+					SQLValue::Time(_) => SQLValue::Time(datetime.time()),
+					_ => SQLValue::Datetime(datetime),
+				};
+			}
+		}
+	}
+	Ok(())
+}
+
+fn check_unique_constraints_possible<G: Deref<Target = HashMap<String, SQLValueGuess>>>(
+	tables: &[Rc<SQLTable>],
+	value_guessess: &[G],
+	all_foreign_columns: &[Vec<ForeignColumn>],
+	row_counts: &[usize],
+) -> Result<()> {
+	for (table_idx, table) in tables.iter().enumerate() {
+		if table.literals.is_some() {
+			continue;
+		}
+
+		for constraint in &table.unique_column_sets() {
+			let mut combinations = Some(1u64);
+			for column_idx in get_column_indices(table, constraint)? {
+				let foreign_column = all_foreign_columns[table_idx]
+					.iter()
+					.find(|foreign_column| foreign_column.column_idx == column_idx);
+				let possible_values = match foreign_column {
+					Some(foreign_column) => {
+						Some(row_counts[foreign_column.foreign_table_idx] as u64)
+					}
+					None => value_guessess[table_idx]
+						.get(&table.columns[column_idx].name)
+						.and_then(count_possible_values),
+				};
+				combinations = match (combinations, possible_values) {
+					(Some(combinations), Some(possible_values)) => {
+						Some(combinations.saturating_mul(possible_values))
+					}
+					_ => None,
+				};
+			}
+
+			if let Some(combinations) = combinations {
+				if combinations < row_counts[table_idx] as u64 && constraint.len() == 1 {
+					bail!(
+						"{}.{}: values have to be unique, but only {} different values can be generated for {} rows",
+						table.qualified_name(), constraint[0], combinations, row_counts[table_idx]
+					);
+				} else if combinations < row_counts[table_idx] as u64 {
+					bail!(
+						"{}: only {} unique combinations of ({}) are possible, but {} rows were requested",
+						table.qualified_name(), combinations, constraint.join(", "), row_counts[table_idx]
+					);
+				}
+			}
+		}
+	}
+
+	Ok(())
+}
+
+// Regenerates colliding values of columns which aren't foreign keys or auto incremented.
+// NULLs are never equal in SQL, so rows with a NULL member can't collide.
+fn enforce_unique_constraints<G: Deref<Target = HashMap<String, SQLValueGuess>>>(
+	rng: &mut impl Rng,
+	tables: &[Rc<SQLTable>],
+	value_guessess: &[G],
+	all_entries: &mut [Vec<Vec<SQLValue>>],
+	with_foreign_keys: bool,
+) -> Result<()> {
+	for (table_idx, table) in tables.iter().enumerate() {
+		if table.literals.is_some() {
+			continue;
+		}
+
+		for constraint in &table.unique_column_sets() {
+			let column_idxs = get_column_indices(table, constraint)?;
+			let has_foreign_keys = column_idxs
+				.iter()
+				.any(|idx| table.columns[*idx].foreign_key.is_some());
+			if has_foreign_keys != with_foreign_keys {
+				continue;
+			}
+
+			let regenerated_columns = column_idxs
+				.iter()
+				.filter(|idx| table.columns[**idx].foreign_key.is_none())
+				.filter_map(|idx| {
+					let guess = value_guessess[table_idx].get(&table.columns[*idx].name)?;
+					let is_regenerated =
+						!matches!(
+							guess,
+							SQLValueGuess::Int(SQLIntValueGuess::AutoIncrement { .. })
+						) && !matches!(time_guess(guess), Some(SQLTimeValueGuess::After { .. }));
+					is_regenerated.then_some((*idx, guess))
+				})
+				.collect::<Vec<_>>();
+
+			let mut used_combinations = HashSet::new();
+			for entry in all_entries[table_idx].iter_mut() {
+				let mut retries = 0;
+				loop {
+					if column_idxs.iter().any(|idx| entry[*idx] == SQLValue::Null) {
+						break;
+					}
+					let combination = column_idxs
+						.iter()
+						.map(|idx| entry[*idx].clone())
+						.collect::<Vec<_>>();
+					if used_combinations.insert(combination) {
+						break;
+					}
+
+					if regenerated_columns.is_empty() || retries == UNIQUE_RETRIES {
+						bail!(
+							"{}: failed to generate unique values for ({}), try generating less rows",
+							table.qualified_name(), constraint.join(", ")
+						);
+					}
+					retries += 1;
+					for (column_idx, guess) in &regenerated_columns {
+						let mut value =
+							generate_value(rng, &table.columns[*column_idx], guess, &mut 0);
+						if value != SQLValue::Null {
+							value = derive_row_value(rng, table, guess, entry).unwrap_or(value);
+						}
+						entry[*column_idx] = value;
+					}
+				}
+			}
+		}
+	}
+
+	Ok(())
+}
+
+pub(crate) fn format_identifier(name: &str, options: &SQLOptions) -> String {
+	if !options.quote_identifiers && !is_reserved_word(name, options.dialect) {
+		return name.to_string();
+	}
+	match options.dialect {
+		SQLDialect::MySQL => format!("`{}`", name.replace('`', "``")),
+		SQLDialect::SQLServer => format!("[{}]", name.replace(']', "]]")),
+		SQLDialect::Postgres | SQLDialect::SQLite | SQLDialect::Oracle => {
+			format!("\"{}\"", name.replace('"', "\"\""))
+		}
+	}
+}
+
+pub(crate) fn format_table_name(table: &SQLTable, options: &SQLOptions) -> String {
+	match &table.schema {
+		Some(schema) => format!(
+			"{}.{}",
+			format_identifier(schema, options),
+			format_identifier(&table.name, options)
+		),
+		None => format_identifier(&table.name, options),
+	}
+}
+
+pub(crate) fn quote_string(value: &str, options: &SQLOptions) -> String {
+	let escaped = value.replace('\'', "''");
+	match options.dialect {
+		// Backslashes start escape sequences in MySQL strings
+		SQLDialect::MySQL => format!("'{}'", escaped.replace('\\', "\\\\")),
+		// Without the N prefix characters outside of the database code page are lost
+		SQLDialect::SQLServer => format!("N'{}'", escaped),
+		SQLDialect::Postgres | SQLDialect::SQLite | SQLDialect::Oracle => format!("'{}'", escaped),
+	}
+}
+
+// Cuts at the last space which fits, unless the first word is already too long
+fn truncate_words(str: &mut String, max_chars: usize) {
+	if str.chars().count() <= max_chars {
+		return;
+	}
+	let fitting = str.chars().take(max_chars + 1).collect::<String>();
+	match fitting.trim_end().rfind(' ') {
+		Some(space_idx) if space_idx > 0 => {
+			str.truncate(space_idx);
+			// Don't leave a dangling comma from addresses
+			str.truncate(str.trim_end_matches([' ', ',']).len());
+		}
+		_ => truncate_chars(str, max_chars),
+	}
+}
+
+// Characters which are drawn together with the one before them, like accents written as
+// combining marks, emoji skin tones or the parts of emoji joined with a zero width joiner
+fn is_grapheme_extension(c: char) -> bool {
+	matches!(c,
+		'\u{0300}'..='\u{036F}' | '\u{1AB0}'..='\u{1AFF}' | '\u{1DC0}'..='\u{1DFF}' |
+		'\u{20D0}'..='\u{20FF}' | '\u{FE20}'..='\u{FE2F}' | '\u{FE00}'..='\u{FE0F}' |
+		'\u{200D}' | '\u{1F3FB}'..='\u{1F3FF}' | '\u{E0020}'..='\u{E007F}'
+	)
+}
+
+// Sizes of CHAR and VARCHAR columns are in characters, and cutting at a byte index could
+// land inside of a multi-byte character. The cut is also moved back so that it doesn't
+// separate a character from its accent or split up an emoji.
+fn truncate_chars(str: &mut String, max_chars: usize) {
+	let Some((mut byte_idx, mut next_char)) = str.char_indices().nth(max_chars) else {
+		return;
+	};
+	while let Some(previous_char) = str[..byte_idx].chars().next_back() {
+		if !is_grapheme_extension(next_char) && previous_char != '\u{200D}' {
+			break;
+		}
+		byte_idx -= previous_char.len_utf8();
+		next_char = previous_char;
+	}
+	str.truncate(byte_idx);
+}
+
+/// Reads a fixed value the way it would be written in SQL, like `42`, `true` or `2020-01-31`.
+/// Returns why the value doesn't fit the column, if it doesn't.
+pub fn parse_fixed_value(column: &SQLColumn, value: &str) -> Result<SQLValue, String> {
+	if value.trim().eq_ignore_ascii_case("NULL") {
+		return if column.nullable {
+			Ok(SQLValue::Null)
+		} else {
+			Err(format!("{} is not nullable", column.name))
+		};
+	}
+
+	let trimmed = value.trim();
+	match column.sql_type {
+		SQLType::Int => trimmed
+			.parse()
+			.map(SQLValue::Int)
+			.map_err(|_| format!("'{}' is not a whole number", value)),
+		SQLType::Float | SQLType::Decimal(_) => trimmed
+			.parse()
+			.map(SQLValue::Float)
+			.map_err(|_| format!("'{}' is not a number", value)),
+		SQLType::Bool => match trimmed.to_lowercase().as_str() {
+			"true" | "1" => Ok(SQLValue::Bool(true)),
+			"false" | "0" => Ok(SQLValue::Bool(false)),
+			_ => Err(format!("'{}' is not a boolean", value)),
+		},
+		SQLType::Date => NaiveDate::parse_from_str(trimmed, "%Y-%m-%d")
+			.map(SQLValue::Date)
+			.map_err(|_| format!("'{}' is not a date like 2020-01-31", value)),
+		SQLType::Time => NaiveTime::parse_from_str(trimmed, "%H:%M:%S")
+			.map(SQLValue::Time)
+			.map_err(|_| format!("'{}' is not a time like 13:45:00", value)),
+		SQLType::Datetime => NaiveDateTime::parse_from_str(trimmed, "%Y-%m-%d %H:%M:%S")
+			.or_else(|_| NaiveDateTime::parse_from_str(trimmed, "%Y-%m-%dT%H:%M:%S"))
+			.map(SQLValue::Datetime)
+			.map_err(|_| {
+				format!(
+					"'{}' is not a date and time like 2020-01-31 13:45:00",
+					value
+				)
+			}),
+		SQLType::Char(size) if value.chars().count() > size as usize => {
+			Err(format!("'{}' is longer than {} characters", value, size))
+		}
+		SQLType::Varchar(size) if value.chars().count() > size as usize => {
+			Err(format!("'{}' is longer than {} characters", value, size))
+		}
+		SQLType::Char(_) | SQLType::Varchar(_) | SQLType::Text => {
+			Ok(SQLValue::String(value.to_string()))
+		}
+	}
+}
+
+/// Splits pasted values on new lines, or on commas when they are all on one line
+pub fn parse_value_list(text: &str) -> Vec<String> {
+	let separator = if text.trim().contains('\n') {
+		'\n'
+	} else {
+		','
+	};
+	text.split(separator)
+		.map(|value| value.trim())
+		.filter(|value| !value.is_empty())
+		.map(|value| value.to_string())
+		.collect()
+}
+
+fn generate_password_hash(rng: &mut impl Rng, max_size: usize) -> String {
+	const BCRYPT_PREFIX: &str = "$2b$10$";
+	const BCRYPT_LENGTH: usize = 60;
+	const BCRYPT_ALPHABET: &[u8] =
+		b"./ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789";
+	const HEX_DIGITS: &[u8] = b"0123456789abcdef";
+
+	if max_size >= BCRYPT_LENGTH {
+		let salt_and_hash = (BCRYPT_PREFIX.len()..BCRYPT_LENGTH)
+			.map(|_| *BCRYPT_ALPHABET.choose(rng).unwrap() as char)
+			.collect::<String>();
+		format!("{}{}", BCRYPT_PREFIX, salt_and_hash)
+	} else {
+		(0..64)
+			.map(|_| *HEX_DIGITS.choose(rng).unwrap() as char)
+			.collect()
+	}
+}
+
+const COLOR_NAMES: &[&str] = &[
+	"black",
+	"white",
+	"red",
+	"green",
+	"blue",
+	"yellow",
+	"orange",
+	"purple",
+	"pink",
+	"brown",
+	"gray",
+	"cyan",
+	"magenta",
+	"lime",
+	"navy",
+	"teal",
+	"olive",
+	"maroon",
+	"silver",
+	"gold",
+	"beige",
+	"coral",
+	"indigo",
+	"violet",
+	"turquoise",
+	"salmon",
+	"crimson",
+	"khaki",
+	"lavender",
+];
+
+const FILE_EXTENSIONS: &[&str] = &[
+	"pdf", "txt", "csv", "png", "jpg", "docx", "xlsx", "zip", "json",
+];
+
+fn generate_file_name(rng: &mut impl Rng) -> String {
+	let word: String = Word().fake_with_rng(rng);
+	format!(
+		"{}.{}",
+		word.to_lowercase(),
+		FILE_EXTENSIONS.choose(rng).unwrap()
+	)
+}
+
+// Directories are dropped from the start when the path is too long, so none of them
+// end up cut in half
+fn generate_file_path(rng: &mut impl Rng, max_size: usize) -> String {
+	let mut segments = (0..rng.gen_range(2..=4))
+		.map(|_| Word().fake_with_rng::<String, _>(rng).to_lowercase())
+		.collect::<Vec<_>>();
+	segments.push(generate_file_name(rng));
+
+	let mut path = format!("/{}", segments.join("/"));
+	while path.chars().count() > max_size && segments.len() > 1 {
+		segments.remove(0);
+		path = format!("/{}", segments.join("/"));
+	}
+	path
+}
+
+fn capitalize(text: &str) -> String {
+	let mut chars = text.chars();
+	match chars.next() {
+		Some(first) => first.to_uppercase().chain(chars).collect(),
+		None => String::new(),
+	}
+}
+
+fn generate_lorem(rng: &mut impl Rng, guess: &SQLLoremValueGuess, max_size: usize) -> String {
+	let mut text = match guess {
+		SQLLoremValueGuess::Words(min, max) => {
+			let words: Vec<String> = Words(*min.min(max)..max + 1).fake_with_rng(rng);
+			words.join(" ")
+		}
+		SQLLoremValueGuess::Sentences(min, max) => {
+			let word_count = rng.gen_range(*min.min(max)..=*max);
+			let mut sentences = vec![];
+			let mut current_count = 0;
+			while current_count < word_count {
+				let sentence: String = Sentence(4..10).fake_with_rng(rng);
+				current_count += sentence.split_whitespace().count();
+				sentences.push(capitalize(&sentence));
+			}
+			sentences.join(" ")
+		}
+		SQLLoremValueGuess::Paragraph => {
+			let sentences: Vec<String> = Sentences(3..6).fake_with_rng(rng);
+			sentences
+				.iter()
+				.map(|sentence| capitalize(sentence))
+				.collect::<Vec<_>>()
+				.join(" ")
+		}
+	};
+
+	truncate_words(&mut text, max_size);
+	text
+}
+
+fn generate_address_line(rng: &mut impl Rng) -> String {
+	let building_number: String = BuildingNumber().fake_with_rng(rng);
+	let street: String = StreetName().fake_with_rng(rng);
+	if rng.gen_bool(0.3) {
+		let apartment: String = SecondaryAddress().fake_with_rng(rng);
+		format!("{} {}, {}", building_number, street, apartment)
+	} else {
+		format!("{} {}", building_number, street)
+	}
+}
+
+fn random_digits(rng: &mut impl Rng, count: usize) -> Vec<u32> {
+	(0..count).map(|_| rng.gen_range(0..10)).collect()
+}
+
+fn luhn_check_digit(digits: &[u32]) -> u32 {
+	// Every second digit is doubled, starting from the one next to the check digit
+	let sum = digits
+		.iter()
+		.rev()
+		.enumerate()
+		.map(|(i, digit)| match (i % 2 == 0, digit * 2) {
+			(true, doubled) if doubled > 9 => doubled - 9,
+			(true, doubled) => doubled,
+			(false, _) => *digit,
+		})
+		.sum::<u32>();
+	(10 - sum % 10) % 10
+}
+
+fn mod11_check_digit(digits: &[u32]) -> u32 {
+	let weighted_sum = |first_weight: u32| -> u32 {
+		digits
+			.iter()
+			.enumerate()
+			.map(|(i, digit)| digit * ((first_weight - 1 + i as u32) % 9 + 1))
+			.sum::<u32>()
+			% 11
+	};
+	// A second set of weights is tried when the first gives 10, and 0 is used if both do
+	match weighted_sum(1) {
+		10 => match weighted_sum(3) {
+			10 => 0,
+			remainder => remainder,
+		},
+		remainder => remainder,
+	}
+}
+
+fn digits_to_string(digits: &[u32]) -> String {
+	digits
+		.iter()
+		.map(|digit| char::from_digit(*digit, 10).unwrap())
+		.collect()
+}
+
+fn generate_credit_card_number(rng: &mut impl Rng) -> String {
+	// Starts with 4 like Visa cards, the last digit is the check digit
+	let mut digits = vec![4];
+	digits.extend(random_digits(rng, 14));
+	digits.push(luhn_check_digit(&digits));
+	digits_to_string(&digits)
+}
+
+fn generate_national_id(
+	rng: &mut impl Rng,
+	digits: usize,
+	checksum: Option<&SQLChecksum>,
+) -> String {
+	let Some(checksum) = checksum else {
+		return digits_to_string(&random_digits(rng, digits));
+	};
+	let mut digits = random_digits(rng, digits.saturating_sub(1));
+	let check_digit = match checksum {
+		SQLChecksum::Luhn => luhn_check_digit(&digits),
+		SQLChecksum::Mod11 => mod11_check_digit(&digits),
+	};
+	digits.push(check_digit);
+	digits_to_string(&digits)
+}
 
-				let used_values = all_entries[*table_idx].iter()
-					.enumerate()
-					.filter(|(entry_idx, _)| entries_with_foreign_keys_copy.contains(&(*table_idx, *entry_idx)))
-					.map(|(_, entry)| entry[*column_idx].as_str())
-					.collect::<HashSet<_>>();
+/// Countries which can be picked for IBANs, with the amount of digits after the check digits.
+/// Only ones with all digit account numbers are included.
+pub const IBAN_COUNTRIES: [(&str, usize); 8] = [
+	("AT", 16),
+	("BE", 12),
+	("DE", 18),
+	("DK", 14),
+	("ES", 20),
+	("FI", 14),
+	("LT", 16),
+	("PL", 24),
+];
 
-				available_values.retain(|value| !used_values.contains(value));
+fn generate_iban(rng: &mut impl Rng, country: Option<&str>) -> String {
+	let (country, length) = country
+		.and_then(|country| {
+			IBAN_COUNTRIES
+				.iter()
+				.find(|(code, _)| code.eq_ignore_ascii_case(country))
+		})
+		.unwrap_or_else(|| IBAN_COUNTRIES.choose(rng).unwrap());
+	let account = digits_to_string(&random_digits(rng, *length));
 
-				if let Some(chosen_value) = available_values.choose(&mut rng) {
-					all_entries[*table_idx][*entry_idx][*column_idx] = chosen_value.to_string();
-				} else {
-					// Early break, thre are no currently available options
-					// Try next time
-					return true;
-				}
-			}
+	// ISO 13616: the account, country and "00" read as a number, with letters as 10..35
+	let rearranged = format!("{}{}00", account, country);
+	let remainder =
+		rearranged
+			.chars()
+			.map(|c| c.to_digit(36).unwrap())
+			.fold(0, |remainder, value| {
+				let shift = if value > 9 { 100 } else { 10 };
+				(remainder * shift + value) % 97
+			});
+	format!("{}{:02}{}", country, 98 - remainder, account)
+}
 
-			false
-		});
+fn expand_pattern(rng: &mut impl Rng, pattern: &str) -> String {
+	const DIGITS: &[u8] = b"0123456789";
+	const LETTERS: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+	const ALPHANUMERIC: &[u8] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ";
 
-		// This is to stop infnite loop, where during each iteration nothing gets removed
-		if before_retain == entries_with_foreign_keys.len() {
-			bail!("Failed to resolve foreign keys")
-		}
-	}
+	pattern
+		.chars()
+		.map(|c| match c {
+			'#' => *DIGITS.choose(rng).unwrap() as char,
+			'?' => *LETTERS.choose(rng).unwrap() as char,
+			'*' => *ALPHANUMERIC.choose(rng).unwrap() as char,
+			c => c,
+		})
+		.collect()
+}
 
-	for (i, table) in tables.iter().enumerate() {
-		let mut column_names = vec![];
-		for column in &table.columns {
-			column_names.push(column.name.as_str());
+// Picks a value between `min` and `max`, which must not be inverted
+fn sample_distribution(
+	rng: &mut impl Rng,
+	distribution: &SQLDistribution,
+	min: f64,
+	max: f64,
+) -> f64 {
+	match distribution {
+		SQLDistribution::Uniform if min < max => rng.gen_range(min..max),
+		SQLDistribution::Uniform => min,
+		SQLDistribution::Normal { mean, stddev } => {
+			// Box-Muller transform, 1 - u1 keeps the logarithm away from 0
+			let u1: f64 = rng.gen();
+			let u2: f64 = rng.gen();
+			let standard =
+				(-2.0 * (1.0 - u1).ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos();
+			(mean + standard * stddev.abs()).clamp(min, max)
 		}
-
-		let entries = &all_entries[i];
-		lines.push(format!("INSERT INTO {}", table.name));
-		lines.push(format!("{}({})", INDENT, column_names.join(", ")));
-		lines.push("VALUES".into());
-		let entries_str = entries
-			.iter()
-			.map(|entry| format!("{}({})", INDENT, entry.join(", ")))
-			.collect::<Vec<_>>()
-			.join(",\n");
-		lines.push(format!("{};\n", entries_str));
 	}
-
-	Ok(lines.join("\n"))
 }
 
-fn generate_time_value(rng: &mut ThreadRng, guess: &SQLTimeValueGuess) -> NaiveDateTime {
+fn generate_time_value(rng: &mut impl Rng, guess: &SQLTimeValueGuess) -> NaiveDateTime {
 	let now = Local::now().naive_local();
 
 	match guess {
 		SQLTimeValueGuess::Now => now,
-		SQLTimeValueGuess::Future => {
-			let days = rng.gen_range(1..=30);
-			now.checked_add_days(Days::new(days)).unwrap()
+		// Offsets are picked down to the second, so that rows don't share the time of day
+		SQLTimeValueGuess::Future { min_days, max_days } => {
+			let (min, max) = (
+				*min_days.min(max_days) as i64,
+				*min_days.max(max_days) as i64,
+			);
+			now + Duration::seconds(rng.gen_range(min * SECONDS_PER_DAY..=max * SECONDS_PER_DAY))
 		}
-		SQLTimeValueGuess::Past => {
-			let days = rng.gen_range(7..=365);
-			now.checked_sub_days(Days::new(days)).unwrap()
+		SQLTimeValueGuess::Past { min_days, max_days } => {
+			let (min, max) = (
+				*min_days.min(max_days) as i64,
+				*min_days.max(max_days) as i64,
+			);
+			now - Duration::seconds(rng.gen_range(min * SECONDS_PER_DAY..=max * SECONDS_PER_DAY))
+		}
+		// Someone `max` years old can be born up to a day short of `max + 1` years ago
+		SQLTimeValueGuess::YearsAgo { min, max } => {
+			let (min, max) = (*min.min(max), *min.max(max));
+			let latest = now.checked_sub_months(Months::new(min * 12)).unwrap_or(now);
+			let earliest = now
+				.checked_sub_months(Months::new((max + 1) * 12))
+				.unwrap_or(latest);
+			let seconds = (latest - earliest).num_seconds().max(1);
+			latest - Duration::seconds(rng.gen_range(0..seconds))
+		}
+		SQLTimeValueGuess::Between(start, end) => {
+			let start = start.and_hms_opt(0, 0, 0).unwrap();
+			let end = end.and_hms_opt(23, 59, 59).unwrap();
+			random_datetime_between(rng, start, end)
+		}
+		SQLTimeValueGuess::BetweenDatetimes(start, end) => {
+			random_datetime_between(rng, *start, *end)
+		}
+		// Replaced in `apply_time_offsets`, once the other column is generated
+		SQLTimeValueGuess::After { .. } => now,
+		SQLTimeValueGuess::TimeWindow {
+			from,
+			to,
+			step_minutes,
+		} => {
+			let step = step_minutes
+				.filter(|step| *step > 0)
+				.map_or(1, |step| step as i64 * 60);
+			let steps = window_seconds(*from, *to) / step;
+			let seconds = (from.num_seconds_from_midnight() as i64
+				+ rng.gen_range(0..=steps) * step)
+				% SECONDS_PER_DAY;
+			now.date()
+				.and_time(NaiveTime::from_num_seconds_from_midnight_opt(seconds as u32, 0).unwrap())
 		}
 	}
 }
 
+// Length of a time window, which wraps around midnight when `to` is before `from`
+fn window_seconds(from: NaiveTime, to: NaiveTime) -> i64 {
+	(to.num_seconds_from_midnight() as i64 - from.num_seconds_from_midnight() as i64)
+		.rem_euclid(SECONDS_PER_DAY)
+}
+
+// Uniform down to the second, `check_time_range` rejects ranges which end before they start
+fn random_datetime_between(
+	rng: &mut impl Rng,
+	start: NaiveDateTime,
+	end: NaiveDateTime,
+) -> NaiveDateTime {
+	let seconds = (end - start).num_seconds().max(0);
+	start + Duration::seconds(rng.gen_range(0..=seconds))
+}
+
 fn generate_value(
-	rng: &mut ThreadRng,
+	rng: &mut impl Rng,
+	column: &SQLColumn,
 	guess: &SQLValueGuess,
 	auto_increment_counter: &mut u32,
-) -> String {
+) -> SQLValue {
 	match guess {
+		// Values which don't fit the column are still written as is, `parse_fixed_value`
+		// warns about them before generating
+		SQLValueGuess::Fixed(value) => {
+			parse_fixed_value(column, value).unwrap_or_else(|_| SQLValue::String(value.clone()))
+		}
+		SQLValueGuess::FromList(values) => match values.choose(rng) {
+			Some(value) => {
+				parse_fixed_value(column, value).unwrap_or_else(|_| SQLValue::String(value.clone()))
+			}
+			None => SQLValue::Null,
+		},
 		SQLValueGuess::Int(int_guess) => match int_guess {
-			SQLIntValueGuess::Range(min, max) => rng.gen_range((*min)..=(*max)).to_string(),
-			SQLIntValueGuess::AutoIncrement => {
-				let str = auto_increment_counter.to_string();
+			// Ranges typed in backwards are swapped, instead of panicking
+			SQLIntValueGuess::Range {
+				min,
+				max,
+				step,
+				distribution,
+			} => {
+				let (min, max) = (*min.min(max) as i64, *min.max(max) as i64);
+				let step = step.unwrap_or(1).max(1) as i64;
+				let step_count = (max - min) / step;
+				let steps = match distribution {
+					SQLDistribution::Uniform => rng.gen_range(0..=step_count),
+					SQLDistribution::Normal { .. } => {
+						let value = sample_distribution(rng, distribution, min as f64, max as f64);
+						((value - min as f64) / step as f64).round() as i64
+					}
+				};
+				SQLValue::Int(min + steps.clamp(0, step_count) * step)
+			}
+			SQLIntValueGuess::AutoIncrement { start, step, .. } => {
+				let value = SQLValue::Int(
+					*start as i64 + *auto_increment_counter as i64 * (*step).max(1) as i64,
+				);
 				*auto_increment_counter += 1;
-				str
+				value
 			}
 		},
 		SQLValueGuess::Date(time_gues) => {
 			let datetime = generate_time_value(rng, &time_gues);
-			format!("'{}'", datetime.format("%Y-%m-%d"))
+			SQLValue::Date(datetime.date())
 		}
 		SQLValueGuess::Time(time_gues) => {
 			let datetime = generate_time_value(rng, &time_gues);
-			format!("'{}'", datetime.format("%H:%M:%S"))
+			SQLValue::Time(datetime.time())
 		}
 		SQLValueGuess::Datetime(time_gues) => {
 			let datetime = generate_time_value(rng, &time_gues);
-			format!("'{}'", datetime.format("%Y-%m-%d %H:%M:%S"))
+			SQLValue::Datetime(datetime)
 		}
 		SQLValueGuess::Bool(bool_guess) => match bool_guess {
-			SQLBoolValueGuess::True => "1".into(),
-			SQLBoolValueGuess::False => "0".into(),
-			SQLBoolValueGuess::Random => rng.gen_range(0..=1).to_string(),
+			SQLBoolValueGuess::True => SQLValue::Bool(true),
+			SQLBoolValueGuess::False => SQLValue::Bool(false),
+			SQLBoolValueGuess::Random => SQLValue::Bool(rng.gen_bool(0.5)),
 		},
-		SQLValueGuess::Float(min, max) => {
-			let value = rng.gen_range((*min)..(*max));
-			((value * 100.0 as f32).round() / 100.0).to_string()
+		// Filled in when foreign keys are resolved
+		SQLValueGuess::ForeignKey(_) => SQLValue::Null,
+		SQLValueGuess::Nullable(null_probability, guess) => {
+			if rng.gen_bool(null_probability.clamp(0.0, 1.0) as f64) {
+				SQLValue::Null
+			} else {
+				generate_value(rng, column, guess, auto_increment_counter)
+			}
+		}
+		SQLValueGuess::Float(min, max, decimals, distribution) => {
+			// Picked as f64, f32 doesn't have enough precision for coordinates
+			let (min, max) = (min.min(*max) as f64, min.max(*max) as f64);
+			let value = sample_distribution(rng, distribution, min, max);
+			let scale = 10f64.powi(*decimals as i32);
+			SQLValue::Decimal((value * scale).round() / scale, *decimals)
 		}
 		SQLValueGuess::String(max_size, string_guess) => {
 			let mut str = match string_guess {
-				SQLStringValueGuess::LoremIpsum => {
-					let mut current_len = 0;
-					let mut text = vec![];
-					let words: Vec<String> = Words(3..10).fake_with_rng(rng);
-					for word in words {
-						current_len += word.len() + 1;
-						text.push(word);
-						if current_len > *max_size {
-							break;
-						}
-					}
-					text.join(" ").to_string()
+				SQLStringValueGuess::LoremIpsum(lorem_guess) => {
+					generate_lorem(rng, lorem_guess, *max_size)
 				}
 				SQLStringValueGuess::FirstName => FirstName().fake_with_rng(rng),
 				SQLStringValueGuess::LastName => LastName().fake_with_rng(rng),
-				SQLStringValueGuess::FullName => Name().fake_with_rng(rng),
+				// Replaced in `apply_row_values`, unless both of the columns are NULL
+				SQLStringValueGuess::FullName | SQLStringValueGuess::FullNameFromColumns { .. } => {
+					Name().fake_with_rng(rng)
+				}
 				SQLStringValueGuess::PhoneNumber => PhoneNumber().fake_with_rng(rng),
 				SQLStringValueGuess::CityName => CityName().fake_with_rng(rng),
-				SQLStringValueGuess::Address => StreetName().fake_with_rng(rng),
-				SQLStringValueGuess::Email => FreeEmail().fake_with_rng(rng),
+				SQLStringValueGuess::StreetName => StreetName().fake_with_rng(rng),
+				SQLStringValueGuess::Address => generate_address_line(rng),
+				SQLStringValueGuess::FullAddress => {
+					let city: String = CityName().fake_with_rng(rng);
+					let zip_code: String = ZipCode().fake_with_rng(rng);
+					format!("{}, {}, {}", generate_address_line(rng), city, zip_code)
+				}
+				SQLStringValueGuess::CountryName => CountryName().fake_with_rng(rng),
+				SQLStringValueGuess::CountryCode => CountryCode().fake_with_rng(rng),
+				// Codes are made only of digits, so cutting one short still leaves a valid code.
+				// Shorter ones are padded only with `SQLOptions::pad_char_columns`.
+				SQLStringValueGuess::PostalCode => ZipCode().fake_with_rng(rng),
+				SQLStringValueGuess::CompanyName => CompanyName().fake_with_rng(rng),
+				SQLStringValueGuess::JobTitle => JobTitle().fake_with_rng(rng),
+				SQLStringValueGuess::Username => {
+					let username: String = Username().fake_with_rng(rng);
+					username.to_lowercase().split_whitespace().collect()
+				}
+				SQLStringValueGuess::PasswordHash => generate_password_hash(rng, *max_size),
+				SQLStringValueGuess::CreditCardNumber => generate_credit_card_number(rng),
+				SQLStringValueGuess::NationalId { digits, checksum } => {
+					generate_national_id(rng, *digits as usize, checksum.as_ref())
+				}
+				SQLStringValueGuess::Iban(country) => generate_iban(rng, country.as_deref()),
+				SQLStringValueGuess::Bic => Bic().fake_with_rng(rng),
+				SQLStringValueGuess::CurrencyCode => CurrencyCode().fake_with_rng(rng),
+				SQLStringValueGuess::IPv6 if *max_size >= IPV6_MAX_LENGTH => {
+					IPv6().fake_with_rng(rng)
+				}
+				SQLStringValueGuess::IPv4 | SQLStringValueGuess::IPv6 => IPv4().fake_with_rng(rng),
+				SQLStringValueGuess::MacAddress => MACAddress().fake_with_rng(rng),
+				SQLStringValueGuess::ColorHex => format!("#{:06x}", rng.gen_range(0..0x1000000)),
+				SQLStringValueGuess::ColorName => COLOR_NAMES.choose(rng).unwrap().to_string(),
+				SQLStringValueGuess::FileName => generate_file_name(rng),
+				SQLStringValueGuess::FilePath => generate_file_path(rng, *max_size),
+				SQLStringValueGuess::Hostname => {
+					let word: String = Word().fake_with_rng(rng);
+					let suffix: String = DomainSuffix().fake_with_rng(rng);
+					format!("{}-{}.{}", word, rng.gen_range(1..100), suffix)
+				}
+				// Replaced in `apply_row_values`, unless the row has no names to use
+				SQLStringValueGuess::Email | SQLStringValueGuess::EmailFromName => {
+					FreeEmail().fake_with_rng(rng)
+				}
 				SQLStringValueGuess::URL => {
 					let suffix: String = DomainSuffix().fake_with_rng(rng);
 					let noun: String = BsNoun().fake_with_rng(rng);
@@ -294,60 +2759,350 @@ fn generate_value(
 					format!("www.{}.{}", noun, suffix)
 				}
 				SQLStringValueGuess::RandomEnum(options) => {
-					options.choose(rng).unwrap().to_string()
+					// All weights being zero is treated the same as all being equal
+					options
+						.choose_weighted(rng, |(_, weight)| weight.max(0.0))
+						.or_else(|_| options.choose(rng).ok_or(()))
+						.map(|(variant, _)| variant.clone())
+						.unwrap_or_default()
+				}
+				SQLStringValueGuess::Pattern(pattern) => expand_pattern(rng, pattern),
+				SQLStringValueGuess::Sequential { prefix, start, pad } => {
+					let number = format!(
+						"{:0pad$}",
+						*start as u64 + *auto_increment_counter as u64,
+						pad = *pad as usize
+					);
+					*auto_increment_counter += 1;
+
+					// The prefix is cut instead of the number, so values stay unique
+					let mut prefix = prefix.clone();
+					truncate_chars(&mut prefix, max_size.saturating_sub(number.len()));
+					prefix + &number
 				}
 				SQLStringValueGuess::Empty => "".into(),
 			};
 
-			str.truncate(*max_size);
-			format!("'{}'", str)
+			match string_guess {
+				// Variants are checked to fit before generating, see `check_values_fit`
+				SQLStringValueGuess::RandomEnum(_) => {}
+				SQLStringValueGuess::LoremIpsum(_)
+				| SQLStringValueGuess::FullName
+				| SQLStringValueGuess::FullNameFromColumns { .. }
+				| SQLStringValueGuess::CityName
+				| SQLStringValueGuess::StreetName
+				| SQLStringValueGuess::Address
+				| SQLStringValueGuess::FullAddress
+				| SQLStringValueGuess::CountryName
+				| SQLStringValueGuess::CompanyName
+				| SQLStringValueGuess::JobTitle => truncate_words(&mut str, *max_size),
+				_ => truncate_chars(&mut str, *max_size),
+			}
+			SQLValue::String(str)
 		}
 	}
 }
 
-fn generate_string_guess(column: &SQLColumn) -> SQLStringValueGuess {
-	if let Some(constraint) = &column.check_constraint {
-		if let SQLCheckConstraint::OneOf(options) = constraint {
-			return SQLStringValueGuess::RandomEnum(options.clone());
-		} else {
-			return SQLStringValueGuess::LoremIpsum;
+fn is_within_range(value: &str, min: &Option<f64>, max: &Option<f64>) -> bool {
+	if let Ok(value) = value.parse::<f64>() {
+		min.is_none_or(|min| value >= min) && max.is_none_or(|max| value <= max)
+	} else {
+		true
+	}
+}
+
+/// Bounds allowed by all of the column's range check constraints, `None` if it has none
+pub fn constraint_range(column: &SQLColumn) -> Option<(Option<f64>, Option<f64>)> {
+	let mut range = None;
+	for constraint in &column.check_constraints {
+		if let SQLCheckConstraint::Range(min, max) = constraint {
+			let (current_min, current_max) = range.unwrap_or((None, None));
+			let min = match (current_min, *min) {
+				(Some(a), Some(b)) => Some(f64::max(a, b)),
+				(a, b) => a.or(b),
+			};
+			let max = match (current_max, *max) {
+				(Some(a), Some(b)) => Some(f64::min(a, b)),
+				(a, b) => a.or(b),
+			};
+			range = Some((min, max));
+		}
+	}
+	range
+}
+
+/// Describes how a number range goes outside of the column's range check constraints
+pub fn check_constraint_range(column: &SQLColumn, guess: &SQLValueGuess) -> Option<String> {
+	let (lower, upper) = constraint_range(column)?;
+	// Floats are compared as they were entered, so that 0.1 isn't bigger than 0.1
+	let (min, max, exceeds) = match guess {
+		SQLValueGuess::Nullable(_, guess) => return check_constraint_range(column, guess),
+		SQLValueGuess::Int(SQLIntValueGuess::Range { min, max, .. }) => {
+			let (min, max) = (*min.min(max), *min.max(max));
+			let exceeds = lower.is_some_and(|lower| (min as f64) < lower)
+				|| upper.is_some_and(|upper| max as f64 > upper);
+			(min.to_string(), max.to_string(), exceeds)
+		}
+		SQLValueGuess::Float(min, max, ..) => {
+			let (min, max) = (min.min(*max), min.max(*max));
+			let exceeds = lower.is_some_and(|lower| min < lower as f32)
+				|| upper.is_some_and(|upper| max > upper as f32);
+			(min.to_string(), max.to_string(), exceeds)
+		}
+		_ => return None,
+	};
+	exceeds.then(|| {
+		format!(
+			"range {}..{} goes outside of the check constraint {}",
+			min,
+			max,
+			SQLCheckConstraint::Range(lower, upper)
+		)
+	})
+}
+
+// Range constraints replace the default bounds, one sided ones only move the side they limit
+fn constrained_bounds(column: &SQLColumn, min: f64, max: f64) -> (f64, f64) {
+	let span = max - min;
+	match constraint_range(column) {
+		Some((Some(lower), Some(upper))) => (lower, upper),
+		Some((Some(lower), None)) => (lower, if max > lower { max } else { lower + span }),
+		Some((None, Some(upper))) => (if min < upper { min } else { upper - span }, upper),
+		_ => (min, max),
+	}
+}
+
+// Intersects all "in (...)" constraints and then filters out variants outside of any range constraint
+fn get_one_of_options(constraints: &[SQLCheckConstraint]) -> Option<Vec<String>> {
+	let mut options: Option<Vec<String>> = None;
+	for constraint in constraints {
+		if let SQLCheckConstraint::OneOf(variants) = constraint {
+			options = Some(match options {
+				Some(options) => options
+					.into_iter()
+					.filter(|option| variants.contains(option))
+					.collect(),
+				None => variants.clone(),
+			});
 		}
 	}
 
+	let mut options = options?;
+	for constraint in constraints {
+		if let SQLCheckConstraint::Range(min, max) = constraint {
+			options.retain(|option| is_within_range(option, min, max));
+		}
+	}
+
+	Some(options)
+}
+
+// Parts of a column name like `ip_address`, for checks that would match too much as substrings
+fn name_words(name: &str) -> Vec<&str> {
+	name.split(|c: char| !c.is_alphanumeric()).collect()
+}
+
+// Like `birth_date`, `date_of_birth`, `birthday` or `dob`
+fn is_birthdate(name: &str) -> bool {
+	name.contains("birth") || name_words(name).contains(&"dob")
+}
+
+fn is_first_name(name: &str) -> bool {
+	name.contains("first") && name.contains("name")
+}
+
+fn is_last_name(name: &str) -> bool {
+	(name.contains("last") && name.contains("name")) || name.contains("surname")
+}
+
+/// First and last name columns of the table, found the same way as their guesses are
+pub fn find_name_columns(table: &SQLTable) -> (Option<&SQLColumn>, Option<&SQLColumn>) {
+	let string_columns = || {
+		table.columns.iter().filter(|column| {
+			matches!(
+				column.sql_type,
+				SQLType::Char(_) | SQLType::Varchar(_) | SQLType::Text
+			)
+		})
+	};
+	(
+		string_columns().find(|column| is_first_name(&column.name.to_lowercase())),
+		string_columns().find(|column| is_last_name(&column.name.to_lowercase())),
+	)
+}
+
+fn generate_string_guess(column: &SQLColumn, max_size: usize) -> SQLStringValueGuess {
+	if let Some(options) = get_one_of_options(&column.check_constraints) {
+		if !options.is_empty() {
+			let options = options.into_iter().map(|variant| (variant, 1.0)).collect();
+			return SQLStringValueGuess::RandomEnum(options);
+		}
+	}
+
+	if !column.check_constraints.is_empty() {
+		return SQLStringValueGuess::LoremIpsum(SQLLoremValueGuess::default());
+	}
+
 	let name = column.name.to_lowercase();
-	if name.contains("first") && name.contains("name") {
+	let words = name_words(&name);
+	if is_first_name(&name) {
 		SQLStringValueGuess::FirstName
-	} else if (name.contains("last") && name.contains("name")) || name.contains("surname") {
+	} else if is_last_name(&name) {
 		SQLStringValueGuess::LastName
 	} else if name.contains("phone") && name.contains("number") {
 		SQLStringValueGuess::PhoneNumber
+	} else if name.contains("username") || name.contains("login") {
+		SQLStringValueGuess::Username
+	} else if name.contains("password") || name.contains("pwd") || name.contains("hash") {
+		SQLStringValueGuess::PasswordHash
+	} else if name.contains("card") && (name.contains("number") || name.contains("credit")) {
+		SQLStringValueGuess::CreditCardNumber
+	} else if (name.contains("personal") && name.contains("code"))
+		|| (name.contains("national") && name.contains("id"))
+	{
+		SQLStringValueGuess::NationalId {
+			digits: 11,
+			checksum: Some(SQLChecksum::Mod11),
+		}
+	} else if words.contains(&"ssn") {
+		SQLStringValueGuess::NationalId {
+			digits: 9,
+			checksum: None,
+		}
+	} else if name.contains("currency") {
+		SQLStringValueGuess::CurrencyCode
+	} else if words.contains(&"ipv6") && max_size >= IPV6_MAX_LENGTH {
+		SQLStringValueGuess::IPv6
+	} else if words.contains(&"ip") || words.contains(&"ipv4") || name.contains("ip_address") {
+		SQLStringValueGuess::IPv4
+	} else if words.contains(&"mac") || name.contains("mac_address") {
+		SQLStringValueGuess::MacAddress
+	} else if name.contains("host") {
+		SQLStringValueGuess::Hostname
+	} else if name.contains("color") || name.contains("colour") {
+		if name.contains("hex") || max_size == 7 {
+			SQLStringValueGuess::ColorHex
+		} else {
+			SQLStringValueGuess::ColorName
+		}
+	} else if words.contains(&"path") || name.contains("filepath") {
+		SQLStringValueGuess::FilePath
+	} else if words.contains(&"file") || name.contains("filename") {
+		SQLStringValueGuess::FileName
+	} else if name.contains("iban") {
+		SQLStringValueGuess::Iban(None)
+	} else if name == "bic" || name.ends_with("_bic") || name.contains("swift") {
+		SQLStringValueGuess::Bic
+	} else if (name.contains("country") && (name.contains("code") || max_size < 4))
+		|| name.starts_with("iso")
+	{
+		SQLStringValueGuess::CountryCode
+	} else if name.contains("country") {
+		SQLStringValueGuess::CountryName
+	} else if name.contains("zip") || name.contains("postal") || name.contains("postcode") {
+		SQLStringValueGuess::PostalCode
 	} else if name.contains("city") {
 		SQLStringValueGuess::CityName
-	} else if name.contains("address") {
+	} else if name.contains("address") && max_size >= 60 {
+		SQLStringValueGuess::FullAddress
+	} else if name.contains("address") && max_size >= 30 {
 		SQLStringValueGuess::Address
+	} else if name.contains("address") || name.contains("street") {
+		SQLStringValueGuess::StreetName
 	} else if name.contains("email") {
 		SQLStringValueGuess::Email
 	} else if name.contains("homepage") || name.contains("website") || name.contains("url") {
 		SQLStringValueGuess::URL
+	} else if ["company", "employer", "supplier", "vendor"]
+		.iter()
+		.any(|word| name.contains(word))
+	{
+		SQLStringValueGuess::CompanyName
+	} else if ["title", "position", "job"]
+		.iter()
+		.any(|word| name.contains(word))
+	{
+		SQLStringValueGuess::JobTitle
+	} else if ["description", "comment", "note", "summary"]
+		.iter()
+		.any(|word| name.contains(word))
+		&& max_size >= 50
+	{
+		SQLStringValueGuess::LoremIpsum(SQLLoremValueGuess::Sentences(5, 20))
 	} else {
-		SQLStringValueGuess::LoremIpsum
+		SQLStringValueGuess::LoremIpsum(SQLLoremValueGuess::default())
 	}
 }
 
 pub fn generate_guess(column: &SQLColumn) -> SQLValueGuess {
+	let guess = generate_value_guess(column);
+	if column.nullable && !column.primary_key {
+		SQLValueGuess::Nullable(DEFAULT_NULL_PROBABILITY, Box::new(guess))
+	} else {
+		guess
+	}
+}
+
+/// Same as `generate_guess`, but never NULL
+pub fn generate_value_guess(column: &SQLColumn) -> SQLValueGuess {
 	match column.sql_type {
 		SQLType::Int => {
-			if column.primary_key {
-				SQLValueGuess::Int(SQLIntValueGuess::AutoIncrement)
+			if column.auto_increment {
+				// Ids are inserted by default, so the script works without relying on the database
+				// counting the same way. Leaving them out is an opt-in.
+				SQLValueGuess::Int(SQLIntValueGuess::auto_increment())
+			} else {
+				let words = name_words(&column.name);
+				let is_age = words.iter().any(|word| word.eq_ignore_ascii_case("age"));
+				let (min, max) = if is_age { (18.0, 80.0) } else { (0.0, 100.0) };
+				let (min, max) = constrained_bounds(column, min, max);
+				SQLValueGuess::Int(SQLIntValueGuess::range(
+					min.ceil() as i32,
+					max.floor() as i32,
+				))
+			}
+		}
+		SQLType::Float | SQLType::Decimal(_) => {
+			let name = column.name.to_lowercase();
+			let words = name_words(&name);
+			let (min, max, decimals): (f32, f32, u8) = if ["price", "amount", "cost", "total"]
+				.iter()
+				.any(|word| name.contains(word))
+			{
+				(0.0, 999.99, 2)
+			} else if name.contains("latitude") || words.contains(&"lat") {
+				(-90.0, 90.0, 6)
+			} else if name.contains("longitude") || words.contains(&"lon") || words.contains(&"lng")
+			{
+				(-180.0, 180.0, 6)
 			} else {
-				SQLValueGuess::Int(SQLIntValueGuess::Range(0, 100))
+				(0.0, 100.0, 2)
+			};
+			let (min, max) = constrained_bounds(column, min as f64, max as f64);
+			let (min, max) = (min as f32, max as f32);
+
+			// DECIMAL(precision, scale) has exactly `scale` decimals, and the rest of the digits
+			// limit how big the values can get
+			match column.sql_type {
+				SQLType::Decimal(Some((precision, scale))) => {
+					let limit = 10f32.powi(precision.saturating_sub(scale) as i32)
+						- 10f32.powi(-(scale as i32));
+					SQLValueGuess::Float(
+						min.max(-limit),
+						max.min(limit),
+						scale,
+						SQLDistribution::Uniform,
+					)
+				}
+				_ => SQLValueGuess::Float(min, max, decimals, SQLDistribution::Uniform),
 			}
 		}
-		SQLType::Float | SQLType::Decimal => SQLValueGuess::Float(0.0, 100.0),
 		SQLType::Date => {
 			let name = column.name.to_lowercase();
-			if name.contains("create") || name.contains("update") {
-				SQLValueGuess::Date(SQLTimeValueGuess::Past)
+			if is_birthdate(&name) {
+				SQLValueGuess::Date(SQLTimeValueGuess::YearsAgo { min: 18, max: 80 })
+			} else if name.contains("create") || name.contains("update") {
+				SQLValueGuess::Date(SQLTimeValueGuess::past())
 			} else {
 				SQLValueGuess::Date(SQLTimeValueGuess::Now)
 			}
@@ -355,34 +3110,1083 @@ pub fn generate_guess(column: &SQLColumn) -> SQLValueGuess {
 		SQLType::Time => {
 			let name = column.name.to_lowercase();
 			if name.contains("create") || name.contains("update") {
-				SQLValueGuess::Time(SQLTimeValueGuess::Past)
+				SQLValueGuess::Time(SQLTimeValueGuess::past())
 			} else {
-				SQLValueGuess::Time(SQLTimeValueGuess::Now)
+				SQLValueGuess::Time(SQLTimeValueGuess::whole_day())
 			}
 		}
 		SQLType::Datetime => {
 			let name = column.name.to_lowercase();
-			if name.contains("create") || name.contains("update") {
-				SQLValueGuess::Datetime(SQLTimeValueGuess::Past)
+			if is_birthdate(&name) {
+				SQLValueGuess::Datetime(SQLTimeValueGuess::YearsAgo { min: 18, max: 80 })
+			} else if name.contains("create") || name.contains("update") {
+				SQLValueGuess::Datetime(SQLTimeValueGuess::past())
 			} else {
 				SQLValueGuess::Datetime(SQLTimeValueGuess::Now)
 			}
 		}
 		SQLType::Bool => SQLValueGuess::Bool(SQLBoolValueGuess::Random),
-		SQLType::Varchar(max_size) => {
-			SQLValueGuess::String(max_size as usize, generate_string_guess(column))
-		}
-		SQLType::Char(max_size) => {
-			SQLValueGuess::String(max_size as usize, generate_string_guess(column))
+		SQLType::Varchar(max_size) => SQLValueGuess::String(
+			max_size as usize,
+			generate_string_guess(column, max_size as usize),
+		),
+		SQLType::Char(max_size) => SQLValueGuess::String(
+			max_size as usize,
+			generate_string_guess(column, max_size as usize),
+		),
+		SQLType::Text => {
+			SQLValueGuess::String(TEXT_MAX_SIZE, generate_string_guess(column, TEXT_MAX_SIZE))
 		}
 	}
 }
 
+/// Distinct combinations with a value from each of the lists, without listing out all of
+/// the possible combinations. Returns `None` if there are less of them than asked for.
+fn sample_combinations(
+	rng: &mut impl Rng,
+	choices: &[Vec<SQLValue>],
+	amount: usize,
+) -> Option<Vec<Vec<SQLValue>>> {
+	let total = choices
+		.iter()
+		.fold(1usize, |total, values| total.saturating_mul(values.len()));
+	if total < amount {
+		return None;
+	}
+
+	let combinations = index::sample(rng, total, amount)
+		.into_iter()
+		.map(|mut combination_idx| {
+			let mut combination = vec![];
+			for values in choices {
+				combination.push(values[combination_idx % values.len()].clone());
+				combination_idx /= values.len();
+			}
+			combination
+		})
+		.collect();
+	Some(combinations)
+}
+
+// Weights follow Zipf's law, so the first value is picked twice as often as the second one
+fn choose_skewed<'a, T>(rng: &mut impl Rng, values: &'a [T]) -> Option<&'a T> {
+	let weights = (1..=values.len()).map(|rank| 1.0 / rank as f64);
+	let index = WeightedIndex::new(weights).ok()?;
+	values.get(index.sample(rng))
+}
+
+fn generate_foreign_key_guess(
+	table: &SQLTable,
+	column: &SQLColumn,
+	foreign_key: &SQLForeignKey,
+) -> SQLValueGuess {
+	let is_unique = table
+		.unique_column_sets()
+		.iter()
+		.any(|columns| columns.len() == 1 && columns[0] == column.name);
+	let guess = if is_unique {
+		SQLValueGuess::ForeignKey(SQLForeignKeyValueGuess::OneToOne)
+	} else {
+		SQLValueGuess::ForeignKey(SQLForeignKeyValueGuess::Uniform)
+	};
+	if !column.nullable || column.primary_key {
+		return guess;
+	}
+	// Roots of self referencing hierarchies are already controlled by `root_row_probability`
+	let is_self_reference = names_match(&foreign_key.table, &table.name);
+	let null_probability = if is_self_reference {
+		0.0
+	} else {
+		DEFAULT_FOREIGN_KEY_NULL_PROBABILITY
+	};
+	SQLValueGuess::Nullable(null_probability, Box::new(guess))
+}
+
+// Word pairs of columns where the first one happens before the second one
+const TIME_ORDER_WORDS: [(&str, &str); 5] = [
+	("created", "updated"),
+	("created", "modified"),
+	("start", "end"),
+	("begin", "end"),
+	("from", "to"),
+];
+
+// Finds `created_at` for `updated_at`, `start_date` for `end_date` and so on
+fn find_earlier_column<'a>(table: &'a SQLTable, column: &SQLColumn) -> Option<&'a SQLColumn> {
+	let name = column.name.to_lowercase();
+	let words = name_words(&name);
+	TIME_ORDER_WORDS
+		.iter()
+		.filter(|(_, later)| words.contains(later))
+		.find_map(|(earlier, later)| {
+			let earlier_words = words
+				.iter()
+				.map(|word| if word == later { *earlier } else { *word })
+				.collect::<Vec<_>>();
+			table.columns.iter().find(|other| {
+				matches!(other.sql_type, SQLType::Date | SQLType::Datetime)
+					&& name_words(&other.name.to_lowercase()) == earlier_words
+			})
+		})
+}
+
+// Keeps the size and NULL probability of the guess
+fn replace_string_guess(guess: SQLValueGuess, string_guess: SQLStringValueGuess) -> SQLValueGuess {
+	match guess {
+		SQLValueGuess::Nullable(null_probability, guess) => SQLValueGuess::Nullable(
+			null_probability,
+			Box::new(replace_string_guess(*guess, string_guess)),
+		),
+		SQLValueGuess::String(max_size, _) => SQLValueGuess::String(max_size, string_guess),
+		guess => guess,
+	}
+}
+
 pub fn generate_table_guessess(table: &SQLTable) -> HashMap<String, SQLValueGuess> {
 	table
 		.columns
 		.iter()
-		.filter(|column| column.foreign_key.is_none())
-		.map(|column| (column.name.clone(), generate_guess(column)))
+		.map(|column| {
+			let mut guess = match &column.foreign_key {
+				Some(foreign_key) => generate_foreign_key_guess(table, column, foreign_key),
+				None => generate_guess(column),
+			};
+			let is_email = matches!(string_guess(&guess), Some((_, SQLStringValueGuess::Email)));
+			if is_email && find_name_columns(table) != (None, None) {
+				guess = replace_string_guess(guess, SQLStringValueGuess::EmailFromName);
+			}
+			let name = column.name.to_lowercase();
+			let is_full_name =
+				(name.contains("full") || name.contains("display")) && name.contains("name");
+			if let (true, (Some(first), Some(last))) = (is_full_name, find_name_columns(table)) {
+				guess = replace_string_guess(
+					guess,
+					SQLStringValueGuess::FullNameFromColumns {
+						first: first.name.clone(),
+						last: last.name.clone(),
+					},
+				);
+			}
+			if let (SQLType::Date | SQLType::Datetime, None) =
+				(&column.sql_type, &column.foreign_key)
+			{
+				if let Some(earlier_column) = find_earlier_column(table, column) {
+					let after = SQLTimeValueGuess::After {
+						column: earlier_column.name.clone(),
+						min_days: 0,
+						max_days: 30,
+					};
+					let after = match column.sql_type {
+						SQLType::Date => SQLValueGuess::Date(after),
+						_ => SQLValueGuess::Datetime(after),
+					};
+					guess = match guess {
+						SQLValueGuess::Nullable(null_probability, _) => {
+							SQLValueGuess::Nullable(null_probability, Box::new(after))
+						}
+						_ => after,
+					};
+				}
+			}
+			(column.name.clone(), guess)
+		})
 		.collect()
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn generate(tables: Vec<SQLTable>, table_rows: &[(&str, u32)]) -> Result<GeneratedData> {
+		let tables = tables.into_iter().map(Rc::new).collect::<Vec<_>>();
+		let guessess = tables
+			.iter()
+			.map(|table| generate_table_guessess(table))
+			.collect::<Vec<_>>();
+		let options = GenerateOptions {
+			table_rows: table_rows
+				.iter()
+				.map(|(name, rows)| (name.to_string(), *rows))
+				.collect(),
+			seed: Some(1),
+			..Default::default()
+		};
+		generate_rows(&tables, &guessess.iter().collect::<Vec<_>>(), &options)
+	}
+
+	fn customer_table() -> SQLTable {
+		SQLTable::new(
+			"customer",
+			vec![
+				SQLColumn::new("id", SQLType::Int)
+					.primary_key()
+					.auto_increment(),
+				SQLColumn::new("name", SQLType::Varchar(50)),
+			],
+		)
+	}
+
+	fn orders_table() -> SQLTable {
+		SQLTable::new(
+			"orders",
+			vec![
+				SQLColumn::new("id", SQLType::Int)
+					.primary_key()
+					.auto_increment(),
+				SQLColumn::new("customer_id", SQLType::Int).foreign_key("customer", "id"),
+			],
+		)
+	}
+
+	fn column_values(
+		generated: &GeneratedData,
+		table_idx: usize,
+		column_idx: usize,
+	) -> Vec<SQLValue> {
+		generated.tables[table_idx]
+			.rows
+			.iter()
+			.map(|row| row[column_idx].clone())
+			.collect()
+	}
+
+	const NON_ASCII_XML: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<xmi:XMI xmlns:xmi="http://www.omg.org/spec/XMI/20131001" xmlns:uml="http://www.omg.org/spec/UML/20131001" xmlns:MagicDraw_Profile="http://www.omg.org/spec/UML/20131001/MagicDraw_Profile">
+<uml:Model xmi:id="m1" name="Parduotuvė">
+<packagedElement xmi:type="uml:Class" xmi:id="c1" name="Užsakymas">
+<ownedAttribute xmi:type="uml:Property" xmi:id="p1" name="id" isID="true"><type href="SQL2003.mdzip#INTEGER"/></ownedAttribute>
+<ownedAttribute xmi:type="uml:Property" xmi:id="p2" name="Straße"><type href="SQL2003.mdzip#VARCHAR"/></ownedAttribute>
+<ownedAttribute xmi:type="uml:Property" xmi:id="p3" name="Būsena"><type href="SQL2003.mdzip#VARCHAR"/></ownedAttribute>
+<ownedRule xmi:type="uml:Constraint" xmi:id="r1">
+<constrainedElement xmi:idref="c1"/>
+<specification xmi:type="uml:OpaqueExpression" xmi:id="e1"><body>Būsena in ('naujas', 'įvykdytas', 'atšauktas')</body><language>SQL</language></specification>
+</ownedRule>
+</packagedElement>
+</uml:Model>
+<MagicDraw_Profile:typeModifier xmi:id="t1" base_Element="p2" typeModifier="7"/>
+<MagicDraw_Profile:typeModifier xmi:id="t2" base_Element="p3" typeModifier="20"/>
+</xmi:XMI>"#;
+
+	#[test]
+	fn non_ascii_names_from_model_to_sql() {
+		let project = crate::magicdraw_parser::parse_project_file(
+			std::io::Cursor::new(NON_ASCII_XML),
+			&Default::default(),
+		)
+		.unwrap();
+		let tables = project.collections[0].tables.clone();
+		let generated = generate(tables, &[("Užsakymas", 20)]).unwrap();
+		for row in &generated.tables[0].rows {
+			let SQLValue::String(street) = &row[1] else {
+				panic!("street is not a string");
+			};
+			assert!(street.chars().count() <= 7, "{}", street);
+			assert!(matches!(&row[2], SQLValue::String(state)
+				if ["naujas", "įvykdytas", "atšauktas"].contains(&state.as_str())));
+		}
+
+		let options = SQLOptions {
+			dialect: SQLDialect::Postgres,
+			quote_identifiers: true,
+			..Default::default()
+		};
+		let sql = to_sql(&generated, &options);
+		let header = format!(
+			"INSERT INTO \"Užsakymas\"\n{}(\"id\", \"Straße\", \"Būsena\")",
+			INDENT
+		);
+		assert!(sql.contains(&header), "{}", sql);
+		assert!(sql.contains("'įvykdytas'") || sql.contains("'atšauktas'"));
+	}
+
+	#[test]
+	fn guesses_follow_the_columns() {
+		assert_eq!(
+			generate_guess(&SQLColumn::new("age", SQLType::Int)),
+			SQLValueGuess::Int(SQLIntValueGuess::range(18, 80))
+		);
+		assert_eq!(
+			generate_guess(
+				&SQLColumn::new("id", SQLType::Int)
+					.primary_key()
+					.auto_increment()
+					.nullable()
+			),
+			SQLValueGuess::Int(SQLIntValueGuess::auto_increment())
+		);
+		assert_eq!(
+			generate_guess(&SQLColumn::new("price", SQLType::Decimal(Some((5, 2))))),
+			SQLValueGuess::Float(0.0, 999.99, 2, SQLDistribution::Uniform)
+		);
+		assert_eq!(
+			generate_guess(&SQLColumn::new("email", SQLType::Varchar(100)).nullable()),
+			SQLValueGuess::Nullable(
+				DEFAULT_NULL_PROBABILITY,
+				Box::new(SQLValueGuess::String(100, SQLStringValueGuess::Email))
+			)
+		);
+	}
+
+	#[test]
+	fn fake_entries_are_inserted_in_dependency_order() {
+		let tables = [orders_table(), customer_table()].map(Rc::new);
+		let guessess = tables
+			.iter()
+			.map(|table| Box::new(generate_table_guessess(table)))
+			.collect::<Vec<_>>();
+		let options = GenerateOptions {
+			rows_per_table: 3,
+			seed: Some(4),
+			..Default::default()
+		};
+		let sql = generate_fake_entries(&tables, &guessess, &options).unwrap();
+
+		let customer_insert = sql.find("INSERT INTO customer").unwrap();
+		let orders_insert = sql.find("INSERT INTO orders").unwrap();
+		assert!(customer_insert < orders_insert, "{}", sql);
+		let rows = sql.lines().filter(|line| line.starts_with(INDENT)).count();
+		assert_eq!(rows, 2 * (1 + 3), "{}", sql);
+		assert_eq!(
+			generate_fake_entries(&tables, &guessess, &options).unwrap(),
+			sql
+		);
+	}
+
+	#[test]
+	fn cycles_are_broken_through_nullable_foreign_keys() {
+		let orders = SQLTable::new(
+			"orders",
+			vec![
+				SQLColumn::new("id", SQLType::Int)
+					.primary_key()
+					.auto_increment(),
+				SQLColumn::new("invoice_id", SQLType::Int)
+					.nullable()
+					.foreign_key("invoices", "id"),
+			],
+		);
+		let invoices = SQLTable::new(
+			"invoices",
+			vec![
+				SQLColumn::new("id", SQLType::Int)
+					.primary_key()
+					.auto_increment(),
+				SQLColumn::new("order_id", SQLType::Int).foreign_key("orders", "id"),
+			],
+		);
+		let generated =
+			generate(vec![orders, invoices], &[("orders", 5), ("invoices", 5)]).unwrap();
+
+		let order_ids = column_values(&generated, 0, 0);
+		let invoice_ids = column_values(&generated, 1, 0);
+		assert!(column_values(&generated, 0, 1)
+			.iter()
+			.all(|value| *value == SQLValue::Null));
+		assert!(column_values(&generated, 1, 1)
+			.iter()
+			.all(|id| order_ids.contains(id)));
+		let deferred_values = &generated.tables[0].deferred_values;
+		assert!(!deferred_values.is_empty());
+		assert!(deferred_values
+			.iter()
+			.all(|(_, column_idx, id)| *column_idx == 1 && invoice_ids.contains(id)));
+
+		let sql = to_sql(&generated, &SQLOptions::default());
+		let invoices_insert = sql.find("INSERT INTO invoices").unwrap();
+		let updates = sql
+			.match_indices("UPDATE orders SET invoice_id = ")
+			.map(|(idx, _)| idx)
+			.collect::<Vec<_>>();
+		assert_eq!(updates.len(), deferred_values.len(), "{}", sql);
+		assert!(updates.iter().all(|idx| *idx > invoices_insert), "{}", sql);
+	}
+
+	#[test]
+	fn root_row_probability_outside_of_range_is_rejected() {
+		for root_row_probability in [-0.1, 2.0, f64::NAN] {
+			let options = GenerateOptions {
+				root_row_probability,
+				..Default::default()
+			};
+			let tables = [Rc::new(customer_table())];
+			let guessess = [generate_table_guessess(&tables[0])];
+			let error =
+				generate_rows(&tables, &guessess.iter().collect::<Vec<_>>(), &options).unwrap_err();
+			assert!(
+				error.to_string().starts_with("root row probability"),
+				"{}",
+				error
+			);
+		}
+	}
+
+	#[test]
+	fn columns_without_a_guess_are_reported() {
+		let tables = [Rc::new(customer_table())];
+		let mut guessess = generate_table_guessess(&tables[0]);
+		guessess.remove("name");
+		let error = generate_rows(&tables, &[&guessess], &GenerateOptions::default()).unwrap_err();
+		assert_eq!(
+			error.to_string(),
+			"customer.name: no generator is picked for the column"
+		);
+	}
+
+	#[test]
+	fn unresolved_foreign_keys_are_named() {
+		let orders = SQLTable::new(
+			"orders",
+			vec![
+				SQLColumn::new("id", SQLType::Int).primary_key(),
+				SQLColumn::new("invoice_id", SQLType::Int).foreign_key("invoices", "order_id"),
+			],
+		);
+		let invoices = SQLTable::new(
+			"invoices",
+			vec![
+				SQLColumn::new("id", SQLType::Int).primary_key(),
+				SQLColumn::new("order_id", SQLType::Int).foreign_key("orders", "invoice_id"),
+			],
+		);
+		let error = generate(vec![orders, invoices], &[]).unwrap_err();
+		assert_eq!(
+			error.to_string(),
+			"Failed to resolve foreign keys: circular foreign keys \
+			orders.invoice_id → invoices.order_id → orders.invoice_id"
+		);
+
+		let error =
+			generate(vec![customer_table(), orders_table()], &[("customer", 0)]).unwrap_err();
+		assert_eq!(
+			error.to_string(),
+			"Failed to resolve foreign keys: orders.customer_id → customer.id \
+			(customer has no rows)"
+		);
+	}
+
+	fn junction_tables() -> Vec<SQLTable> {
+		let product = SQLTable::new(
+			"product",
+			vec![SQLColumn::new("id", SQLType::Int)
+				.primary_key()
+				.auto_increment()],
+		);
+		let tag = SQLTable::new(
+			"tag",
+			vec![SQLColumn::new("id", SQLType::Int)
+				.primary_key()
+				.auto_increment()],
+		);
+		let product_tag = SQLTable::new(
+			"product_tag",
+			vec![
+				SQLColumn::new("product_id", SQLType::Int)
+					.primary_key()
+					.foreign_key("product", "id"),
+				SQLColumn::new("tag_id", SQLType::Int)
+					.primary_key()
+					.foreign_key("tag", "id"),
+			],
+		);
+		vec![product, tag, product_tag]
+	}
+
+	#[test]
+	fn junction_rows_use_every_pair_once() {
+		let table_rows = [("product", 3), ("tag", 3), ("product_tag", 9)];
+		let generated = generate(junction_tables(), &table_rows).unwrap();
+		let pairs = generated.tables[2]
+			.rows
+			.iter()
+			.map(|row| (row[0].clone(), row[1].clone()))
+			.collect::<HashSet<_>>();
+		assert_eq!(pairs.len(), 9);
+
+		let table_rows = [("product", 3), ("tag", 3), ("product_tag", 10)];
+		let error = generate(junction_tables(), &table_rows).unwrap_err();
+		assert_eq!(
+			error.to_string(),
+			"product_tag: only 9 unique combinations of (product_id, tag_id) are possible, \
+			but 10 rows were requested"
+		);
+	}
+
+	#[test]
+	fn company_names_and_job_titles() {
+		let supplier = SQLColumn::new("supplier", SQLType::Varchar(12));
+		let job_title = SQLColumn::new("job_title", SQLType::Varchar(12));
+		assert_eq!(
+			generate_guess(&supplier),
+			SQLValueGuess::String(12, SQLStringValueGuess::CompanyName)
+		);
+		assert_eq!(
+			generate_guess(&job_title),
+			SQLValueGuess::String(12, SQLStringValueGuess::JobTitle)
+		);
+
+		let mut rng = StdRng::seed_from_u64(5);
+		let mut counter = 0;
+		for column in [supplier, job_title] {
+			let guess = generate_guess(&column);
+			for _ in 0..50 {
+				let SQLValue::String(value) =
+					generate_value(&mut rng, &column, &guess, &mut counter)
+				else {
+					panic!("{} is not a string", column.name);
+				};
+				assert!(!value.is_empty());
+				assert!(value.chars().count() <= 12, "{}", value);
+				assert_eq!(value.trim_end(), value);
+			}
+		}
+
+		let mut company = "Müller & Söhne GmbH".to_string();
+		truncate_words(&mut company, 15);
+		assert_eq!(company, "Müller & Söhne");
+		truncate_words(&mut company, 8);
+		assert_eq!(company, "Müller");
+		truncate_words(&mut company, 3);
+		assert_eq!(company, "Mül");
+	}
+
+	fn passes_luhn(number: &str) -> bool {
+		let sum = number
+			.chars()
+			.rev()
+			.map(|c| c.to_digit(10).unwrap())
+			.enumerate()
+			.map(|(i, digit)| match i % 2 {
+				1 if digit * 2 > 9 => digit * 2 - 9,
+				1 => digit * 2,
+				_ => digit,
+			})
+			.sum::<u32>();
+		sum % 10 == 0
+	}
+
+	fn is_valid_iban(iban: &str) -> bool {
+		let (start, account) = iban.split_at(4);
+		let remainder = account
+			.chars()
+			.chain(start.chars())
+			.map(|c| c.to_digit(36).unwrap().to_string())
+			.collect::<String>()
+			.chars()
+			.fold(0, |remainder, c| {
+				(remainder * 10 + c.to_digit(10).unwrap()) % 97
+			});
+		remainder == 1
+	}
+
+	#[test]
+	fn payment_numbers_pass_their_checksums() {
+		assert!(passes_luhn("4111111111111111"));
+		assert!(!passes_luhn("4111111111111112"));
+		assert!(is_valid_iban("DE89370400440532013000"));
+		assert!(!is_valid_iban("DE88370400440532013000"));
+
+		let card_number = SQLColumn::new("card_number", SQLType::Varchar(19));
+		let iban = SQLColumn::new("iban", SQLType::Varchar(34));
+		let bic = SQLColumn::new("bic", SQLType::Varchar(11));
+		assert_eq!(
+			generate_guess(&card_number),
+			SQLValueGuess::String(19, SQLStringValueGuess::CreditCardNumber)
+		);
+		assert_eq!(
+			generate_guess(&iban),
+			SQLValueGuess::String(34, SQLStringValueGuess::Iban(None))
+		);
+		assert_eq!(
+			generate_guess(&bic),
+			SQLValueGuess::String(11, SQLStringValueGuess::Bic)
+		);
+
+		let mut rng = StdRng::seed_from_u64(6);
+		let mut counter = 0;
+		let mut generate_string = |column: &SQLColumn, guess: &SQLValueGuess| match generate_value(
+			&mut rng,
+			column,
+			guess,
+			&mut counter,
+		) {
+			SQLValue::String(value) => value,
+			value => panic!("{} is not a string", value),
+		};
+		let lithuanian = SQLValueGuess::String(34, SQLStringValueGuess::Iban(Some("lt".into())));
+		for _ in 0..50 {
+			let number = generate_string(&card_number, &generate_guess(&card_number));
+			assert_eq!(number.len(), 16);
+			assert!(passes_luhn(&number), "{}", number);
+
+			let value = generate_string(&iban, &generate_guess(&iban));
+			assert!(is_valid_iban(&value), "{}", value);
+			let value = generate_string(&iban, &lithuanian);
+			assert!(value.starts_with("LT") && value.len() == 20, "{}", value);
+			assert!(is_valid_iban(&value), "{}", value);
+
+			let value = generate_string(&bic, &generate_guess(&bic));
+			assert!(value.len() == 8 || value.len() == 11, "{}", value);
+			assert!(
+				value[..6].chars().all(|c| c.is_ascii_uppercase()),
+				"{}",
+				value
+			);
+		}
+	}
+
+	#[test]
+	fn strings_are_cut_between_characters() {
+		let expected = [
+			("Žėmėlapis", "Žėmėl"),
+			("Straße", "Straß"),
+			("e\u{301}e\u{301}e\u{301}", "e\u{301}e\u{301}"),
+			("👍🏽👍🏽👍🏽", "👍🏽👍🏽"),
+			("👨\u{200D}👩\u{200D}👧\u{200D}👦", ""),
+			("ąčę", "ąčę"),
+		];
+		let mut rng = StdRng::seed_from_u64(7);
+		let mut counter = 0;
+		for sql_type in [SQLType::Varchar(5), SQLType::Char(5)] {
+			let column = SQLColumn::new("label", sql_type);
+			for (value, truncated) in expected {
+				// Characters other than placeholders are kept as they are
+				let guess = SQLValueGuess::String(5, SQLStringValueGuess::Pattern(value.into()));
+				assert_eq!(
+					generate_value(&mut rng, &column, &guess, &mut counter),
+					SQLValue::String(truncated.to_string())
+				);
+			}
+		}
+	}
+
+	#[test]
+	fn inverted_and_empty_ranges_dont_panic() {
+		let mut rng = StdRng::seed_from_u64(8);
+		let mut counter = 0;
+		let int_column = SQLColumn::new("quantity", SQLType::Int);
+		let float_column = SQLColumn::new("weight", SQLType::Float);
+		let normal = SQLDistribution::Normal {
+			mean: 50.0,
+			stddev: 30.0,
+		};
+		for distribution in [SQLDistribution::Uniform, normal] {
+			let inverted_int = SQLValueGuess::Int(SQLIntValueGuess::Range {
+				min: 100,
+				max: 10,
+				step: None,
+				distribution: distribution.clone(),
+			});
+			let empty_int = SQLValueGuess::Int(SQLIntValueGuess::Range {
+				min: 7,
+				max: 7,
+				step: None,
+				distribution: distribution.clone(),
+			});
+			let inverted_float = SQLValueGuess::Float(100.0, 10.0, 2, distribution.clone());
+			let empty_float = SQLValueGuess::Float(2.5, 2.5, 2, distribution);
+			for _ in 0..50 {
+				let value = generate_value(&mut rng, &int_column, &inverted_int, &mut counter);
+				assert!(matches!(value, SQLValue::Int(10..=100)), "{:?}", value);
+				let value = generate_value(&mut rng, &int_column, &empty_int, &mut counter);
+				assert_eq!(value, SQLValue::Int(7));
+
+				let value = generate_value(&mut rng, &float_column, &inverted_float, &mut counter);
+				assert!(
+					matches!(value, SQLValue::Decimal(value, 2) if (10.0..=100.0).contains(&value)),
+					"{:?}",
+					value
+				);
+				let value = generate_value(&mut rng, &float_column, &empty_float, &mut counter);
+				assert_eq!(value, SQLValue::Decimal(2.5, 2));
+			}
+		}
+	}
+
+	#[test]
+	fn decimals_keep_trailing_zeros() {
+		let balance = SQLColumn::new("balance", SQLType::Decimal(Some((12, 4))));
+		assert!(matches!(
+			generate_guess(&balance),
+			SQLValueGuess::Float(_, _, 4, _)
+		));
+
+		let mut rng = StdRng::seed_from_u64(9);
+		let mut counter = 0;
+		let expected = [(1, "12.5"), (2, "12.50"), (4, "12.5000")];
+		for (decimals, formatted) in expected {
+			let guess = SQLValueGuess::Float(12.5, 12.5, decimals, SQLDistribution::Uniform);
+			let value = generate_value(&mut rng, &balance, &guess, &mut counter);
+			assert_eq!(value.to_string(), formatted);
+			assert_eq!(format_value(&value, &SQLOptions::default()), formatted);
+		}
+
+		let guess = SQLValueGuess::Float(0.0, 1000.0, 4, SQLDistribution::Uniform);
+		for _ in 0..50 {
+			let value = generate_value(&mut rng, &balance, &guess, &mut counter).to_string();
+			let (_, fraction) = value.split_once('.').unwrap();
+			assert_eq!(fraction.len(), 4, "{}", value);
+		}
+	}
+
+	#[test]
+	fn more_children_than_parents() {
+		let generated = generate(
+			vec![customer_table(), orders_table()],
+			&[("customer", 5), ("orders", 50)],
+		)
+		.unwrap();
+		let customer_ids = column_values(&generated, 0, 0);
+		let order_customers = column_values(&generated, 1, 1);
+		assert_eq!(order_customers.len(), 50);
+		assert!(order_customers.iter().all(|id| customer_ids.contains(id)));
+	}
+
+	#[test]
+	fn auto_increment_columns_are_inserted_by_default() {
+		let id = SQLColumn::new("id", SQLType::Int)
+			.primary_key()
+			.auto_increment();
+		assert!(!is_omitted_from_insert(Some(&generate_value_guess(&id))));
+	}
+
+	#[test]
+	fn inserts_rows_without_any_listed_columns() {
+		let table = Rc::new(SQLTable::new(
+			"customer",
+			vec![SQLColumn::new("id", SQLType::Int)
+				.primary_key()
+				.auto_increment()],
+		));
+		let generated_table = GeneratedTable {
+			table: table.clone(),
+			columns: table.columns.clone(),
+			rows: vec![vec![SQLValue::Int(1)], vec![SQLValue::Int(2)]],
+			deferred_values: vec![],
+			omitted_columns: vec![0],
+		};
+		let expected = [
+			(SQLDialect::MySQL, "INSERT INTO customer () VALUES ();\n"),
+			(
+				SQLDialect::Postgres,
+				"INSERT INTO customer DEFAULT VALUES;\n",
+			),
+			(
+				SQLDialect::SQLServer,
+				"INSERT INTO customer DEFAULT VALUES;\n",
+			),
+			(
+				SQLDialect::Oracle,
+				"INSERT INTO customer VALUES (DEFAULT);\n",
+			),
+		];
+		for (dialect, statement) in expected {
+			let options = SQLOptions {
+				dialect,
+				..Default::default()
+			};
+			assert_eq!(
+				table_to_sql(&generated_table, &options),
+				statement.repeat(2)
+			);
+		}
+	}
+
+	#[test]
+	fn values_are_written_in_each_dialect() {
+		let table = Rc::new(SQLTable::new(
+			"customer",
+			vec![
+				SQLColumn::new("active", SQLType::Bool),
+				SQLColumn::new("note", SQLType::Varchar(50)),
+				SQLColumn::new("joined", SQLType::Date),
+			],
+		));
+		let generated_table = GeneratedTable {
+			table: table.clone(),
+			columns: table.columns.clone(),
+			rows: vec![vec![
+				SQLValue::Bool(true),
+				SQLValue::String(r"it's C:\temp".into()),
+				SQLValue::Date(NaiveDate::from_ymd_opt(2023, 3, 1).unwrap()),
+			]],
+			deferred_values: vec![],
+			omitted_columns: vec![],
+		};
+		let expected = [
+			(
+				SQLDialect::MySQL,
+				r"`customer` (`active`, `note`, `joined`)",
+				r"(1, 'it''s C:\\temp', '2023-03-01')",
+			),
+			(
+				SQLDialect::Postgres,
+				r#""customer" ("active", "note", "joined")"#,
+				r"(TRUE, 'it''s C:\temp', '2023-03-01')",
+			),
+			(
+				SQLDialect::SQLite,
+				r#""customer" ("active", "note", "joined")"#,
+				r"(1, 'it''s C:\temp', '2023-03-01')",
+			),
+			(
+				SQLDialect::SQLServer,
+				r"[customer] ([active], [note], [joined])",
+				r"(1, N'it''s C:\temp', N'2023-03-01')",
+			),
+			(
+				SQLDialect::Oracle,
+				r#""customer" ("active", "note", "joined")"#,
+				r"(1, 'it''s C:\temp', TO_DATE('2023-03-01', 'YYYY-MM-DD'))",
+			),
+		];
+		for (dialect, header, row) in expected {
+			let options = SQLOptions {
+				dialect,
+				quote_identifiers: true,
+				..Default::default()
+			};
+			let (table_name, columns) = header.split_once(' ').unwrap();
+			assert_eq!(
+				table_to_sql(&generated_table, &options),
+				format!(
+					"INSERT INTO {}\n{}{}\nVALUES\n{}{};\n",
+					table_name, INDENT, columns, INDENT, row
+				),
+				"{:?}",
+				dialect
+			);
+		}
+	}
+
+	#[test]
+	fn reserved_words_are_always_quoted() {
+		let table = Rc::new(SQLTable::new(
+			"Order",
+			vec![
+				SQLColumn::new("id", SQLType::Int),
+				SQLColumn::new("Group", SQLType::Int),
+			],
+		));
+		let generated_table = GeneratedTable {
+			table: table.clone(),
+			columns: table.columns.clone(),
+			rows: vec![vec![SQLValue::Int(1), SQLValue::Int(2)]],
+			deferred_values: vec![],
+			omitted_columns: vec![],
+		};
+		let expected = [
+			(SQLDialect::MySQL, "`Order`", "(id, `Group`)"),
+			(SQLDialect::Postgres, r#""Order""#, r#"(id, "Group")"#),
+			(SQLDialect::SQLite, r#""Order""#, r#"(id, "Group")"#),
+			(SQLDialect::SQLServer, "[Order]", "(id, [Group])"),
+			(SQLDialect::Oracle, r#""Order""#, r#"(id, "Group")"#),
+		];
+		for (dialect, table_name, columns) in expected {
+			let options = SQLOptions {
+				dialect,
+				quote_identifiers: false,
+				..Default::default()
+			};
+			assert_eq!(
+				table_to_sql(&generated_table, &options),
+				format!(
+					"INSERT INTO {}\n{}{}\nVALUES\n{}(1, 2);\n",
+					table_name, INDENT, columns, INDENT
+				),
+				"{:?}",
+				dialect
+			);
+		}
+	}
+
+	#[test]
+	fn inserts_are_wrapped_in_a_transaction() {
+		let table = Rc::new(customer_table());
+		let generated = GeneratedData {
+			tables: vec![GeneratedTable {
+				table: table.clone(),
+				columns: table.columns.clone(),
+				rows: vec![],
+				deferred_values: vec![],
+				omitted_columns: vec![],
+			}],
+			seed: 0,
+			warnings: vec![],
+		};
+		assert_eq!(sql_script_start(&generated, &SQLOptions::default()), "");
+		assert_eq!(sql_script_end(&generated, &SQLOptions::default()), "");
+
+		let expected = [
+			(
+				SQLDialect::MySQL,
+				"SET FOREIGN_KEY_CHECKS = 0;\nSTART TRANSACTION;\n",
+				"COMMIT;\nSET FOREIGN_KEY_CHECKS = 1;\n",
+			),
+			(
+				SQLDialect::Postgres,
+				"BEGIN;\nSET CONSTRAINTS ALL DEFERRED;\n",
+				"COMMIT;\n",
+			),
+			(
+				SQLDialect::SQLite,
+				"BEGIN TRANSACTION;\nPRAGMA defer_foreign_keys = ON;\n",
+				"COMMIT;\n",
+			),
+			(
+				SQLDialect::SQLServer,
+				"BEGIN TRANSACTION;\nALTER TABLE customer NOCHECK CONSTRAINT ALL;\n",
+				"ALTER TABLE customer WITH CHECK CHECK CONSTRAINT ALL;\nCOMMIT;\n",
+			),
+			(
+				SQLDialect::Oracle,
+				"ALTER SESSION SET CONSTRAINTS = DEFERRED;\n",
+				"COMMIT;\n",
+			),
+		];
+		for (dialect, start, end) in expected {
+			let options = SQLOptions {
+				dialect,
+				transaction: true,
+				disable_foreign_key_checks: true,
+				..Default::default()
+			};
+			assert_eq!(
+				sql_script_start(&generated, &options),
+				start,
+				"{:?}",
+				dialect
+			);
+			assert_eq!(sql_script_end(&generated, &options), end, "{:?}", dialect);
+		}
+	}
+
+	#[test]
+	fn warns_about_short_ipv6_columns() {
+		let table = Rc::new(SQLTable::new(
+			"session",
+			vec![SQLColumn::new("ipv6", SQLType::Varchar(15))],
+		));
+		let guessess = [HashMap::from([(
+			"ipv6".to_string(),
+			SQLValueGuess::String(15, SQLStringValueGuess::IPv6),
+		)])];
+		let generated = generate_rows(
+			&[table],
+			&guessess.iter().collect::<Vec<_>>(),
+			&GenerateOptions::default(),
+		)
+		.unwrap();
+		assert_eq!(generated.warnings.len(), 1);
+		assert!(generated.warnings[0].contains("session.ipv6"));
+		assert!(generated.tables[0]
+			.rows
+			.iter()
+			.all(|row| matches!(&row[0], SQLValue::String(ip) if ip.len() <= 15)));
+	}
+
+	#[test]
+	fn postal_codes_keep_their_format() {
+		let mut rng = StdRng::seed_from_u64(3);
+		let mut counter = 0;
+		for size in [3, 5, 7, 10] {
+			let column = SQLColumn::new("zip", SQLType::Char(size));
+			let guess = SQLValueGuess::String(size as usize, SQLStringValueGuess::PostalCode);
+			for _ in 0..50 {
+				let SQLValue::String(code) =
+					generate_value(&mut rng, &column, &guess, &mut counter)
+				else {
+					panic!("postal code is not a string");
+				};
+				assert!(
+					code.len() <= (size as usize).min(5),
+					"{} in CHAR({})",
+					code,
+					size
+				);
+				assert!(
+					code.len() >= (size as usize).min(3),
+					"{} in CHAR({})",
+					code,
+					size
+				);
+				assert!(code.chars().all(|c| c.is_ascii_digit()), "{}", code);
+			}
+		}
+	}
+
+	#[test]
+	fn self_references_form_a_tree() {
+		let category = SQLTable::new(
+			"category",
+			vec![
+				SQLColumn::new("id", SQLType::Int)
+					.primary_key()
+					.auto_increment(),
+				SQLColumn::new("parent_id", SQLType::Int)
+					.nullable()
+					.foreign_key("category", "id"),
+			],
+		);
+		let generated = generate(vec![category], &[("category", 40)]).unwrap();
+		let rows = &generated.tables[0].rows;
+
+		let mut child_counts = HashMap::new();
+		for (row_idx, row) in rows.iter().enumerate() {
+			if row[1] == SQLValue::Null {
+				continue;
+			}
+			// Parents always come before their children, so there are no cycles
+			let parent_idx = rows[..row_idx]
+				.iter()
+				.position(|parent| parent[0] == row[1]);
+			assert!(
+				parent_idx.is_some(),
+				"row {} has parent {}",
+				row_idx,
+				row[1]
+			);
+			*child_counts.entry(row[1].clone()).or_insert(0) += 1;
+		}
+		assert_eq!(rows[0][1], SQLValue::Null);
+		assert!(child_counts.values().any(|count| *count > 1));
+	}
+
+	#[test]
+	fn foreign_key_distributions_repeat_referenced_rows() {
+		for distribution in [
+			SQLForeignKeyValueGuess::Uniform,
+			SQLForeignKeyValueGuess::Skewed,
+			SQLForeignKeyValueGuess::CoverAll,
+		] {
+			let tables = [customer_table(), orders_table()].map(Rc::new);
+			let mut guessess = tables
+				.iter()
+				.map(|table| generate_table_guessess(table))
+				.collect::<Vec<_>>();
+			guessess[1].insert(
+				"customer_id".into(),
+				SQLValueGuess::ForeignKey(distribution.clone()),
+			);
+			let options = GenerateOptions {
+				rows_per_table: 30,
+				table_rows: HashMap::from([("customer".into(), 10)]),
+				seed: Some(2),
+				..Default::default()
+			};
+			let generated =
+				generate_rows(&tables, &guessess.iter().collect::<Vec<_>>(), &options).unwrap();
+
+			let customer_ids = column_values(&generated, 0, 0);
+			let order_customers = column_values(&generated, 1, 1);
+			assert_eq!(order_customers.len(), 30, "{:?}", distribution);
+			assert!(order_customers.iter().all(|id| customer_ids.contains(id)));
+			if distribution == SQLForeignKeyValueGuess::CoverAll {
+				// 30 orders go around the 10 customers exactly 3 times
+				for id in &customer_ids {
+					let count = order_customers.iter().filter(|value| *value == id).count();
+					assert_eq!(count, 3);
+				}
+			}
+		}
+	}
+}
@@ -0,0 +1,144 @@
+use std::{
+	collections::{BTreeMap, HashMap},
+	ops::{Deref, DerefMut},
+	rc::Rc,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+	generate_sql::{
+		check_constraint_range, check_time_range, parse_fixed_value, GenerateOptions,
+		SQLStringValueGuess, SQLValueGuess, IPV6_MAX_LENGTH,
+	},
+	magicdraw_parser::{SQLColumn, SQLTable},
+};
+
+/// Generator settings which can be saved to a file and loaded again, either in the app
+/// or with the `--config` option of the command line tool
+#[derive(Debug, Default, PartialEq, Clone, Deserialize, Serialize)]
+pub struct GeneratorConfig {
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub rows_per_table: Option<u32>,
+	/// Row counts of specific tables, same as `GenerateOptions::table_rows`
+	#[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+	pub table_rows: BTreeMap<String, u32>,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub seed: Option<u64>,
+	/// Guesses keyed by `table.column`, where the table name includes its schema
+	#[serde(default)]
+	pub columns: BTreeMap<String, SQLValueGuess>,
+}
+
+impl GeneratorConfig {
+	pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+		serde_json::from_str(json)
+	}
+
+	pub fn to_json(&self) -> String {
+		serde_json::to_string_pretty(self).unwrap()
+	}
+}
+
+fn column_key(table: &SQLTable, column: &str) -> String {
+	format!("{}.{}", table.qualified_name(), column)
+}
+
+pub fn export_config<G: Deref<Target = HashMap<String, SQLValueGuess>>>(
+	tables: &[Rc<SQLTable>],
+	value_guessess: &[G],
+	options: &GenerateOptions,
+) -> GeneratorConfig {
+	let mut columns = BTreeMap::new();
+	for (table, guessess) in tables.iter().zip(value_guessess) {
+		for (column, guess) in guessess.iter() {
+			columns.insert(column_key(table, column), guess.clone());
+		}
+	}
+
+	GeneratorConfig {
+		rows_per_table: Some(options.rows_per_table),
+		table_rows: options.table_rows.clone().into_iter().collect(),
+		seed: options.seed,
+		columns,
+	}
+}
+
+// Fixed values and value lists are free text, so they can easily be wrong. Other guesses
+// can be set to something that doesn't fit the column.
+fn check_guess(column: &SQLColumn, guess: &SQLValueGuess) -> Option<String> {
+	match guess {
+		SQLValueGuess::Nullable(_, guess) => check_guess(column, guess),
+		SQLValueGuess::Fixed(value) => parse_fixed_value(column, value).err(),
+		SQLValueGuess::String(max_size, SQLStringValueGuess::IPv6)
+			if *max_size < IPV6_MAX_LENGTH =>
+		{
+			Some(format!(
+				"IPv6 addresses don't fit into {} characters, IPv4 addresses are generated instead",
+				max_size
+			))
+		}
+		SQLValueGuess::FromList(values) if values.is_empty() => {
+			Some("list of values is empty".into())
+		}
+		SQLValueGuess::FromList(values) => values
+			.iter()
+			.find_map(|value| parse_fixed_value(column, value).err()),
+		SQLValueGuess::Int(_) | SQLValueGuess::Float(..) => check_constraint_range(column, guess),
+		SQLValueGuess::Date(guess)
+		| SQLValueGuess::Time(guess)
+		| SQLValueGuess::Datetime(guess) => check_time_range(guess),
+		_ => None,
+	}
+}
+
+/// Overwrites guesses and options with the ones from the config. Columns which are
+/// not in the tables are skipped, and a warning is returned for each of them.
+pub fn apply_config<G: DerefMut<Target = HashMap<String, SQLValueGuess>>>(
+	config: &GeneratorConfig,
+	tables: &[Rc<SQLTable>],
+	value_guessess: &mut [G],
+	options: &mut GenerateOptions,
+) -> Vec<String> {
+	let mut warnings = vec![];
+
+	if let Some(rows_per_table) = config.rows_per_table {
+		options.rows_per_table = rows_per_table;
+	}
+	if config.seed.is_some() {
+		options.seed = config.seed;
+	}
+	for (table_name, rows) in &config.table_rows {
+		let is_known = tables
+			.iter()
+			.any(|table| table.qualified_name().eq(table_name) || table.name.eq(table_name));
+		if !is_known {
+			warnings.push(format!("Table '{}' from config was not found", table_name));
+		}
+		options.table_rows.insert(table_name.clone(), *rows);
+	}
+
+	for (key, guess) in &config.columns {
+		let found = tables
+			.iter()
+			.zip(value_guessess.iter_mut())
+			.find_map(|(table, guessess)| {
+				let column = key
+					.strip_prefix(&table.qualified_name())?
+					.strip_prefix('.')?;
+				let column = table.columns.iter().find(|c| c.name == column)?;
+				Some((column, guessess.get_mut(&column.name)?))
+			});
+		match found {
+			Some((column, current)) => {
+				if let Some(problem) = check_guess(column, guess) {
+					warnings.push(format!("Column '{}' from config: {}", key, problem));
+				}
+				*current = guess.clone()
+			}
+			None => warnings.push(format!("Column '{}' from config was not found", key)),
+		}
+	}
+
+	warnings
+}
@@ -0,0 +1,12 @@
+pub mod generate_csv;
+pub mod generate_ddl;
+pub mod generate_json;
+pub mod generate_sql;
+pub mod generator_config;
+pub mod magicdraw_parser;
+pub mod reserved_words;
+#[cfg(feature = "wasm-api")]
+pub mod wasm_api;
+
+pub use generate_sql::{generate_fake_entries, generate_table_guessess, SQLValueGuess};
+pub use magicdraw_parser::{parse_project, SQLTableCollection};
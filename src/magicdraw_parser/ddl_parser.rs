@@ -1,14 +1,17 @@
 use std::io::{Read, Seek};
 
-use anyhow::{Context, Ok, Result};
 use xml::attribute::OwnedAttribute;
 use xml::name::OwnedName;
-use xml::{reader::XmlEvent, EventReader};
+use xml::reader::XmlEvent;
 use zip::ZipArchive;
 
 use crate::magicdraw_parser::utils::get_attribute;
 
-use super::utils::{check_attribute, check_name, parse_element, MyEventReader};
+use super::utils::{
+	check_attribute, check_name, open_zip_entry, parse_element, MyEventReader, ParseProjectError,
+};
+
+pub const DDL_FILE_NAME: &str = "personal-com.nomagic.magicdraw.ce.dmn.personaldmncodeengineering";
 
 #[derive(Debug)]
 pub struct DDLClass {
@@ -19,6 +22,7 @@ pub struct DDLClass {
 #[derive(Debug)]
 pub struct DDLScript {
 	pub script_id: String,
+	pub name: Option<String>,
 	pub classess: Vec<DDLClass>,
 }
 
@@ -37,7 +41,7 @@ fn get_id_from_href(attrs: &[OwnedAttribute]) -> Option<String> {
 fn parse_class<R: Read>(
 	parser: &mut MyEventReader<R>,
 	attrs: &[OwnedAttribute],
-) -> Result<DDLClass> {
+) -> Result<DDLClass, ParseProjectError> {
 	let mut property_ids = vec![];
 	let mut class_id = None;
 
@@ -55,13 +59,16 @@ fn parse_class<R: Read>(
 		if is_model_element(&name, &attrs) && class_id.is_none() {
 			class_id = get_id_from_href(&attrs);
 		} else if is_property_element(&name, &attrs) {
-			property_ids.push(get_id_from_href(&attrs).context("Property id not found")?);
+			property_ids.push(
+				get_id_from_href(&attrs)
+					.ok_or(ParseProjectError::MissingElement("DDL property id"))?,
+			);
 		}
 		Ok(())
 	})?;
 
 	Ok(DDLClass {
-		class_id: class_id.context("Missing dll class id")?,
+		class_id: class_id.ok_or(ParseProjectError::MissingElement("DDL class id"))?,
 		property_ids,
 	})
 }
@@ -69,9 +76,10 @@ fn parse_class<R: Read>(
 fn parse_script<R: Read>(
 	parser: &mut MyEventReader<R>,
 	attrs: &[OwnedAttribute],
-) -> Result<DDLScript> {
+) -> Result<DDLScript, ParseProjectError> {
 	let mut classess = vec![];
 	let mut script_id = None;
+	let name = get_attribute(attrs, None, "name").ok().map(str::to_string);
 
 	fn is_model_element(name: &OwnedName, attributes: &[OwnedAttribute]) -> bool {
 		check_name(name, None, "modelElement")
@@ -98,7 +106,8 @@ fn parse_script<R: Read>(
 	})?;
 
 	Ok(DDLScript {
-		script_id: script_id.context("Missing script id")?,
+		script_id: script_id.ok_or(ParseProjectError::MissingElement("DDL script id"))?,
+		name,
 		classess,
 	})
 }
@@ -106,7 +115,7 @@ fn parse_script<R: Read>(
 fn parse_project<R: Read>(
 	parser: &mut MyEventReader<R>,
 	attrs: &[OwnedAttribute],
-) -> Result<DDLProject> {
+) -> Result<DDLProject, ParseProjectError> {
 	let mut scripts = vec![];
 	let mut model_id = None;
 
@@ -135,17 +144,21 @@ fn parse_project<R: Read>(
 	})?;
 
 	Ok(DDLProject {
-		model_id: model_id.context("Missing model id")?,
+		model_id: model_id.ok_or(ParseProjectError::MissingElement("DDL model id"))?,
 		scripts,
 	})
 }
 
-pub fn parse_ddl_scripts<R: Read + Seek>(project: &mut ZipArchive<R>) -> Result<Vec<DDLProject>> {
+pub fn parse_ddl_scripts<R: Read + Seek>(
+	project: &mut ZipArchive<R>,
+) -> Result<Vec<DDLProject>, ParseProjectError> {
 	let mut ddl_scripts = vec![];
 
-	let file =
-		project.by_name("personal-com.nomagic.magicdraw.ce.dmn.personaldmncodeengineering")?;
-	let mut parser: MyEventReader<_> = EventReader::new(file).into();
+	let file = open_zip_entry(project, DDL_FILE_NAME).map_err(|e| match e {
+		ParseProjectError::MissingZipEntry(_) => ParseProjectError::MissingDDLScript,
+		e => e,
+	})?;
+	let mut parser = MyEventReader::new(DDL_FILE_NAME, file);
 
 	fn is_project_element(name: &OwnedName, attributes: &[OwnedAttribute]) -> bool {
 		check_name(name, None, "contents")
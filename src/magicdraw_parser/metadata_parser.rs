@@ -0,0 +1,95 @@
+use std::io::{Read, Seek};
+
+use serde::{Deserialize, Serialize};
+use xml::reader::XmlEvent;
+use zip::ZipArchive;
+
+use super::{
+	uml_model_parser::{UMLModel, MODEL_FILE_NAME},
+	utils::{check_name, get_element_characters, open_zip_entry, MyEventReader, ParseProjectError},
+};
+
+/// What was loaded, so data isn't accidentally generated from the wrong version of a model
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+pub struct ProjectMetadata {
+	pub name: Option<String>,
+	pub tool_version: Option<String>,
+	/// Date of the last save, formatted as `YYYY-MM-DD`
+	pub modified: Option<String>,
+}
+
+// The XMI header comes before the model, so reading stops as soon as the model starts
+pub fn parse_tool_version<R: Read>(
+	file_name: &str,
+	file: R,
+) -> Result<Option<String>, ParseProjectError> {
+	let mut parser = MyEventReader::new(file_name, file);
+	loop {
+		match parser.next()? {
+			XmlEvent::StartElement { name, .. } => {
+				if check_name(&name, Some("xmi"), "exporterVersion") {
+					let version = get_element_characters(&mut parser)?.trim().to_string();
+					return Ok(Some(version).filter(|version| !version.is_empty()));
+				} else if check_name(&name, Some("uml"), "Model") {
+					break;
+				}
+			}
+			XmlEvent::EndDocument => break,
+			_ => {}
+		}
+	}
+
+	Ok(None)
+}
+
+fn get_project_name(models: &[UMLModel]) -> Option<String> {
+	models
+		.iter()
+		.map(|model| model.name.trim())
+		.find(|name| !name.is_empty())
+		.map(str::to_string)
+}
+
+pub fn parse_project_metadata<R: Read + Seek>(
+	project: &mut ZipArchive<R>,
+	models: &[UMLModel],
+) -> Result<ProjectMetadata, ParseProjectError> {
+	// Fragmented projects have no single model file to take these from
+	let model_file = match open_zip_entry(project, MODEL_FILE_NAME) {
+		Ok(model_file) => model_file,
+		Err(ParseProjectError::MissingZipEntry(_)) => {
+			return Ok(ProjectMetadata {
+				name: get_project_name(models),
+				..Default::default()
+			})
+		}
+		Err(e) => return Err(e),
+	};
+	let modified = model_file.last_modified();
+	let modified = format!(
+		"{:04}-{:02}-{:02}",
+		modified.year(),
+		modified.month(),
+		modified.day()
+	);
+	let tool_version = parse_tool_version(MODEL_FILE_NAME, model_file)?;
+
+	Ok(ProjectMetadata {
+		name: get_project_name(models),
+		tool_version,
+		modified: Some(modified),
+	})
+}
+
+/// Plain XML exports carry no save date, only what's written in the XMI header
+pub fn parse_xml_metadata<R: Read>(
+	file_name: &str,
+	file: R,
+	models: &[UMLModel],
+) -> Result<ProjectMetadata, ParseProjectError> {
+	Ok(ProjectMetadata {
+		name: get_project_name(models),
+		tool_version: parse_tool_version(file_name, file)?,
+		modified: None,
+	})
+}
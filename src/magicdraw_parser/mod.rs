@@ -1,33 +1,48 @@
 mod ddl_parser;
+mod metadata_parser;
+mod model_index;
 mod sql_types_parser;
 mod uml_model_parser;
 mod utils;
+pub use metadata_parser::ProjectMetadata;
 use serde::{Deserialize, Serialize};
+pub use sql_types_parser::{SQLTypeName, TypeMapping};
+pub use utils::ParseProjectError;
 
-use anyhow::{Context, Result};
 use lazy_regex::regex_captures;
 use std::{
-	collections::HashSet,
+	borrow::Borrow,
+	collections::{HashMap, HashSet},
 	fmt::Display,
-	io::{Read, Seek},
+	future::Future,
+	io::{Cursor, Read, Seek},
 };
 use zip::ZipArchive;
 
 use crate::unwrap_opt_continue;
 
+use self::utils::block_on_ready;
+
 use self::{
-	ddl_parser::parse_ddl_scripts,
-	sql_types_parser::{parse_sql_types, SQLTypeName},
+	ddl_parser::{parse_ddl_scripts, DDLClass, DDLProject, DDL_FILE_NAME},
+	metadata_parser::{parse_project_metadata, parse_xml_metadata},
+	model_index::ModelIndex,
+	sql_types_parser::{
+		get_type_package_name, parse_sql_types, parse_sql_types_file, parse_type_name,
+		ParsedSQLTypes, META_MODEL_FILE_NAME,
+	},
 	uml_model_parser::{
-		parse_uml_model, UMLClass, UMLForeignKeyModifier, UMLModel, UMLModifier,
-		UMLNullableModifier, UMLPrimaryKeyModifier, UMLTypeModifier,
+		is_model_fragment_file, parse_uml_model, parse_uml_model_file, UMLAutoIncrementModifier,
+		UMLClass, UMLForeignKeyModifier, UMLIndexModifier, UMLModel, UMLModifier,
+		UMLNullableModifier, UMLProperty, UMLTypeModifier, UMLUniqueModifier, MODEL_FILE_NAME,
 	},
 };
 
-#[derive(Debug, PartialEq, Deserialize, Serialize)]
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Deserialize, Serialize)]
 pub enum SQLType {
 	Int,
-	Decimal,
+	/// Precision and scale
+	Decimal(Option<(u8, u8)>),
 	Date,
 	Time,
 	Datetime,
@@ -35,13 +50,30 @@ pub enum SQLType {
 	Bool,
 	Char(u8),
 	Varchar(u16),
+	/// String without a declared size
+	Text,
+}
+
+impl SQLType {
+	/// Whether values of this type can be stored in a column of the other type
+	pub fn is_compatible_with(&self, other: &SQLType) -> bool {
+		use SQLType::*;
+		matches!(
+			(self, other),
+			(Char(_) | Varchar(_) | Text, Char(_) | Varchar(_) | Text)
+				| (Decimal(_) | Float, Decimal(_) | Float)
+		) || std::mem::discriminant(self) == std::mem::discriminant(other)
+	}
 }
 
 impl Display for SQLType {
 	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
 		match self {
 			SQLType::Int => write!(f, "INT"),
-			SQLType::Decimal => write!(f, "DECIMAL"),
+			SQLType::Decimal(None) => write!(f, "DECIMAL"),
+			SQLType::Decimal(Some((precision, scale))) => {
+				write!(f, "DECIMAL({},{})", precision, scale)
+			}
 			SQLType::Date => write!(f, "DATE"),
 			SQLType::Time => write!(f, "TIME"),
 			SQLType::Datetime => write!(f, "DATETIME"),
@@ -49,200 +81,598 @@ impl Display for SQLType {
 			SQLType::Bool => write!(f, "BOOL"),
 			SQLType::Char(size) => write!(f, "CHAR({})", size),
 			SQLType::Varchar(size) => write!(f, "VARCHAR({})", size),
+			SQLType::Text => write!(f, "TEXT"),
 		}
 	}
 }
 
-#[derive(Debug, PartialEq, Deserialize, Serialize)]
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
 pub enum SQLCheckConstraint {
 	OneOf(Vec<String>),
+	Range(Option<f64>, Option<f64>),
 	Freeform(String),
 }
 
-#[derive(Debug, PartialEq, Deserialize, Serialize)]
+impl Display for SQLCheckConstraint {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			SQLCheckConstraint::OneOf(variants) => {
+				let variants = variants
+					.iter()
+					.map(|v| format!("'{}'", v))
+					.collect::<Vec<_>>();
+				write!(f, "in ({})", variants.join(", "))
+			}
+			SQLCheckConstraint::Range(Some(min), Some(max)) => {
+				write!(f, "between {} and {}", min, max)
+			}
+			SQLCheckConstraint::Range(Some(min), None) => write!(f, ">= {}", min),
+			SQLCheckConstraint::Range(None, Some(max)) => write!(f, "<= {}", max),
+			SQLCheckConstraint::Range(None, None) => Ok(()),
+			SQLCheckConstraint::Freeform(body) => write!(f, "{}", body),
+		}
+	}
+}
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Deserialize, Serialize)]
+pub struct SQLMultiplicity {
+	pub lower: u32,
+	/// `None` stands for an unlimited upper bound ("*")
+	pub upper: Option<u32>,
+}
+
+impl Display for SQLMultiplicity {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match (self.lower, self.upper) {
+			(0, None) => write!(f, "*"),
+			(lower, None) => write!(f, "{}..*", lower),
+			(lower, Some(upper)) if lower == upper => write!(f, "{}", lower),
+			(lower, Some(upper)) => write!(f, "{}..{}", lower, upper),
+		}
+	}
+}
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Deserialize, Serialize)]
+pub struct SQLForeignKey {
+	/// Constraint name, like `fk_order_customer`, if the model gives one
+	#[serde(default)]
+	pub name: Option<String>,
+	pub schema: Option<String>,
+	pub table: String,
+	pub column: String,
+	/// How many rows of the referencing table may point to the same referenced row
+	pub cardinality: Option<SQLMultiplicity>,
+}
+
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
 pub struct SQLColumn {
+	/// Position of the column in the DDL script, skipped properties leave gaps
+	pub ordinal: usize,
 	pub name: String,
 	pub sql_type: SQLType,
 	pub primary_key: bool,
+	/// Values are assigned by the database, from the model or guessed for integer primary keys
+	#[serde(default)]
+	pub auto_increment: bool,
 	pub nullable: bool,
-	pub foreign_key: Option<(String, String)>,
-	pub check_constraint: Option<SQLCheckConstraint>,
+	pub foreign_key: Option<SQLForeignKey>,
+	pub check_constraints: Vec<SQLCheckConstraint>,
+}
+
+#[derive(Debug, Default, PartialEq, Eq, Hash, Clone, Copy, Deserialize, Serialize)]
+pub enum TableKind {
+	#[default]
+	Table,
+	/// Rows can't be inserted into views, so they are skipped by default
+	View,
 }
 
-#[derive(Debug, PartialEq, Deserialize, Serialize)]
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
+pub struct SQLIndex {
+	pub name: Option<String>,
+	pub columns: Vec<String>,
+	pub unique: bool,
+}
+
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
 pub struct SQLTable {
+	pub schema: Option<String>,
 	pub name: String,
+	#[serde(default)]
+	pub kind: TableKind,
 	pub columns: Vec<SQLColumn>,
+	/// Fixed rows of a lookup table built from a UML enumeration
+	pub literals: Option<Vec<String>>,
+	/// Column names whose combined values must be unique, single columns included
+	#[serde(default)]
+	pub unique_constraints: Vec<Vec<String>>,
+	#[serde(default)]
+	pub indexes: Vec<SQLIndex>,
 }
 
-#[derive(Debug, PartialEq, Deserialize, Serialize)]
-pub struct SQLTableCollection {
-	pub tables: Vec<SQLTable>,
+const PLACEHOLDER_SQL_TYPE: SQLType = SQLType::Varchar(255);
+const LOOKUP_ID_COLUMN: &str = "id";
+const LOOKUP_NAME_COLUMN: &str = "name";
+
+fn qualify_name(schema: &Option<String>, name: &str) -> String {
+	match schema {
+		Some(schema) => format!("{}.{}", schema, name),
+		None => name.to_string(),
+	}
 }
 
-fn find_class_by_id<'a>(models: &'a [UMLModel], id: &str) -> Option<&'a UMLClass> {
-	for model in models {
-		for package in &model.packages {
-			if let Some(class) = package.classess.iter().find(|t| t.id.eq(id)) {
-				return Some(class);
-			}
+impl SQLColumn {
+	/// A non nullable column without any keys or constraints, at ordinal 0
+	pub fn new(name: impl Into<String>, sql_type: SQLType) -> Self {
+		SQLColumn {
+			ordinal: 0,
+			name: name.into(),
+			sql_type,
+			primary_key: false,
+			auto_increment: false,
+			nullable: false,
+			foreign_key: None,
+			check_constraints: vec![],
 		}
 	}
-	None
+
+	pub fn ordinal(mut self, ordinal: usize) -> Self {
+		self.ordinal = ordinal;
+		self
+	}
+
+	pub fn primary_key(mut self) -> Self {
+		self.primary_key = true;
+		self
+	}
+
+	pub fn auto_increment(mut self) -> Self {
+		self.auto_increment = true;
+		self
+	}
+
+	pub fn nullable(mut self) -> Self {
+		self.nullable = true;
+		self
+	}
+
+	pub fn foreign_key(mut self, table: impl Into<String>, column: impl Into<String>) -> Self {
+		self.foreign_key = Some(SQLForeignKey {
+			name: None,
+			schema: None,
+			table: table.into(),
+			column: column.into(),
+			cardinality: None,
+		});
+		self
+	}
+
+	pub fn check_constraint(mut self, constraint: SQLCheckConstraint) -> Self {
+		self.check_constraints.push(constraint);
+		self
+	}
 }
 
-fn is_nullabe(modifiers: &[UMLModifier], property: &str) -> bool {
-	for modifier in modifiers {
-		if let UMLModifier::Nullable(UMLNullableModifier {
-			property_id,
-			nullable,
-		}) = modifier
-		{
-			if property_id.eq(property) {
-				return *nullable;
-			}
+impl SQLTable {
+	/// A table without a schema. Columns are numbered in the order they are given.
+	pub fn new(name: impl Into<String>, columns: Vec<SQLColumn>) -> Self {
+		let columns = columns
+			.into_iter()
+			.enumerate()
+			.map(|(ordinal, column)| column.ordinal(ordinal))
+			.collect();
+		SQLTable {
+			schema: None,
+			name: name.into(),
+			kind: TableKind::Table,
+			columns,
+			literals: None,
+			unique_constraints: vec![],
+			indexes: vec![],
 		}
 	}
-	false
+
+	/// Columns of the primary key, in the order they are in the table
+	pub fn primary_key_columns(&self) -> Vec<String> {
+		self.columns
+			.iter()
+			.filter(|column| column.primary_key)
+			.map(|column| column.name.clone())
+			.collect()
+	}
+
+	/// Primary key, unique constraints and the columns of unique indexes
+	pub fn unique_column_sets(&self) -> Vec<Vec<String>> {
+		let primary_key = Some(self.primary_key_columns()).filter(|columns| !columns.is_empty());
+		primary_key
+			.into_iter()
+			.chain(self.unique_constraints.iter().cloned())
+			.chain(
+				self.indexes
+					.iter()
+					.filter(|index| index.unique)
+					.map(|index| index.columns.clone()),
+			)
+			.collect()
+	}
+
+	/// Table name prefixed with its schema, if it has one
+	pub fn qualified_name(&self) -> String {
+		qualify_name(&self.schema, &self.name)
+	}
 }
 
-fn is_primary_key(modifiers: &[UMLModifier], property: &str) -> bool {
-	for modifier in modifiers {
-		if let UMLModifier::PirmaryKey(UMLPrimaryKeyModifier { property_id }) = modifier {
-			if property_id.eq(property) {
-				return true;
-			}
+impl SQLForeignKey {
+	/// Referenced table name prefixed with its schema, if it has one
+	pub fn qualified_table(&self) -> String {
+		qualify_name(&self.schema, &self.table)
+	}
+}
+
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
+pub struct SQLTableCollection {
+	pub name: Option<String>,
+	pub tables: Vec<SQLTable>,
+}
+
+// An element belongs to a schema when its package has the SQLProfile:Schema stereotype
+fn get_schema(index: &ModelIndex, element_id: &str) -> Option<String> {
+	let package = index.find_package(element_id)?;
+
+	let is_schema = index
+		.get_modifiers(&package.id)
+		.any(|modifier| matches!(modifier, UMLModifier::Schema(_)));
+	if is_schema {
+		package.name.clone()
+	} else {
+		None
+	}
+}
+
+fn is_nullabe(index: &ModelIndex, property: &str) -> bool {
+	for modifier in index.get_modifiers(property) {
+		if let UMLModifier::Nullable(UMLNullableModifier { nullable, .. }) = modifier {
+			return *nullable;
 		}
 	}
 	false
 }
 
-fn get_type_modifier<'a>(modifiers: &'a [UMLModifier], property: &str) -> Option<&'a str> {
-	for modifier in modifiers {
-		if let UMLModifier::Type(UMLTypeModifier {
-			property_id,
-			modifier,
-		}) = modifier
+fn get_auto_increment_modifier(index: &ModelIndex, property: &str) -> Option<bool> {
+	for modifier in index.get_modifiers(property) {
+		if let UMLModifier::AutoIncrement(UMLAutoIncrementModifier { auto_increment, .. }) =
+			modifier
 		{
-			if property_id.eq(property) {
-				return Some(modifier);
-			}
+			return Some(*auto_increment);
 		}
 	}
 	None
 }
 
-fn get_foreign_key_constraint<'a>(modifiers: &'a [UMLModifier], from_id: &str) -> Option<&'a str> {
-	for modifier in modifiers {
-		if let UMLModifier::ForeignKey(UMLForeignKeyModifier {
-			from_property_id,
-			to_property_id,
-		}) = modifier
-		{
-			if from_property_id.eq(from_id) {
-				return Some(&to_property_id);
-			}
+fn has_primary_key_modifier(index: &ModelIndex, property: &str) -> bool {
+	index
+		.get_modifiers(property)
+		.any(|modifier| matches!(modifier, UMLModifier::PirmaryKey(_)))
+}
+
+// SQLProfile primary key modifiers take precedence, the isID flag is only used for classes without them
+fn is_primary_key(index: &ModelIndex, class: &UMLClass, property: &UMLProperty) -> bool {
+	let class_has_modifiers = class
+		.properties
+		.iter()
+		.any(|property| has_primary_key_modifier(index, &property.id));
+	if class_has_modifiers {
+		has_primary_key_modifier(index, &property.id)
+	} else {
+		property.is_id
+	}
+}
+
+fn get_type_modifier<'a>(index: &ModelIndex<'a>, property: &str) -> Option<&'a str> {
+	for modifier in index.get_modifiers(property) {
+		if let UMLModifier::Type(UMLTypeModifier { modifier, .. }) = modifier {
+			return Some(modifier);
+		}
+	}
+	None
+}
+
+fn get_foreign_key_constraint<'a>(
+	index: &ModelIndex<'a>,
+	from_id: &str,
+) -> Option<&'a UMLForeignKeyModifier> {
+	for modifier in index.get_modifiers(from_id) {
+		if let UMLModifier::ForeignKey(constraint) = modifier {
+			return Some(constraint);
 		}
 	}
 	None
 }
 
 fn get_foreign_key(
-	modifiers: &[UMLModifier],
+	index: &ModelIndex,
 	classess: &[&UMLClass],
 	property: &str,
-) -> Result<Option<(String, String)>> {
-	let to_id = get_foreign_key_constraint(modifiers, property);
-	if to_id.is_none() {
+) -> Result<Option<SQLForeignKey>, ParseProjectError> {
+	let constraint = get_foreign_key_constraint(index, property);
+	if constraint.is_none() {
 		return Ok(None);
 	}
-	let to_id = to_id.unwrap();
+	let constraint = constraint.unwrap();
 
-	for class in classess {
-		for property in &class.properties {
-			if property.id.eq(to_id) {
-				let property_name = property.name.clone().context("Missing property name")?;
-				let class_name = class.name.clone().context("Missing class name")?;
-				return Ok(Some((class_name, property_name)));
-			}
-		}
+	let (class, property) = match index.find_property(&constraint.to_property_id) {
+		Some(found) => found,
+		None => return Ok(None),
+	};
+	if !classess.iter().any(|other| other.id.eq(&class.id)) {
+		return Ok(None);
 	}
 
-	Ok(None)
+	let property_name = property
+		.name
+		.clone()
+		.ok_or_else(|| ParseProjectError::MissingPropertyName(property.id.clone()))?;
+	let class_name = class
+		.name
+		.clone()
+		.ok_or_else(|| ParseProjectError::MissingClassName(class.id.clone()))?;
+	Ok(Some(SQLForeignKey {
+		name: constraint.name.clone(),
+		schema: get_schema(index, &class.id),
+		table: class_name,
+		column: property_name,
+		cardinality: None,
+	}))
+}
+
+fn find_primary_key<'a>(index: &ModelIndex, class: &'a UMLClass) -> Option<&'a UMLProperty> {
+	class
+		.properties
+		.iter()
+		.find(|property| is_primary_key(index, class, property))
+}
+
+fn get_inferred_foreign_key<'a>(
+	index: &ModelIndex,
+	classess: &[&'a UMLClass],
+	property: &UMLProperty,
+) -> Option<(&'a UMLClass, &'a UMLProperty)> {
+	let type_id = property.type_id.as_ref()?;
+	let class = classess.iter().find(|class| class.id.eq(type_id))?;
+	let primary_key = find_primary_key(index, class)?;
+	Some((class, primary_key))
+}
+
+fn find_association_opposite_end<'a>(
+	index: &ModelIndex<'a>,
+	property: &UMLProperty,
+) -> Option<&'a UMLProperty> {
+	let association_id = property.association_id.as_ref()?;
+	let association = index.find_association(association_id)?;
+
+	let opposite_id = association
+		.member_end_ids
+		.iter()
+		.find(|id| !id.eq(&&property.id))?;
+	association
+		.owned_ends
+		.iter()
+		.find(|end| end.id.eq(opposite_id))
+		.or_else(|| {
+			index
+				.find_property(opposite_id)
+				.map(|(_, property)| property)
+		})
+}
+
+// When lowerValue or upperValue are missing, UML defaults them to 1
+fn get_multiplicity(property: &UMLProperty) -> SQLMultiplicity {
+	let lower = property
+		.lower_value
+		.as_ref()
+		.and_then(|value| value.parse().ok())
+		.unwrap_or(1);
+	let upper = match property.upper_value.as_deref() {
+		Some("*") | Some("-1") => None,
+		Some(value) => Some(value.parse().unwrap_or(1)),
+		None => Some(1),
+	};
+	SQLMultiplicity { lower, upper }
 }
 
 fn parse_check_constraint(str: &str) -> SQLCheckConstraint {
 	fn try_parse_one_of(str: &str) -> Option<SQLCheckConstraint> {
-		let (_, inner) = regex_captures!(r#"^in \((.+)\)$"#, str)?;
+		let (_, inner) = regex_captures!(r#"^(?i)in\s*\((.+)\)$"#, str)?;
 		let mut variants = vec![];
-		for part in inner.split(", ") {
-			let (_, variant) = regex_captures!(r#"^'(.+)'$"#, part)?;
+		for part in inner.split(',') {
+			let (_, variant) = regex_captures!(r#"^'(.+)'$"#, part.trim())?;
 			variants.push(variant.to_string());
 		}
 
 		Some(SQLCheckConstraint::OneOf(variants))
 	}
 
-	try_parse_one_of(str).unwrap_or(SQLCheckConstraint::Freeform(str.to_string()))
+	fn try_parse_range(str: &str) -> Option<SQLCheckConstraint> {
+		if let Some((_, min, max)) =
+			regex_captures!(r#"^(?i)between\s+(-?[\d.]+)\s+and\s+(-?[\d.]+)$"#, str)
+		{
+			return Some(SQLCheckConstraint::Range(
+				Some(min.parse().ok()?),
+				Some(max.parse().ok()?),
+			));
+		}
+
+		let (_, operator, value) = regex_captures!(r#"^(>=|<=|>|<)\s*(-?[\d.]+)$"#, str)?;
+		let value = value.parse().ok()?;
+		Some(match operator {
+			">=" | ">" => SQLCheckConstraint::Range(Some(value), None),
+			_ => SQLCheckConstraint::Range(None, Some(value)),
+		})
+	}
+
+	// Bodies may be split across multiple lines or indented with tabs
+	let str = &str.split_whitespace().collect::<Vec<_>>().join(" ");
+
+	try_parse_one_of(str)
+		.or_else(|| try_parse_range(str))
+		.unwrap_or(SQLCheckConstraint::Freeform(str.to_string()))
 }
 
-// TODO: Refactor this function, less nesting would be good
-fn get_sql_check_constraint<'a>(
-	models: &'a [UMLModel],
+fn get_sql_check_constraints(
+	index: &ModelIndex,
+	class_id: &str,
 	property_name: &str,
-) -> Option<SQLCheckConstraint> {
-	for model in models {
-		for package in &model.packages {
-			for class in &package.classess {
-				for constraint in &class.constraints {
-					let prop_name = unwrap_opt_continue!(&constraint.property_name);
-					let body = unwrap_opt_continue!(&constraint.body);
-
-					if prop_name.eq(property_name) && constraint.body.is_some() {
-						return Some(parse_check_constraint(body));
-					}
-				}
-			}
-		}
-	}
-	None
+) -> Vec<SQLCheckConstraint> {
+	index
+		.get_check_constraint_bodies(class_id, property_name)
+		.iter()
+		.map(|body| parse_check_constraint(body))
+		.collect()
+}
+
+// Accepts modifiers like "(10,2)", "( 50 )" or "(255 CHAR)", unit suffixes are ignored
+fn parse_type_modifier(type_modifier: &str) -> Option<Vec<u32>> {
+	let type_modifier = type_modifier.trim();
+	let type_modifier = type_modifier
+		.strip_prefix('(')
+		.and_then(|modifier| modifier.strip_suffix(')'))
+		.unwrap_or(type_modifier);
+
+	type_modifier
+		.split(',')
+		.map(|part| {
+			let (_, number) = regex_captures!(r#"^\s*(\d+)"#, part)?;
+			number.parse().ok()
+		})
+		.collect()
 }
 
 fn get_sql_type(
-	modifiers: &[UMLModifier],
+	index: &ModelIndex,
 	type_name: SQLTypeName,
 	property: &str,
-) -> Result<SQLType> {
-	Ok(match type_name {
+	default_sizes: Option<&Vec<u32>>,
+) -> SQLType {
+	let sizes = get_type_modifier(index, property)
+		.and_then(parse_type_modifier)
+		.or_else(|| default_sizes.cloned());
+	let first_size = sizes.as_ref().and_then(|sizes| sizes.first().copied());
+
+	// Missing or unreadable sizes fall back to 31 / 255, `resolve_sql_type` reports them as warnings
+	match type_name {
 		SQLTypeName::Int => SQLType::Int,
 		SQLTypeName::Date => SQLType::Date,
 		SQLTypeName::Datetime => SQLType::Datetime,
 		SQLTypeName::Time => SQLType::Time,
 		SQLTypeName::Float => SQLType::Float,
 		SQLTypeName::Bool => SQLType::Bool,
-		SQLTypeName::Decimal => SQLType::Decimal,
-		SQLTypeName::Char => {
-			if let Some(type_modifier) = get_type_modifier(modifiers, property) {
-				let (_, size) = regex_captures!(r#"^\((\d+)\)$"#, type_modifier)
-					.context("Type modifier doesn't match format")?;
-				SQLType::Char(size.parse()?)
-			} else {
-				// TODO: Add better error message to say which table is missing type modifier
-				// For now just pick a defautl arbitrarily
-				SQLType::Char(31)
-			}
-		}
-		SQLTypeName::Varchar => {
-			if let Some(type_modifier) = get_type_modifier(modifiers, property) {
-				let (_, size) = regex_captures!(r#"^\((\d+)\)$"#, type_modifier)
-					.context("Type modifier doesn't match format")?;
-				SQLType::Varchar(size.parse()?)
-			} else {
-				// TODO: Add better error message to say which table is missing type modifier
-				// For now just pick a defautl arbitrarily
-				SQLType::Varchar(255)
-			}
-		}
+		SQLTypeName::Text => SQLType::Text,
+		SQLTypeName::Decimal => SQLType::Decimal(first_size.and_then(|precision| {
+			let scale = sizes.as_ref()?.get(1).copied().unwrap_or(0);
+			Some((precision.try_into().ok()?, scale.try_into().ok()?))
+		})),
+		SQLTypeName::Char => SQLType::Char(
+			first_size
+				.and_then(|size| size.try_into().ok())
+				.unwrap_or(31),
+		),
+		SQLTypeName::Varchar => SQLType::Varchar(
+			first_size
+				.and_then(|size| size.try_into().ok())
+				.unwrap_or(255),
+		),
+	}
+}
+
+// Returns the type modifier if it's present, but could not be understood
+fn get_invalid_type_modifier<'a>(
+	index: &ModelIndex<'a>,
+	type_name: SQLTypeName,
+	property: &str,
+) -> Option<&'a str> {
+	if !matches!(
+		type_name,
+		SQLTypeName::Char | SQLTypeName::Varchar | SQLTypeName::Decimal
+	) {
+		return None;
+	}
+
+	let type_modifier = get_type_modifier(index, property)?;
+	let sizes = parse_type_modifier(type_modifier);
+	let max_size = match type_name {
+		SQLTypeName::Varchar => u16::MAX as u32,
+		_ => u8::MAX as u32,
+	};
+	let is_valid = sizes.is_some_and(|sizes| {
+		!sizes.is_empty() && sizes.len() <= 2 && sizes.iter().all(|size| *size <= max_size)
+	});
+	(!is_valid).then_some(type_modifier)
+}
+
+fn is_missing_type_modifier(index: &ModelIndex, type_name: SQLTypeName, property: &str) -> bool {
+	matches!(type_name, SQLTypeName::Char | SQLTypeName::Varchar)
+		&& get_type_modifier(index, property).is_none()
+}
+
+// Returns `None` if the type is not a known SQL type or could not be resolved, a warning is
+// recorded in that case
+fn resolve_sql_type(
+	index: &ModelIndex,
+	sql_types: &ParsedSQLTypes,
+	type_href: &str,
+	property: &str,
+	(table, column): (&str, &str),
+	warnings: &mut Vec<ParseWarning>,
+) -> Result<Option<SQLType>, ParseProjectError> {
+	if let Some(type_name) = sql_types.unknown_type_names.get(type_href) {
+		warnings.push(ParseWarning::UnknownSQLType {
+			table: table.into(),
+			column: column.into(),
+			type_name: type_name.clone(),
+		});
+		return Ok(None);
+	}
+
+	let Some(&type_name) = sql_types.type_names.get(type_href) else {
+		warnings.push(ParseWarning::UnresolvedSQLType {
+			table: table.into(),
+			column: column.into(),
+			type_href: type_href.into(),
+			missing_package: get_type_package_name(type_href)
+				.filter(|package_name| sql_types.missing_packages.contains(*package_name))
+				.map(str::to_string),
+			loaded_packages: sql_types.loaded_packages.clone(),
+		});
+		return Ok(None);
+	};
+	let default_sizes = sql_types.default_sizes.get(type_href);
+	let sql_type = get_sql_type(index, type_name, property, default_sizes);
+	if let Some(type_modifier) = get_invalid_type_modifier(index, type_name, property) {
+		warnings.push(ParseWarning::InvalidTypeModifier {
+			table: table.into(),
+			column: column.into(),
+			type_modifier: type_modifier.into(),
+			default_type: sql_type.to_string(),
+		});
+	}
+	if default_sizes.is_none() && is_missing_type_modifier(index, type_name, property) {
+		warnings.push(ParseWarning::MissingTypeModifier {
+			table: table.into(),
+			column: column.into(),
+			default_type: sql_type.to_string(),
+		});
+	}
+
+	Ok(Some(sql_type))
+}
+
+// Locally defined primitive types may also be referenced by id through the `type` attribute
+fn get_type_href<'a>(sql_types: &ParsedSQLTypes, property: &'a UMLProperty) -> Option<&'a String> {
+	property.type_href.as_ref().or_else(|| {
+		property.type_id.as_ref().filter(|type_id| {
+			sql_types.type_names.contains_key(*type_id)
+				|| sql_types.unknown_type_names.contains_key(*type_id)
+		})
 	})
 }
 
@@ -256,63 +686,1491 @@ fn get_used_types<'a>(models: &'a [UMLModel]) -> HashSet<&'a String> {
 		.collect::<HashSet<_>>()
 }
 
-pub fn parse_project<R: Read + Seek>(project_file: R) -> Result<Vec<SQLTableCollection>> {
-	let mut zip = ZipArchive::new(project_file).unwrap();
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
+pub enum ParseWarning {
+	MissingPropertyName {
+		table: String,
+		property_id: String,
+		/// Name given to the placeholder column, if one was created
+		placeholder: Option<String>,
+	},
+	MissingPropertyType {
+		table: String,
+		column: String,
+		placeholder: bool,
+	},
+	UnknownSQLType {
+		table: String,
+		column: String,
+		type_name: String,
+	},
+	MissingTypeModifier {
+		table: String,
+		column: String,
+		default_type: String,
+	},
+	UnresolvedForeignKey {
+		table: String,
+		column: String,
+	},
+	MissingDDLScript,
+	MergedDuplicateTable {
+		table: String,
+	},
+	InvalidTypeModifier {
+		table: String,
+		column: String,
+		type_modifier: String,
+		default_type: String,
+	},
+	DuplicateColumnName {
+		table: String,
+		column: String,
+		renamed_to: String,
+	},
+	BrokenForeignKey {
+		table: String,
+		column: String,
+		foreign_table: String,
+		foreign_column: String,
+	},
+	IncompatibleForeignKeyType {
+		table: String,
+		column: String,
+		sql_type: String,
+		foreign_table: String,
+		foreign_column: String,
+		foreign_sql_type: String,
+	},
+	SkippedNonPersistentClass {
+		table: String,
+		reason: String,
+	},
+	UnresolvedDDLClass {
+		script: Option<String>,
+		class_id: String,
+	},
+	UnresolvedSQLType {
+		table: String,
+		column: String,
+		type_href: String,
+		/// Used project the type should have come from, if it wasn't found
+		missing_package: Option<String>,
+		loaded_packages: Vec<String>,
+	},
+}
+
+impl ParseWarning {
+	/// Warnings which only describe how the project was read, nothing is lost or guessed
+	pub fn is_informational(&self) -> bool {
+		matches!(
+			self,
+			ParseWarning::MissingDDLScript | ParseWarning::SkippedNonPersistentClass { .. }
+		)
+	}
+}
 
-	let (models, modifiers) = parse_uml_model(&mut zip)?;
-	let ddl_scripts = parse_ddl_scripts(&mut zip)?;
-	let sql_type_names = parse_sql_types(&mut zip, &get_used_types(&models))?;
+impl Display for ParseWarning {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		use ParseWarning::*;
+		match self {
+			MissingPropertyName {
+				table,
+				property_id,
+				placeholder: None,
+			} => {
+				write!(
+					f,
+					"{}: skipped property '{}' without a name",
+					table, property_id
+				)
+			}
+			MissingPropertyName {
+				table,
+				property_id,
+				placeholder: Some(placeholder),
+			} => {
+				write!(
+					f,
+					"{}: property '{}' has no name, added as placeholder column '{}'",
+					table, property_id, placeholder
+				)
+			}
+			MissingPropertyType {
+				table,
+				column,
+				placeholder: false,
+			} => {
+				write!(f, "{}.{}: skipped column without a type", table, column)
+			}
+			MissingPropertyType {
+				table,
+				column,
+				placeholder: true,
+			} => {
+				write!(
+					f,
+					"{}.{}: column has no type, added as a {} placeholder",
+					table, column, PLACEHOLDER_SQL_TYPE
+				)
+			}
+			UnknownSQLType {
+				table,
+				column,
+				type_name,
+			} => {
+				write!(
+					f,
+					"{}.{}: skipped column with unknown SQL type '{}'",
+					table, column, type_name
+				)
+			}
+			MissingTypeModifier {
+				table,
+				column,
+				default_type,
+			} => {
+				write!(
+					f,
+					"{}.{}: missing type modifier, defaulted to {}",
+					table, column, default_type
+				)
+			}
+			UnresolvedForeignKey { table, column } => {
+				write!(
+					f,
+					"{}.{}: referenced foreign column is not part of the DDL script",
+					table, column
+				)
+			}
+			MissingDDLScript => {
+				write!(
+					f,
+					"Project has no DDL script, tables were built from all UML classes"
+				)
+			}
+			MergedDuplicateTable { table } => {
+				write!(
+					f,
+					"{}: class was included more than once, its columns were merged",
+					table
+				)
+			}
+			InvalidTypeModifier {
+				table,
+				column,
+				type_modifier,
+				default_type,
+			} => {
+				write!(
+					f,
+					"{}.{}: could not understand type modifier '{}', defaulted to {}",
+					table, column, type_modifier, default_type
+				)
+			}
+			DuplicateColumnName {
+				table,
+				column,
+				renamed_to,
+			} => {
+				write!(
+					f,
+					"{}.{}: column name is used more than once, renamed to '{}'",
+					table, column, renamed_to
+				)
+			}
+			BrokenForeignKey {
+				table,
+				column,
+				foreign_table,
+				foreign_column,
+			} => {
+				write!(
+					f,
+					"{}.{}: referenced column {}.{} is not part of the collection, foreign key was dropped",
+					table, column, foreign_table, foreign_column
+				)
+			}
+			IncompatibleForeignKeyType {
+				table,
+				column,
+				sql_type,
+				foreign_table,
+				foreign_column,
+				foreign_sql_type,
+			} => {
+				write!(
+					f,
+					"{}.{} ({}) references {}.{} of incompatible type {}",
+					table, column, sql_type, foreign_table, foreign_column, foreign_sql_type
+				)
+			}
+			SkippedNonPersistentClass { table, reason } => {
+				write!(f, "{}: skipped {} class", table, reason)
+			}
+			UnresolvedDDLClass {
+				script: Some(script),
+				class_id,
+			} => {
+				write!(
+					f,
+					"DDL script '{}' includes class '{}' which is not in the model, its table was skipped",
+					script, class_id
+				)
+			}
+			UnresolvedDDLClass {
+				script: None,
+				class_id,
+			} => {
+				write!(
+					f,
+					"DDL script includes class '{}' which is not in the model, its table was skipped",
+					class_id
+				)
+			}
+			UnresolvedSQLType {
+				table,
+				column,
+				type_href,
+				missing_package,
+				loaded_packages,
+			} => {
+				write!(
+					f,
+					"{}.{}: skipped column, its type '{}' could not be resolved",
+					table, column, type_href
+				)?;
+				if let Some(missing_package) = missing_package {
+					let loaded_packages = if loaded_packages.is_empty() {
+						"none".into()
+					} else {
+						loaded_packages.join(", ")
+					};
+					write!(
+						f,
+						", used project '{}' was not found (loaded type libraries: {})",
+						missing_package, loaded_packages
+					)?;
+				}
+				Ok(())
+			}
+		}
+	}
+}
 
-	let mut collections = vec![];
-	for ddl_project in ddl_scripts {
-		for ddl_script in ddl_project.scripts {
-			let mut tables = vec![];
-
-			let mut model_classess = vec![];
-			for ddl_class in &ddl_script.classess {
-				let model_class = find_class_by_id(&models, &ddl_class.class_id)
-					.context("UML class not found")?;
-				model_classess.push(model_class);
+#[derive(Debug, PartialEq)]
+pub struct ParsedProject {
+	pub metadata: ProjectMetadata,
+	pub collections: Vec<SQLTableCollection>,
+	pub warnings: Vec<ParseWarning>,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum ParseMode {
+	/// Skip anything which can't be resolved and report it as a warning
+	#[default]
+	Lenient,
+	/// Fail on any anomaly, for validating a schema before a release
+	Strict,
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ParseOptions {
+	pub mode: ParseMode,
+	/// Treat properties typed by another class as foreign keys to that class's primary key,
+	/// for models which use plain UML associations instead of `SQLProfile:FK`
+	pub infer_foreign_keys: bool,
+	/// Combine all DDL scripts into a single collection instead of one collection per script
+	pub merge_ddl_scripts: bool,
+	/// Keep properties without a type or name as placeholder columns, instead of skipping them
+	pub placeholder_columns: bool,
+	/// Build tables from abstract and «transient» classes too
+	pub include_non_persistent_classes: bool,
+	/// Types from custom profiles by their lowercase name, see `parse_type_mappings`
+	pub type_mappings: HashMap<String, TypeMapping>,
+}
+
+/// Reads one mapping per line, like `Money = DECIMAL(19,4)` or `Identifier = INT`.
+/// Empty lines and lines starting with `#` are skipped.
+pub fn parse_type_mappings(text: &str) -> Result<HashMap<String, TypeMapping>, ParseProjectError> {
+	let mut type_mappings = HashMap::new();
+	for line in text.lines().map(str::trim) {
+		if line.is_empty() || line.starts_with('#') {
+			continue;
+		}
+
+		let invalid_mapping = || ParseProjectError::InvalidTypeMapping(line.into());
+		let (name, sql_type) = line.split_once('=').ok_or_else(invalid_mapping)?;
+		let (_, type_name, sizes) = regex_captures!(r#"^([^(]+)(\(.*\))?$"#, sql_type.trim())
+			.ok_or_else(invalid_mapping)?;
+		let type_name = parse_type_name(type_name).ok_or_else(invalid_mapping)?;
+		let default_sizes = if sizes.is_empty() {
+			vec![]
+		} else {
+			parse_type_modifier(sizes).ok_or_else(invalid_mapping)?
+		};
+
+		type_mappings.insert(
+			name.trim().to_lowercase(),
+			TypeMapping {
+				type_name,
+				default_sizes,
+			},
+		);
+	}
+	Ok(type_mappings)
+}
+
+// Returns the class ids which were merged
+fn merge_duplicate_ddl_classes(classess: &mut Vec<DDLClass>) -> Vec<String> {
+	let mut merged_class_ids = vec![];
+	let mut unique_classess: Vec<DDLClass> = vec![];
+	for ddl_class in classess.drain(..) {
+		if let Some(existing) = unique_classess
+			.iter_mut()
+			.find(|existing| existing.class_id == ddl_class.class_id)
+		{
+			for property_id in ddl_class.property_ids {
+				if !existing.property_ids.contains(&property_id) {
+					existing.property_ids.push(property_id);
+				}
+			}
+			if !merged_class_ids.contains(&ddl_class.class_id) {
+				merged_class_ids.push(ddl_class.class_id);
+			}
+		} else {
+			unique_classess.push(ddl_class);
+		}
+	}
+	*classess = unique_classess;
+	merged_class_ids
+}
+
+fn check_is_magicdraw_project<R: Read + Seek>(
+	zip: &ZipArchive<R>,
+) -> Result<(), ParseProjectError> {
+	let expected_files = [MODEL_FILE_NAME, META_MODEL_FILE_NAME, DDL_FILE_NAME];
+	if zip
+		.file_names()
+		.any(|name| expected_files.contains(&name) || is_model_fragment_file(name))
+	{
+		Ok(())
+	} else {
+		Err(ParseProjectError::NotAMagicDrawProject(
+			expected_files.iter().map(|name| name.to_string()).collect(),
+		))
+	}
+}
+
+struct ProjectContext<'a> {
+	models: &'a [UMLModel],
+	index: &'a ModelIndex<'a>,
+	sql_types: &'a ParsedSQLTypes,
+	options: &'a ParseOptions,
+}
+
+fn build_table(
+	ctx: &ProjectContext,
+	model_class: &UMLClass,
+	property_ids: &[String],
+	model_classess: &[&UMLClass],
+	warnings: &mut Vec<ParseWarning>,
+) -> Result<SQLTable, ParseProjectError> {
+	let name = model_class
+		.name
+		.clone()
+		.ok_or_else(|| ParseProjectError::MissingClassName(model_class.id.clone()))?;
+
+	let mut columns = vec![];
+	for (ordinal, property_id) in property_ids.iter().enumerate() {
+		let property = model_class
+			.properties
+			.iter()
+			.find(|p| p.id.eq(property_id))
+			.ok_or_else(|| ParseProjectError::PropertyNotFound(property_id.clone()))?;
+		let prop_name = match &property.name {
+			Some(prop_name) => prop_name.clone(),
+			None => {
+				let placeholder = ctx
+					.options
+					.placeholder_columns
+					.then(|| format!("column{}", ordinal + 1));
+				warnings.push(ParseWarning::MissingPropertyName {
+					table: name.clone(),
+					property_id: property_id.clone(),
+					placeholder: placeholder.clone(),
+				});
+				unwrap_opt_continue!(placeholder)
 			}
+		};
+
+		let mut foreign_key = get_foreign_key(ctx.index, model_classess, property_id)?;
+		if foreign_key.is_none() && get_foreign_key_constraint(ctx.index, property_id).is_some() {
+			warnings.push(ParseWarning::UnresolvedForeignKey {
+				table: name.clone(),
+				column: prop_name.clone(),
+			});
+		}
 
-			for (ddl_class, model_class) in ddl_script.classess.iter().zip(&model_classess) {
-				let name = model_class
+		let sql_type;
+		if let Some(enumeration) = property
+			.type_id
+			.as_ref()
+			.and_then(|type_id| ctx.index.find_enumeration(type_id))
+		{
+			sql_type = SQLType::Int;
+			if foreign_key.is_none() {
+				let enumeration_name = enumeration
+					.name
+					.clone()
+					.ok_or_else(|| ParseProjectError::MissingClassName(enumeration.id.clone()))?;
+				foreign_key = Some(SQLForeignKey {
+					name: None,
+					schema: get_schema(ctx.index, &enumeration.id),
+					table: enumeration_name,
+					column: LOOKUP_ID_COLUMN.into(),
+					cardinality: Some(SQLMultiplicity {
+						lower: 0,
+						upper: None,
+					}),
+				});
+			}
+		} else if let Some(type_href) = get_type_href(ctx.sql_types, property) {
+			sql_type = unwrap_opt_continue!(resolve_sql_type(
+				ctx.index,
+				ctx.sql_types,
+				type_href,
+				property_id,
+				(&name, &prop_name),
+				warnings
+			)?);
+		} else if let Some((foreign_class, foreign_property)) = ctx
+			.options
+			.infer_foreign_keys
+			.then(|| get_inferred_foreign_key(ctx.index, model_classess, property))
+			.flatten()
+		{
+			let Some(foreign_type_href) = get_type_href(ctx.sql_types, foreign_property) else {
+				warnings.push(ParseWarning::MissingPropertyType {
+					table: name.clone(),
+					column: prop_name.clone(),
+					placeholder: false,
+				});
+				continue;
+			};
+			sql_type = unwrap_opt_continue!(resolve_sql_type(
+				ctx.index,
+				ctx.sql_types,
+				foreign_type_href,
+				&foreign_property.id,
+				(&name, &prop_name),
+				warnings
+			)?);
+
+			// Explicit SQLProfile:FK modifiers always take precedence
+			if foreign_key.is_none() {
+				let class_name = foreign_class
 					.name
 					.clone()
-					.context("UML class name not found")?;
-
-				let mut columns = vec![];
-				for property_id in &ddl_class.property_ids {
-					let property = model_class
-						.properties
-						.iter()
-						.find(|p| p.id.eq(property_id))
-						.context("Property not found")?;
-					let prop_name = unwrap_opt_continue!(&property.name).clone();
-
-					let type_href = unwrap_opt_continue!(&property.type_href);
-					let type_name = sql_type_names
-						.get(type_href)
-						.context("Property type name conversion not found")?;
-
-					let check_constraint = get_sql_check_constraint(&models, &prop_name);
-					let foreign_key = get_foreign_key(&modifiers, &model_classess, property_id)?;
-
-					columns.push(SQLColumn {
-						name: prop_name,
-						sql_type: get_sql_type(&modifiers, *type_name, property_id)?,
-						primary_key: is_primary_key(&modifiers, property_id),
-						nullable: is_nullabe(&modifiers, property_id),
-						foreign_key,
-						check_constraint,
-					})
+					.ok_or_else(|| ParseProjectError::MissingClassName(foreign_class.id.clone()))?;
+				let property_name = foreign_property.name.clone().ok_or_else(|| {
+					ParseProjectError::MissingPropertyName(foreign_property.id.clone())
+				})?;
+				foreign_key = Some(SQLForeignKey {
+					name: None,
+					schema: get_schema(ctx.index, &foreign_class.id),
+					table: class_name,
+					column: property_name,
+					cardinality: None,
+				});
+			}
+		} else {
+			warnings.push(ParseWarning::MissingPropertyType {
+				table: name.clone(),
+				column: prop_name.clone(),
+				placeholder: ctx.options.placeholder_columns,
+			});
+			if !ctx.options.placeholder_columns {
+				continue;
+			}
+			sql_type = PLACEHOLDER_SQL_TYPE;
+		}
+
+		if let (Some(foreign_key), Some(opposite_end)) = (
+			&mut foreign_key,
+			find_association_opposite_end(ctx.index, property),
+		) {
+			foreign_key.cardinality = Some(get_multiplicity(opposite_end));
+		}
+
+		let check_constraints = get_sql_check_constraints(ctx.index, &model_class.id, &prop_name);
+
+		let primary_key = is_primary_key(ctx.index, model_class, property);
+		let auto_increment = get_auto_increment_modifier(ctx.index, property_id)
+			.unwrap_or(primary_key && sql_type == SQLType::Int);
+		columns.push(SQLColumn {
+			ordinal,
+			name: prop_name,
+			sql_type,
+			primary_key,
+			auto_increment,
+			nullable: is_nullabe(ctx.index, property_id),
+			foreign_key,
+			check_constraints,
+		})
+	}
+	columns.sort_by_key(|column| column.ordinal);
+	rename_duplicate_columns(&name, &mut columns, warnings);
+	let unique_constraints = get_unique_constraints(ctx.index, property_ids, &columns);
+	let indexes = get_indexes(ctx.index, property_ids, &columns);
+
+	let is_view = ctx
+		.index
+		.get_modifiers(&model_class.id)
+		.any(|modifier| matches!(modifier, UMLModifier::View(..)));
+	Ok(SQLTable {
+		schema: get_schema(ctx.index, &model_class.id),
+		name,
+		kind: if is_view {
+			TableKind::View
+		} else {
+			TableKind::Table
+		},
+		columns,
+		literals: None,
+		unique_constraints,
+		indexes,
+	})
+}
+
+// Members are looked up by ordinal, so names given by `rename_duplicate_columns` are used.
+// Returns `None` if any of the members is outside of the table.
+fn get_member_names(
+	property_ids: &[String],
+	columns: &[SQLColumn],
+	members: &[String],
+) -> Option<Vec<String>> {
+	members
+		.iter()
+		.map(|member| {
+			let ordinal = property_ids.iter().position(|id| id.eq(member))?;
+			let column = columns.iter().find(|column| column.ordinal == ordinal)?;
+			Some(column.name.clone())
+		})
+		.collect()
+}
+
+fn get_unique_constraints(
+	index: &ModelIndex,
+	property_ids: &[String],
+	columns: &[SQLColumn],
+) -> Vec<Vec<String>> {
+	let mut constraints = vec![];
+	for property_id in property_ids {
+		for modifier in index.get_modifiers(property_id) {
+			if let UMLModifier::Unique(UMLUniqueModifier {
+				property_ids: members,
+			}) = modifier
+			{
+				if let Some(names) = get_member_names(property_ids, columns, members) {
+					constraints.push(names);
+				}
+			}
+		}
+	}
+	constraints
+}
+
+fn get_indexes(
+	index: &ModelIndex,
+	property_ids: &[String],
+	columns: &[SQLColumn],
+) -> Vec<SQLIndex> {
+	let mut indexes = vec![];
+	for property_id in property_ids {
+		for modifier in index.get_modifiers(property_id) {
+			if let UMLModifier::Index(UMLIndexModifier {
+				name,
+				property_ids: members,
+				unique,
+			}) = modifier
+			{
+				if let Some(names) = get_member_names(property_ids, columns, members) {
+					indexes.push(SQLIndex {
+						name: name.clone(),
+						columns: names,
+						unique: *unique,
+					});
+				}
+			}
+		}
+	}
+	indexes
+}
+
+// The first column keeps its name, later ones get a numeric suffix
+fn rename_duplicate_columns(
+	table: &str,
+	columns: &mut [SQLColumn],
+	warnings: &mut Vec<ParseWarning>,
+) {
+	let mut used_names = HashSet::new();
+	for column in columns.iter_mut() {
+		if used_names.insert(column.name.to_lowercase()) {
+			continue;
+		}
+
+		let mut suffix = 2;
+		let mut renamed_to = format!("{}_{}", column.name, suffix);
+		while used_names.contains(&renamed_to.to_lowercase()) {
+			suffix += 1;
+			renamed_to = format!("{}_{}", column.name, suffix);
+		}
+		used_names.insert(renamed_to.to_lowercase());
+
+		warnings.push(ParseWarning::DuplicateColumnName {
+			table: table.into(),
+			column: column.name.clone(),
+			renamed_to: renamed_to.clone(),
+		});
+		column.name = renamed_to;
+	}
+}
+
+// References may be recorded with different casing or stray whitespace after a rename,
+// but an exact match is preferred, so names differing only in case can be told apart
+pub(crate) fn names_match(name: &str, other: &str) -> bool {
+	name.trim().to_lowercase() == other.trim().to_lowercase()
+}
+
+pub(crate) fn find_foreign_table<'a, T: Borrow<SQLTable>>(
+	tables: &'a [T],
+	foreign_key: &SQLForeignKey,
+) -> Option<(usize, &'a T)> {
+	let schemas_match = |table: &SQLTable| match (&table.schema, &foreign_key.schema) {
+		(Some(schema), Some(other)) => names_match(schema, other),
+		(None, None) => true,
+		_ => false,
+	};
+	tables
+		.iter()
+		.enumerate()
+		.find(|(_, table)| {
+			let table = (*table).borrow();
+			table.name.eq(&foreign_key.table) && table.schema.eq(&foreign_key.schema)
+		})
+		.or_else(|| {
+			tables.iter().enumerate().find(|(_, table)| {
+				let table = (*table).borrow();
+				names_match(&table.name, &foreign_key.table) && schemas_match(table)
+			})
+		})
+}
+
+pub(crate) fn find_foreign_column(table: &SQLTable, column_name: &str) -> Option<usize> {
+	table
+		.columns
+		.iter()
+		.position(|column| column.name.eq(column_name))
+		.or_else(|| {
+			table
+				.columns
+				.iter()
+				.position(|column| names_match(&column.name, column_name))
+		})
+}
+
+// Drops foreign keys which point outside of the collection, so that generation can't fail on them.
+// The others are renamed to the referenced names, in case they only matched loosely.
+fn validate_foreign_keys(tables: &mut [SQLTable], warnings: &mut Vec<ParseWarning>) {
+	let mut broken_columns = vec![];
+	let mut renamed_references = vec![];
+	for (table_idx, table) in tables.iter().enumerate() {
+		for (column_idx, column) in table.columns.iter().enumerate() {
+			let foreign_key = unwrap_opt_continue!(&column.foreign_key);
+			let foreign_column =
+				find_foreign_table(tables, foreign_key).and_then(|(_, foreign_table)| {
+					let foreign_column_idx =
+						find_foreign_column(foreign_table, &foreign_key.column)?;
+					Some((foreign_table, &foreign_table.columns[foreign_column_idx]))
+				});
+
+			match foreign_column {
+				Some((foreign_table, foreign_column)) => {
+					if !column.sql_type.is_compatible_with(&foreign_column.sql_type) {
+						warnings.push(ParseWarning::IncompatibleForeignKeyType {
+							table: table.qualified_name(),
+							column: column.name.clone(),
+							sql_type: column.sql_type.to_string(),
+							foreign_table: foreign_key.qualified_table(),
+							foreign_column: foreign_key.column.clone(),
+							foreign_sql_type: foreign_column.sql_type.to_string(),
+						});
+					}
+					let renamed = SQLForeignKey {
+						schema: foreign_table.schema.clone(),
+						table: foreign_table.name.clone(),
+						column: foreign_column.name.clone(),
+						..foreign_key.clone()
+					};
+					renamed_references.push((table_idx, column_idx, renamed));
+				}
+				None => {
+					warnings.push(ParseWarning::BrokenForeignKey {
+						table: table.qualified_name(),
+						column: column.name.clone(),
+						foreign_table: foreign_key.qualified_table(),
+						foreign_column: foreign_key.column.clone(),
+					});
+					broken_columns.push((table_idx, column_idx));
 				}
+			}
+		}
+	}
+
+	for (table_idx, column_idx) in broken_columns {
+		tables[table_idx].columns[column_idx].foreign_key = None;
+	}
+	for (table_idx, column_idx, foreign_key) in renamed_references {
+		tables[table_idx].columns[column_idx].foreign_key = Some(foreign_key);
+	}
+}
+
+// Every enumeration which is referenced by a column becomes a lookup table with fixed rows
+fn build_lookup_tables(
+	ctx: &ProjectContext,
+	tables: &[SQLTable],
+) -> Result<Vec<SQLTable>, ParseProjectError> {
+	let enumerations = ctx
+		.models
+		.iter()
+		.flat_map(|model| &model.packages)
+		.flat_map(|package| &package.enumerations);
+
+	let mut lookup_tables = vec![];
+	for enumeration in enumerations {
+		let name = enumeration
+			.name
+			.clone()
+			.ok_or_else(|| ParseProjectError::MissingClassName(enumeration.id.clone()))?;
+		let schema = get_schema(ctx.index, &enumeration.id);
+		let is_referenced = tables
+			.iter()
+			.flat_map(|table| &table.columns)
+			.filter_map(|column| column.foreign_key.as_ref())
+			.any(|foreign_key| foreign_key.table.eq(&name) && foreign_key.schema.eq(&schema));
+		if !is_referenced {
+			continue;
+		}
+
+		let max_literal_len = enumeration
+			.literals
+			.iter()
+			.map(|literal| literal.chars().count())
+			.max()
+			.unwrap_or(0);
+		// Ids are assigned in the order of the literals, so the id isn't auto incremented
+		let columns = vec![
+			SQLColumn::new(LOOKUP_ID_COLUMN, SQLType::Int).primary_key(),
+			SQLColumn::new(
+				LOOKUP_NAME_COLUMN,
+				SQLType::Varchar(u16::try_from(max_literal_len.max(1)).unwrap_or(u16::MAX)),
+			),
+		];
+		lookup_tables.push(SQLTable {
+			schema,
+			literals: Some(enumeration.literals.clone()),
+			unique_constraints: vec![vec![LOOKUP_NAME_COLUMN.into()]],
+			indexes: vec![],
+			..SQLTable::new(name, columns)
+		});
+	}
+
+	Ok(lookup_tables)
+}
+
+// Classes of a collection together with the ids of properties which become its columns
+struct CollectionClassess<'a> {
+	name: Option<String>,
+	classess: Vec<(&'a UMLClass, Vec<String>)>,
+}
+
+// Without a DDL script, every class with at least one SQL typed property becomes a table
+// Returns why the class isn't stored as a table, if it isn't
+fn get_non_persistent_reason(index: &ModelIndex, class: &UMLClass) -> Option<&'static str> {
+	if class.is_abstract {
+		Some("abstract")
+	} else if index
+		.get_modifiers(&class.id)
+		.any(|modifier| matches!(modifier, UMLModifier::Transient(..)))
+	{
+		Some("transient")
+	} else {
+		None
+	}
+}
+
+fn is_persistent_class(
+	ctx: &ProjectContext,
+	class: &UMLClass,
+	warnings: &mut Vec<ParseWarning>,
+) -> bool {
+	if ctx.options.include_non_persistent_classes {
+		return true;
+	}
+
+	match get_non_persistent_reason(ctx.index, class) {
+		Some(reason) => {
+			warnings.push(ParseWarning::SkippedNonPersistentClass {
+				table: class.name.clone().unwrap_or(class.id.clone()),
+				reason: reason.into(),
+			});
+			false
+		}
+		None => true,
+	}
+}
+
+fn get_uml_only_classess<'a>(
+	ctx: &ProjectContext<'a>,
+	warnings: &mut Vec<ParseWarning>,
+) -> CollectionClassess<'a> {
+	let has_sql_type = |property: &UMLProperty| {
+		get_type_href(ctx.sql_types, property)
+			.is_some_and(|type_href| ctx.sql_types.type_names.contains_key(type_href))
+	};
+	let classess = ctx
+		.models
+		.iter()
+		.flat_map(|model| &model.packages)
+		.flat_map(|package| &package.classess)
+		.filter(|class| class.properties.iter().any(has_sql_type))
+		.filter(|class| is_persistent_class(ctx, class, warnings))
+		.map(|class| {
+			let property_ids = class
+				.properties
+				.iter()
+				.map(|property| property.id.clone())
+				.collect();
+			(class, property_ids)
+		})
+		.collect();
+
+	CollectionClassess {
+		name: None,
+		classess,
+	}
+}
 
-				tables.push(SQLTable { name, columns })
+fn get_ddl_script_classess<'a>(
+	ctx: &ProjectContext<'a>,
+	ddl_projects: Vec<DDLProject>,
+	warnings: &mut Vec<ParseWarning>,
+) -> Result<Vec<CollectionClassess<'a>>, ParseProjectError> {
+	let mut ddl_scripts = ddl_projects
+		.into_iter()
+		.flat_map(|ddl_project| ddl_project.scripts)
+		.collect::<Vec<_>>();
+	if ctx.options.merge_ddl_scripts && ddl_scripts.len() > 1 {
+		let mut merged_script = ddl_scripts.remove(0);
+		for ddl_script in ddl_scripts {
+			merged_script.classess.extend(ddl_script.classess);
+		}
+		merged_script.name = None;
+		ddl_scripts = vec![merged_script];
+	}
+
+	let mut collections = vec![];
+	for mut ddl_script in ddl_scripts {
+		let merged_class_ids = merge_duplicate_ddl_classes(&mut ddl_script.classess);
+		for class_id in merged_class_ids {
+			let table = ctx
+				.index
+				.find_class(&class_id)
+				.and_then(|model_class| model_class.name.clone())
+				.unwrap_or(class_id);
+			warnings.push(ParseWarning::MergedDuplicateTable { table });
+		}
+
+		let mut classess = vec![];
+		for ddl_class in ddl_script.classess {
+			// Classes of fragmented projects may live in modules which aren't loaded
+			let Some(model_class) = ctx.index.find_class(&ddl_class.class_id) else {
+				warnings.push(ParseWarning::UnresolvedDDLClass {
+					script: ddl_script.name.clone(),
+					class_id: ddl_class.class_id,
+				});
+				continue;
+			};
+			if is_persistent_class(ctx, model_class, warnings) {
+				classess.push((model_class, ddl_class.property_ids));
 			}
-			collections.push(SQLTableCollection { tables })
 		}
+		collections.push(CollectionClassess {
+			name: ddl_script.name,
+			classess,
+		});
 	}
 
 	Ok(collections)
 }
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum ParseStage {
+	ReadingModel,
+	ResolvingTypes,
+	ReadingDdl,
+	BuildingTables { done: usize, total: usize },
+}
+
+impl Display for ParseStage {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			ParseStage::ReadingModel => write!(f, "Reading UML model"),
+			ParseStage::ResolvingTypes => write!(f, "Resolving SQL types"),
+			ParseStage::ReadingDdl => write!(f, "Reading DDL scripts"),
+			ParseStage::BuildingTables { done, total } => {
+				write!(f, "Building tables ({}/{})", done, total)
+			}
+		}
+	}
+}
+
+/// Parses a project which is already fully loaded into memory, without copying it
+pub fn parse_project_bytes(
+	bytes: &[u8],
+	options: &ParseOptions,
+) -> Result<ParsedProject, ParseProjectError> {
+	parse_project(Cursor::new(bytes), options)
+}
+
+pub fn parse_project<R: Read + Seek>(
+	project_file: R,
+	options: &ParseOptions,
+) -> Result<ParsedProject, ParseProjectError> {
+	block_on_ready(parse_project_with_progress(project_file, options, |_| {
+		std::future::ready(())
+	}))
+}
+
+/// Same as `parse_project`, but reports each stage before starting it. The returned future
+/// is awaited before continuing, which lets the caller yield to the browser between stages.
+pub async fn parse_project_with_progress<R, F, Fut>(
+	project_file: R,
+	options: &ParseOptions,
+	mut on_progress: F,
+) -> Result<ParsedProject, ParseProjectError>
+where
+	R: Read + Seek,
+	F: FnMut(ParseStage) -> Fut,
+	Fut: Future<Output = ()>,
+{
+	let mut zip =
+		ZipArchive::new(project_file).map_err(|e| ParseProjectError::InvalidZip(e.to_string()))?;
+	check_is_magicdraw_project(&zip)?;
+	let mut warnings = vec![];
+
+	on_progress(ParseStage::ReadingModel).await;
+	let (models, modifiers) = parse_uml_model(&mut zip)?;
+	let metadata = parse_project_metadata(&mut zip, &models)?;
+	on_progress(ParseStage::ResolvingTypes).await;
+	let sql_types = parse_sql_types(&mut zip, &get_used_types(&models), &options.type_mappings)?;
+	let index = ModelIndex::new(&models, &modifiers);
+	let ctx = ProjectContext {
+		models: &models,
+		index: &index,
+		sql_types: &sql_types,
+		options,
+	};
+
+	on_progress(ParseStage::ReadingDdl).await;
+	let pending_collections = match parse_ddl_scripts(&mut zip) {
+		Ok(ddl_projects) => get_ddl_script_classess(&ctx, ddl_projects, &mut warnings)?,
+		Err(ParseProjectError::MissingDDLScript) => {
+			warnings.push(ParseWarning::MissingDDLScript);
+			vec![get_uml_only_classess(&ctx, &mut warnings)]
+		}
+		Err(e) => return Err(e),
+	};
+
+	let collections =
+		build_collections(&ctx, pending_collections, &mut warnings, &mut on_progress).await?;
+	check_strict_mode(options, &warnings)?;
+
+	Ok(ParsedProject {
+		metadata,
+		collections,
+		warnings,
+	})
+}
+
+fn check_strict_mode(
+	options: &ParseOptions,
+	warnings: &[ParseWarning],
+) -> Result<(), ParseProjectError> {
+	let violations = warnings
+		.iter()
+		.filter(|warning| !warning.is_informational())
+		.map(ToString::to_string)
+		.collect::<Vec<_>>();
+	if options.mode == ParseMode::Strict && !violations.is_empty() {
+		return Err(ParseProjectError::StrictModeViolations(violations));
+	}
+	Ok(())
+}
+
+async fn build_collections<F, Fut>(
+	ctx: &ProjectContext<'_>,
+	pending_collections: Vec<CollectionClassess<'_>>,
+	warnings: &mut Vec<ParseWarning>,
+	on_progress: &mut F,
+) -> Result<Vec<SQLTableCollection>, ParseProjectError>
+where
+	F: FnMut(ParseStage) -> Fut,
+	Fut: Future<Output = ()>,
+{
+	let total = pending_collections
+		.iter()
+		.map(|collection| collection.classess.len())
+		.sum();
+	let mut done = 0;
+
+	let mut collections = vec![];
+	for collection in pending_collections {
+		let model_classess = collection
+			.classess
+			.iter()
+			.map(|(model_class, _)| *model_class)
+			.collect::<Vec<_>>();
+
+		let mut tables = vec![];
+		for (model_class, property_ids) in &collection.classess {
+			on_progress(ParseStage::BuildingTables { done, total }).await;
+			tables.push(build_table(
+				ctx,
+				model_class,
+				property_ids,
+				&model_classess,
+				warnings,
+			)?);
+			done += 1;
+		}
+
+		let mut lookup_tables = build_lookup_tables(ctx, &tables)?;
+		lookup_tables.append(&mut tables);
+		validate_foreign_keys(&mut lookup_tables, warnings);
+		collections.push(SQLTableCollection {
+			name: collection.name,
+			tables: lookup_tables,
+		});
+	}
+
+	Ok(collections)
+}
+
+const ZIP_MAGIC_BYTES: &[u8] = b"PK\x03\x04";
+const XML_FILE_NAME: &str = "project.xml";
+
+/// Accepts either a zipped `.mdzip` project or a plain XML export (`.xml` / `.mdxml`).
+/// XML exports have no DDL scripts, so tables are built from all UML classes.
+pub fn parse_project_file<R: Read + Seek>(
+	reader: R,
+	options: &ParseOptions,
+) -> Result<ParsedProject, ParseProjectError> {
+	block_on_ready(parse_project_file_with_progress(reader, options, |_| {
+		std::future::ready(())
+	}))
+}
+
+pub async fn parse_project_file_with_progress<R, F, Fut>(
+	mut reader: R,
+	options: &ParseOptions,
+	mut on_progress: F,
+) -> Result<ParsedProject, ParseProjectError>
+where
+	R: Read + Seek,
+	F: FnMut(ParseStage) -> Fut,
+	Fut: Future<Output = ()>,
+{
+	// XML exports are read once for each part, straight from the reader instead of a copy
+	let read_error = |e: std::io::Error| ParseProjectError::InvalidZip(e.to_string());
+	let mut magic_bytes = vec![];
+	(&mut reader)
+		.take(ZIP_MAGIC_BYTES.len() as u64)
+		.read_to_end(&mut magic_bytes)
+		.map_err(read_error)?;
+	reader.rewind().map_err(read_error)?;
+	if magic_bytes == ZIP_MAGIC_BYTES {
+		return parse_project_with_progress(reader, options, on_progress).await;
+	}
+
+	on_progress(ParseStage::ReadingModel).await;
+	let (models, modifiers) = parse_uml_model_file(XML_FILE_NAME, &mut reader)?;
+	if models.is_empty() {
+		return Err(ParseProjectError::NotAMagicDrawProject(vec![
+			"uml:Model".into()
+		]));
+	}
+	reader.rewind().map_err(read_error)?;
+	let metadata = parse_xml_metadata(XML_FILE_NAME, &mut reader, &models)?;
+
+	on_progress(ParseStage::ResolvingTypes).await;
+	reader.rewind().map_err(read_error)?;
+	let sql_types = parse_sql_types_file(
+		XML_FILE_NAME,
+		&mut reader,
+		&get_used_types(&models),
+		&options.type_mappings,
+	)?;
+	let index = ModelIndex::new(&models, &modifiers);
+	let ctx = ProjectContext {
+		models: &models,
+		index: &index,
+		sql_types: &sql_types,
+		options,
+	};
+
+	// XML exports never contain DDL scripts
+	let mut warnings = vec![ParseWarning::MissingDDLScript];
+	let pending_collections = vec![get_uml_only_classess(&ctx, &mut warnings)];
+	let collections =
+		build_collections(&ctx, pending_collections, &mut warnings, &mut on_progress).await?;
+	check_strict_mode(options, &warnings)?;
+
+	Ok(ParsedProject {
+		metadata,
+		collections,
+		warnings,
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use std::io::Write;
+
+	use zip::{write::FileOptions, ZipWriter};
+
+	use super::*;
+
+	fn parse_xml(xml: &str) -> ParsedProject {
+		parse_project_file(Cursor::new(xml), &ParseOptions::default()).unwrap()
+	}
+
+	const META_MODEL_XML: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<project:Project xmlns:project="http://www.nomagic.com/ns/magicdraw/core/project/1.0"/>"#;
+
+	fn zip_project(files: &[(&str, &str)]) -> Vec<u8> {
+		let mut writer = ZipWriter::new(Cursor::new(vec![]));
+		for (name, contents) in files {
+			writer.start_file(*name, FileOptions::default()).unwrap();
+			writer.write_all(contents.as_bytes()).unwrap();
+		}
+		writer.finish().unwrap().into_inner()
+	}
+
+	fn find_table<'a>(project: &'a ParsedProject, name: &str) -> &'a SQLTable {
+		project.collections[0]
+			.tables
+			.iter()
+			.find(|table| table.name == name)
+			.unwrap()
+	}
+
+	const SELF_REFERENCE_XML: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<xmi:XMI xmlns:xmi="http://www.omg.org/spec/XMI/20131001" xmlns:uml="http://www.omg.org/spec/UML/20131001" xmlns:SQLProfile="http://www.magicdraw.com/schemas/SQLProfile.xmi">
+<uml:Model xmi:id="m1" name="Shop">
+<packagedElement xmi:type="uml:Class" xmi:id="c1" name="category">
+<ownedAttribute xmi:type="uml:Property" xmi:id="p1" name="id" isID="true"><type href="SQL2003.mdzip#INTEGER"/></ownedAttribute>
+<ownedAttribute xmi:type="uml:Property" xmi:id="p2" name="parent_category_id"><type href="SQL2003.mdzip#INTEGER"/></ownedAttribute>
+</packagedElement>
+</uml:Model>
+<SQLProfile:FK xmi:id="s1" members="p2" referencedMembers="p1"/>
+<SQLProfile:Column xmi:id="s2" base_Property="p2" nullable="true"/>
+</xmi:XMI>"#;
+
+	const SHARED_COLUMN_CHECKS_XML: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<xmi:XMI xmlns:xmi="http://www.omg.org/spec/XMI/20131001" xmlns:uml="http://www.omg.org/spec/UML/20131001">
+<uml:Model xmi:id="m1" name="Shop">
+<packagedElement xmi:type="uml:Class" xmi:id="c1" name="payment">
+<ownedAttribute xmi:type="uml:Property" xmi:id="p1" name="type"><type href="SQL2003.mdzip#VARCHAR"/></ownedAttribute>
+<ownedRule xmi:type="uml:Constraint" xmi:id="r1">
+<constrainedElement xmi:idref="c1"/>
+<specification xmi:type="uml:OpaqueExpression" xmi:id="e1"><body>type in ('card', 'cash')</body><language>SQL</language></specification>
+</ownedRule>
+</packagedElement>
+<packagedElement xmi:type="uml:Class" xmi:id="c2" name="delivery">
+<ownedAttribute xmi:type="uml:Property" xmi:id="p2" name="type"><type href="SQL2003.mdzip#VARCHAR"/></ownedAttribute>
+<ownedRule xmi:type="uml:Constraint" xmi:id="r2">
+<constrainedElement xmi:idref="c2"/>
+<specification xmi:type="uml:OpaqueExpression" xmi:id="e2"><body>type in ('courier', 'pickup', 'post')</body><language>SQL</language></specification>
+</ownedRule>
+</packagedElement>
+</uml:Model>
+</xmi:XMI>"#;
+
+	#[test]
+	fn check_constraints_belong_to_their_class() {
+		let project = parse_xml(SHARED_COLUMN_CHECKS_XML);
+		assert_eq!(
+			find_table(&project, "payment").columns[0].check_constraints,
+			vec![SQLCheckConstraint::OneOf(vec![
+				"card".into(),
+				"cash".into()
+			])]
+		);
+		assert_eq!(
+			find_table(&project, "delivery").columns[0].check_constraints,
+			vec![SQLCheckConstraint::OneOf(vec![
+				"courier".into(),
+				"pickup".into(),
+				"post".into()
+			])]
+		);
+	}
+
+	const PRIMARY_KEYS_XML: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<xmi:XMI xmlns:xmi="http://www.omg.org/spec/XMI/20131001" xmlns:uml="http://www.omg.org/spec/UML/20131001" xmlns:SQLProfile="http://www.magicdraw.com/schemas/SQLProfile.xmi">
+<uml:Model xmi:id="m1" name="Shop">
+<packagedElement xmi:type="uml:Class" xmi:id="c1" name="customer">
+<ownedAttribute xmi:type="uml:Property" xmi:id="p1" name="id" isID="true"><type href="SQL2003.mdzip#INTEGER"/></ownedAttribute>
+<ownedAttribute xmi:type="uml:Property" xmi:id="p2" name="name"><type href="SQL2003.mdzip#VARCHAR"/></ownedAttribute>
+</packagedElement>
+<packagedElement xmi:type="uml:Class" xmi:id="c2" name="product">
+<ownedAttribute xmi:type="uml:Property" xmi:id="p3" name="legacy_id" isID="true"><type href="SQL2003.mdzip#INTEGER"/></ownedAttribute>
+<ownedAttribute xmi:type="uml:Property" xmi:id="p4" name="code"><type href="SQL2003.mdzip#VARCHAR"/></ownedAttribute>
+</packagedElement>
+</uml:Model>
+<SQLProfile:PKMember xmi:id="s1" base_Property="p4"/>
+</xmi:XMI>"#;
+
+	#[test]
+	fn is_id_is_a_primary_key_fallback() {
+		let project = parse_xml(PRIMARY_KEYS_XML);
+		let primary_keys = |name| {
+			find_table(&project, name)
+				.columns
+				.iter()
+				.map(|column| column.primary_key)
+				.collect::<Vec<_>>()
+		};
+		assert_eq!(primary_keys("customer"), vec![true, false]);
+		assert_eq!(primary_keys("product"), vec![false, true]);
+	}
+
+	#[test]
+	fn check_constraints_spanning_lines() {
+		let one_of = SQLCheckConstraint::OneOf(vec!["new".into(), "paid".into()]);
+		assert_eq!(parse_check_constraint("in ('new', 'paid')"), one_of);
+		assert_eq!(
+			parse_check_constraint("in (\n\t'new',\n\t'paid'\n)"),
+			one_of
+		);
+		assert_eq!(parse_check_constraint("IN\t('new',\r\n'paid')"), one_of);
+		assert_eq!(
+			parse_check_constraint("between\n1\tand 10"),
+			SQLCheckConstraint::Range(Some(1.0), Some(10.0))
+		);
+	}
+
+	const DUPLICATE_COLUMNS_XML: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<xmi:XMI xmlns:xmi="http://www.omg.org/spec/XMI/20131001" xmlns:uml="http://www.omg.org/spec/UML/20131001">
+<uml:Model xmi:id="m1" name="Shop">
+<packagedElement xmi:type="uml:Class" xmi:id="c1" name="customer">
+<ownedAttribute xmi:type="uml:Property" xmi:id="p1" name="email"><type href="SQL2003.mdzip#VARCHAR"/></ownedAttribute>
+<ownedAttribute xmi:type="uml:Property" xmi:id="p2" name="email_2"><type href="SQL2003.mdzip#VARCHAR"/></ownedAttribute>
+<ownedAttribute xmi:type="uml:Property" xmi:id="p3" name="Email"><type href="SQL2003.mdzip#VARCHAR"/></ownedAttribute>
+</packagedElement>
+</uml:Model>
+</xmi:XMI>"#;
+
+	#[test]
+	fn duplicate_column_names_are_renamed() {
+		let project = parse_xml(DUPLICATE_COLUMNS_XML);
+		let names = find_table(&project, "customer")
+			.columns
+			.iter()
+			.map(|column| column.name.as_str())
+			.collect::<Vec<_>>();
+		assert_eq!(names, vec!["email", "email_2", "Email_3"]);
+		assert!(project
+			.warnings
+			.contains(&ParseWarning::DuplicateColumnName {
+				table: "customer".into(),
+				column: "Email".into(),
+				renamed_to: "Email_3".into(),
+			}));
+	}
+
+	const CUSTOMER_MODEL_XML: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<xmi:XMI xmlns:xmi="http://www.omg.org/spec/XMI/20131001" xmlns:uml="http://www.omg.org/spec/UML/20131001">
+<uml:Model xmi:id="m1" name="Shop">
+<packagedElement xmi:type="uml:Class" xmi:id="c1" name="customer">
+<ownedAttribute xmi:type="uml:Property" xmi:id="p1" name="id" isID="true"><type href="SQL2003.mdzip#INTEGER"/></ownedAttribute>
+</packagedElement>
+</uml:Model>
+</xmi:XMI>"#;
+
+	const BOGUS_CLASS_DDL_XML: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<xmi:XMI xmlns:xmi="http://www.omg.org/XMI" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance">
+<contents xsi:type="md.ce.ddl.rt.objects:DDLProjectObject">
+<modelElement xsi:type="uml:Model" href="model#m1"/>
+<objects xsi:type="md.ce.rt.objects:RTComponent" name="shop.sql">
+<modelElement xsi:type="uml:Component" href="model#s1"/>
+<objects xsi:type="md.ce.rt.objects:RTClassObject">
+<modelElement xsi:type="uml:Class" href="model#c1"/>
+<modelElement xsi:type="uml:Property" href="model#p1"/>
+</objects>
+<objects xsi:type="md.ce.rt.objects:RTClassObject">
+<modelElement xsi:type="uml:Class" href="module#c404"/>
+</objects>
+</objects>
+</contents>
+</xmi:XMI>"#;
+
+	#[test]
+	fn unresolved_ddl_classes_are_skipped() {
+		let project = zip_project(&[
+			(MODEL_FILE_NAME, CUSTOMER_MODEL_XML),
+			(META_MODEL_FILE_NAME, META_MODEL_XML),
+			(DDL_FILE_NAME, BOGUS_CLASS_DDL_XML),
+		]);
+		let project = parse_project_file(Cursor::new(project), &ParseOptions::default()).unwrap();
+		assert_eq!(project.collections.len(), 1);
+		assert_eq!(project.collections[0].name.as_deref(), Some("shop.sql"));
+		find_table(&project, "customer");
+		assert_eq!(project.collections[0].tables.len(), 1);
+		assert!(project
+			.warnings
+			.contains(&ParseWarning::UnresolvedDDLClass {
+				script: Some("shop.sql".into()),
+				class_id: "c404".into(),
+			}));
+	}
+
+	const CUSTOMER_FRAGMENT_XML: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<xmi:XMI xmlns:xmi="http://www.omg.org/spec/XMI/20131001" xmlns:uml="http://www.omg.org/spec/UML/20131001">
+<uml:Model xmi:id="m1" name="Shop">
+<packagedElement xmi:type="uml:PrimitiveType" xmi:id="t1" name="INTEGER"/>
+<packagedElement xmi:type="uml:Class" xmi:id="c1" name="customer">
+<ownedAttribute xmi:type="uml:Property" xmi:id="p1" name="id" isID="true" type="t1"/>
+</packagedElement>
+</uml:Model>
+</xmi:XMI>"#;
+
+	const ORDERS_FRAGMENT_XML: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<xmi:XMI xmlns:xmi="http://www.omg.org/spec/XMI/20131001" xmlns:uml="http://www.omg.org/spec/UML/20131001" xmlns:SQLProfile="http://www.magicdraw.com/schemas/SQLProfile.xmi">
+<packagedElement xmi:type="uml:Class" xmi:id="c2" name="orders">
+<ownedAttribute xmi:type="uml:Property" xmi:id="p2" name="id" isID="true" type="t1"/>
+<ownedAttribute xmi:type="uml:Property" xmi:id="p3" name="customer_id" type="t1"/>
+</packagedElement>
+<SQLProfile:FK xmi:id="s1" members="p3" referencedMembers="p1"/>
+</xmi:XMI>"#;
+
+	#[test]
+	fn fragmented_projects_are_merged() {
+		let project = zip_project(&[
+			("model/customer.fragment", CUSTOMER_FRAGMENT_XML),
+			("model/orders.fragment", ORDERS_FRAGMENT_XML),
+			(META_MODEL_FILE_NAME, META_MODEL_XML),
+		]);
+		let project = parse_project_file(Cursor::new(project), &ParseOptions::default()).unwrap();
+		assert_eq!(project.metadata.name.as_deref(), Some("Shop"));
+		find_table(&project, "customer");
+		let customer_id = &find_table(&project, "orders").columns[1];
+		assert_eq!(customer_id.sql_type, SQLType::Int);
+		let foreign_key = customer_id.foreign_key.as_ref().unwrap();
+		assert_eq!(foreign_key.table, "customer");
+		assert_eq!(foreign_key.column, "id");
+	}
+
+	const ENUMERATION_XML: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<xmi:XMI xmlns:xmi="http://www.omg.org/spec/XMI/20131001" xmlns:uml="http://www.omg.org/spec/UML/20131001">
+<uml:Model xmi:id="m1" name="Parduotuvė">
+<packagedElement xmi:type="uml:Enumeration" xmi:id="e1" name="būsena">
+<ownedLiteral xmi:type="uml:EnumerationLiteral" xmi:id="l1" name="naujas"/>
+<ownedLiteral xmi:type="uml:EnumerationLiteral" xmi:id="l2" name="įvykdytas"/>
+</packagedElement>
+<packagedElement xmi:type="uml:Class" xmi:id="c1" name="užsakymas">
+<ownedAttribute xmi:type="uml:Property" xmi:id="p1" name="id" isID="true"><type href="SQL2003.mdzip#INTEGER"/></ownedAttribute>
+<ownedAttribute xmi:type="uml:Property" xmi:id="p2" name="būsena" type="e1"/>
+</packagedElement>
+</uml:Model>
+</xmi:XMI>"#;
+
+	#[test]
+	fn lookup_columns_are_sized_in_characters() {
+		let project = parse_xml(ENUMERATION_XML);
+		let lookup = find_table(&project, "būsena");
+		assert_eq!(
+			lookup.literals,
+			Some(vec!["naujas".into(), "įvykdytas".into()])
+		);
+		assert_eq!(lookup.columns[1].sql_type, SQLType::Varchar(9));
+	}
+
+	#[test]
+	fn foreign_keys_match_names_loosely() {
+		let mut tables = vec![
+			SQLTable::new("customer", vec![SQLColumn::new("id", SQLType::Int)]),
+			SQLTable::new(
+				"orders",
+				vec![
+					SQLColumn::new("customer_id", SQLType::Int).foreign_key(" Customer", "ID "),
+					SQLColumn::new("seller_id", SQLType::Int).foreign_key("Seller", "id"),
+				],
+			),
+		];
+		let mut warnings = vec![];
+		validate_foreign_keys(&mut tables, &mut warnings);
+
+		let foreign_key = tables[1].columns[0].foreign_key.as_ref().unwrap();
+		assert_eq!(
+			(foreign_key.table.as_str(), foreign_key.column.as_str()),
+			("customer", "id")
+		);
+		assert_eq!(tables[1].columns[1].foreign_key, None);
+		assert_eq!(warnings.len(), 1);
+		assert!(matches!(warnings[0], ParseWarning::BrokenForeignKey { .. }));
+	}
+
+	#[test]
+	fn strict_mode_allows_informational_warnings() {
+		let strict = ParseOptions {
+			mode: ParseMode::Strict,
+			..Default::default()
+		};
+		let skipped = ParseWarning::SkippedNonPersistentClass {
+			table: "base_entity".into(),
+			reason: "abstract".into(),
+		};
+		assert!(check_strict_mode(&strict, &[ParseWarning::MissingDDLScript, skipped]).is_ok());
+
+		let broken = ParseWarning::BrokenForeignKey {
+			table: "orders".into(),
+			column: "customer_id".into(),
+			foreign_table: "customer".into(),
+			foreign_column: "id".into(),
+		};
+		let warnings = [ParseWarning::MissingDDLScript, broken.clone()];
+		assert_eq!(
+			check_strict_mode(&strict, &warnings),
+			Err(ParseProjectError::StrictModeViolations(vec![
+				broken.to_string()
+			]))
+		);
+		assert!(check_strict_mode(&ParseOptions::default(), &warnings).is_ok());
+	}
+
+	#[test]
+	fn self_referencing_foreign_key() {
+		let project = parse_xml(SELF_REFERENCE_XML);
+		let category = find_table(&project, "category");
+		let parent = &category.columns[1];
+		assert_eq!(parent.name, "parent_category_id");
+		assert!(parent.nullable);
+		let foreign_key = parent.foreign_key.as_ref().unwrap();
+		assert_eq!(foreign_key.table, "category");
+		assert_eq!(foreign_key.column, "id");
+		assert_eq!(project.warnings, vec![ParseWarning::MissingDDLScript]);
+	}
+}
@@ -0,0 +1,138 @@
+use std::collections::HashMap;
+
+use super::uml_model_parser::{
+	UMLAssociation, UMLClass, UMLEnumeration, UMLModel, UMLModifier, UMLPackage, UMLProperty,
+	UMLSchemaModifier, UMLTransientModifier,
+};
+
+/// Lookup tables over the parsed UML model, built once so that resolving columns
+/// doesn't need to scan the whole model for every property
+pub struct ModelIndex<'a> {
+	classess: HashMap<&'a str, &'a UMLClass>,
+	properties: HashMap<&'a str, (&'a UMLClass, &'a UMLProperty)>,
+	enumerations: HashMap<&'a str, &'a UMLEnumeration>,
+	associations: HashMap<&'a str, &'a UMLAssociation>,
+	/// Package which contains a class or an enumeration
+	packages: HashMap<&'a str, &'a UMLPackage>,
+	/// Constraint bodies by class id and property name
+	check_constraints: HashMap<&'a str, HashMap<&'a str, Vec<&'a str>>>,
+	/// Modifiers by the id of the element they are applied to
+	modifiers: HashMap<&'a str, Vec<&'a UMLModifier>>,
+}
+
+fn get_modifier_element_id(modifier: &UMLModifier) -> &str {
+	match modifier {
+		// Filed under the first member, so that each constraint is found only once per table
+		UMLModifier::Unique(modifier) => &modifier.property_ids[0],
+		UMLModifier::PirmaryKey(modifier) => &modifier.property_id,
+		UMLModifier::Nullable(modifier) => &modifier.property_id,
+		UMLModifier::ForeignKey(modifier) => &modifier.from_property_id,
+		UMLModifier::Type(modifier) => &modifier.property_id,
+		UMLModifier::Schema(UMLSchemaModifier { package_id }) => package_id,
+		UMLModifier::Transient(UMLTransientModifier { class_id }) => class_id,
+		UMLModifier::AutoIncrement(modifier) => &modifier.property_id,
+		UMLModifier::View(modifier) => &modifier.class_id,
+		UMLModifier::Index(modifier) => &modifier.property_ids[0],
+	}
+}
+
+impl<'a> ModelIndex<'a> {
+	pub fn new(models: &'a [UMLModel], modifiers: &'a [UMLModifier]) -> Self {
+		let mut index = ModelIndex {
+			classess: HashMap::new(),
+			properties: HashMap::new(),
+			enumerations: HashMap::new(),
+			associations: HashMap::new(),
+			packages: HashMap::new(),
+			check_constraints: HashMap::new(),
+			modifiers: HashMap::new(),
+		};
+
+		for package in models.iter().flat_map(|model| &model.packages) {
+			for class in &package.classess {
+				// Keep the first definition, same as a linear search would find
+				index.classess.entry(&class.id).or_insert(class);
+				index.packages.entry(&class.id).or_insert(package);
+				for property in &class.properties {
+					index
+						.properties
+						.entry(&property.id)
+						.or_insert((class, property));
+				}
+				for constraint in &class.constraints {
+					if let (Some(class_id), Some(property_name), Some(body)) = (
+						&constraint.class_id,
+						&constraint.property_name,
+						&constraint.body,
+					) {
+						index
+							.check_constraints
+							.entry(class_id)
+							.or_default()
+							.entry(property_name)
+							.or_default()
+							.push(body);
+					}
+				}
+			}
+			for enumeration in &package.enumerations {
+				index
+					.enumerations
+					.entry(&enumeration.id)
+					.or_insert(enumeration);
+				index.packages.entry(&enumeration.id).or_insert(package);
+			}
+			for association in &package.associations {
+				index
+					.associations
+					.entry(&association.id)
+					.or_insert(association);
+			}
+		}
+
+		for modifier in modifiers {
+			index
+				.modifiers
+				.entry(get_modifier_element_id(modifier))
+				.or_default()
+				.push(modifier);
+		}
+
+		index
+	}
+
+	pub fn find_class(&self, id: &str) -> Option<&'a UMLClass> {
+		self.classess.get(id).copied()
+	}
+
+	pub fn find_property(&self, id: &str) -> Option<(&'a UMLClass, &'a UMLProperty)> {
+		self.properties.get(id).copied()
+	}
+
+	pub fn find_enumeration(&self, id: &str) -> Option<&'a UMLEnumeration> {
+		self.enumerations.get(id).copied()
+	}
+
+	pub fn find_association(&self, id: &str) -> Option<&'a UMLAssociation> {
+		self.associations.get(id).copied()
+	}
+
+	pub fn find_package(&self, element_id: &str) -> Option<&'a UMLPackage> {
+		self.packages.get(element_id).copied()
+	}
+
+	pub fn get_check_constraint_bodies(&self, class_id: &str, property_name: &str) -> &[&'a str] {
+		self.check_constraints
+			.get(class_id)
+			.and_then(|constraints| constraints.get(property_name))
+			.map(Vec::as_slice)
+			.unwrap_or(&[])
+	}
+
+	pub fn get_modifiers(&self, element_id: &str) -> impl Iterator<Item = &'a UMLModifier> + '_ {
+		self.modifiers
+			.get(element_id)
+			.into_iter()
+			.flat_map(|modifiers| modifiers.iter().copied())
+	}
+}
@@ -3,13 +3,18 @@ use std::{
 	io::{Read, Seek},
 };
 
-use anyhow::{bail, Context, Result};
-use xml::{attribute::OwnedAttribute, name::OwnedName, reader::XmlEvent, EventReader};
+use xml::{attribute::OwnedAttribute, name::OwnedName, reader::XmlEvent};
 use zip::ZipArchive;
 
 use crate::unwrap_opt_continue;
 
-use super::utils::{check_attribute, check_name, get_attribute, parse_element, MyEventReader};
+use super::uml_model_parser::{is_model_fragment_file, MODEL_FILE_NAME};
+use super::utils::{
+	check_attribute, check_name, get_attribute, open_zip_entry, parse_element, MyEventReader,
+	ParseProjectError,
+};
+
+pub const META_MODEL_FILE_NAME: &str = "com.nomagic.ci.metamodel.project";
 
 #[derive(Debug)]
 struct UsedPackage {
@@ -18,7 +23,7 @@ struct UsedPackage {
 	needed_types: Vec<String>,
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum SQLTypeName {
 	Int,
 	Decimal,
@@ -29,6 +34,35 @@ pub enum SQLTypeName {
 	Bool,
 	Char,
 	Varchar,
+	Text,
+}
+
+/// What a type from a custom profile stands for, like `Money` being a `DECIMAL(19,4)`
+#[derive(Debug, Clone, PartialEq)]
+pub struct TypeMapping {
+	pub type_name: SQLTypeName,
+	/// Used when a property has no type modifier of its own
+	pub default_sizes: Vec<u32>,
+}
+
+#[derive(Debug)]
+pub struct ParsedSQLTypes {
+	pub type_names: HashMap<String, SQLTypeName>,
+	/// Sizes from type mappings, for types which have them
+	pub default_sizes: HashMap<String, Vec<u32>>,
+	/// Names of primitive types which could not be recognized as SQL types
+	pub unknown_type_names: HashMap<String, String>,
+	/// Used projects which types were loaded from
+	pub loaded_packages: Vec<String>,
+	/// Used projects which are referenced by types of the model, but weren't found.
+	/// Usually the type library was renamed or isn't mounted in the project.
+	pub missing_packages: HashSet<String>,
+}
+
+/// Name of the used project a type comes from, like `UML_Standard_Profile.mdzip`
+pub fn get_type_package_name(type_href: &str) -> Option<&str> {
+	let (package_name, _) = type_href.split_once('#')?;
+	(!package_name.is_empty()).then_some(package_name)
 }
 
 fn get_used_project_name(attrs: &[OwnedAttribute]) -> Option<&str> {
@@ -40,7 +74,7 @@ fn parse_used_package<R: Read>(
 	parser: &mut MyEventReader<R>,
 	attrs: &[OwnedAttribute],
 	needed_types: &[&str],
-) -> Result<UsedPackage> {
+) -> Result<UsedPackage, ParseProjectError> {
 	let mut share_point_ids = vec![];
 	let project_uri = get_attribute(&attrs, None, "usedProjectURI")?;
 	let name = project_uri.split("/").last().unwrap();
@@ -55,7 +89,7 @@ fn parse_used_package<R: Read>(
 	})?;
 
 	if share_point_ids.is_empty() {
-		bail!("Share point mount ids not found")
+		return Err(ParseProjectError::MissingSharePoint(name.to_string()));
 	}
 
 	Ok(UsedPackage {
@@ -68,7 +102,7 @@ fn parse_used_package<R: Read>(
 fn list_used_packages<R: Read>(
 	file: R,
 	needed_types: &HashSet<&String>,
-) -> Result<Vec<UsedPackage>> {
+) -> Result<Vec<UsedPackage>, ParseProjectError> {
 	let mut packages = vec![];
 
 	let mut needed_types_per_package = HashMap::new();
@@ -80,7 +114,7 @@ fn list_used_packages<R: Read>(
 		ids.push(type_id);
 	}
 
-	let mut parser: MyEventReader<_> = EventReader::new(file).into();
+	let mut parser = MyEventReader::new(META_MODEL_FILE_NAME, file);
 	loop {
 		match parser.next()? {
 			XmlEvent::StartElement {
@@ -113,25 +147,26 @@ fn is_umodel_snapshot_file(filename: &str) -> bool {
 	filename.ends_with("_resource_com$dnomagic$dmagicdraw$duml_umodel$dshared_umodel$dsnapshot")
 }
 
-fn parse_type_name(str: &str) -> Result<SQLTypeName> {
+pub fn parse_type_name(str: &str) -> Option<SQLTypeName> {
 	use SQLTypeName::*;
-	Ok(match &str.to_lowercase()[..] {
-		"decimal" | "dec" => Decimal,
-		"char" => Char,
-		"varchar" | "string" => Varchar,
-		"float" | "double precision" => Float, // TODO: Cheecky double precision -> float
-		"integer" | "int" => Int,
+	Some(match &str.trim().to_lowercase()[..] {
+		"decimal" | "dec" | "numeric" | "number" => Decimal,
+		"char" | "character" | "nchar" => Char,
+		"varchar" | "string" | "nvarchar" | "character varying" | "varchar2" => Varchar,
+		"text" | "ntext" | "clob" => Text,
+		"float" | "double precision" | "double" | "real" => Float,
+		"integer" | "int" | "smallint" | "bigint" | "tinyint" => Int,
 		"date" => Date,
-		"datetime" => Datetime,
+		"datetime" | "datetime2" | "timestamp" => Datetime,
 		"time" => Time,
-		"boolean" => Bool,
-		_ => bail!("Unknown SQL type: '{}'", str),
+		"boolean" | "bool" | "bit" => Bool,
+		_ => return None,
 	})
 }
 
 fn parse_types_package<R: Read>(
 	parser: &mut MyEventReader<R>,
-) -> Result<Vec<(String, SQLTypeName)>> {
+) -> Result<Vec<(String, String)>, ParseProjectError> {
 	let mut types = vec![];
 
 	fn is_primitive_type_element(name: &OwnedName, attrs: &[OwnedAttribute]) -> bool {
@@ -145,7 +180,7 @@ fn parse_types_package<R: Read>(
 			if !type_name.eq("StructuredExpression") {
 				types.push((
 					get_attribute(&attrs, Some("xmi"), "id")?.to_string(),
-					parse_type_name(type_name)?,
+					type_name.to_string(),
 				));
 			}
 		}
@@ -156,12 +191,13 @@ fn parse_types_package<R: Read>(
 }
 
 fn parse_primitive_types<R: Read>(
+	file_name: &str,
 	reader: R,
 	used_packages: &[UsedPackage],
-) -> Result<Vec<(String, SQLTypeName)>> {
+) -> Result<Vec<(String, String)>, ParseProjectError> {
 	let mut types = vec![];
 
-	let mut parser: MyEventReader<_> = EventReader::new(reader).into();
+	let mut parser = MyEventReader::new(file_name, reader);
 	loop {
 		match parser.next()? {
 			XmlEvent::StartElement {
@@ -170,8 +206,9 @@ fn parse_primitive_types<R: Read>(
 				if check_name(&name, Some("uml"), "Package") {
 					if let Some(id) = get_attribute(&attributes, None, "ID").ok() {
 						let id = id.to_string();
-						if let Some(package) =
-							used_packages.iter().find(|p| p.share_point_ids.contains(&id))
+						if let Some(package) = used_packages
+							.iter()
+							.find(|p| p.share_point_ids.contains(&id))
 						{
 							let package_types = parse_types_package(&mut parser)?
 								.into_iter()
@@ -194,27 +231,188 @@ fn parse_primitive_types<R: Read>(
 	Ok(types)
 }
 
+// Primitive types which were copied into the project itself, instead of being used from a shared project
+fn parse_local_primitive_types<R: Read>(
+	file_name: &str,
+	reader: R,
+) -> Result<Vec<(String, String)>, ParseProjectError> {
+	let mut types = vec![];
+
+	fn is_local_primitive_type_element(name: &OwnedName, attrs: &[OwnedAttribute]) -> bool {
+		check_name(name, None, "packagedElement")
+			&& check_attribute(attrs, Some("xmi"), "type", "uml:PrimitiveType")
+	}
+
+	let mut parser = MyEventReader::new(file_name, reader);
+	loop {
+		match parser.next()? {
+			XmlEvent::StartElement {
+				name, attributes, ..
+			} if is_local_primitive_type_element(&name, &attributes) => {
+				let id = unwrap_opt_continue!(get_attribute(&attributes, Some("xmi"), "id").ok());
+				let type_name = unwrap_opt_continue!(get_attribute(&attributes, None, "name").ok());
+				types.push((id.to_string(), type_name.to_string()));
+			}
+			XmlEvent::EndDocument => {
+				break;
+			}
+			_ => {}
+		}
+	}
+
+	Ok(types)
+}
+
+// User supplied mappings are checked first, so they can also override built-in names
+fn find_type_mapping(
+	type_name: &str,
+	type_mappings: &HashMap<String, TypeMapping>,
+) -> Option<TypeMapping> {
+	if let Some(mapping) = type_mappings.get(&type_name.trim().to_lowercase()) {
+		return Some(mapping.clone());
+	}
+	parse_type_name(type_name).map(|type_name| TypeMapping {
+		type_name,
+		default_sizes: vec![],
+	})
+}
+
+fn add_sql_type(
+	types: &mut ParsedSQLTypes,
+	id: String,
+	type_name: String,
+	type_mappings: &HashMap<String, TypeMapping>,
+) {
+	if let Some(mapping) = find_type_mapping(&type_name, type_mappings) {
+		if !mapping.default_sizes.is_empty() {
+			types
+				.default_sizes
+				.insert(id.clone(), mapping.default_sizes);
+		}
+		types.type_names.insert(id, mapping.type_name);
+	} else {
+		types.unknown_type_names.insert(id, type_name);
+	}
+}
+
+/// Resolves types of a plain XML export, where there are no used projects to look them up in.
+/// Types are either defined in the file itself or are standard types referenced by name, like
+/// `PrimitiveTypes.xmi#Integer`
+pub fn parse_sql_types_file<R: Read>(
+	file_name: &str,
+	file: R,
+	needed_types: &HashSet<&String>,
+	type_mappings: &HashMap<String, TypeMapping>,
+) -> Result<ParsedSQLTypes, ParseProjectError> {
+	let mut types = ParsedSQLTypes {
+		type_names: HashMap::new(),
+		default_sizes: HashMap::new(),
+		unknown_type_names: HashMap::new(),
+		loaded_packages: vec![],
+		missing_packages: HashSet::new(),
+	};
+
+	for needed_type in needed_types {
+		let (_, type_name) = unwrap_opt_continue!(needed_type.rsplit_once('#'));
+		if find_type_mapping(type_name, type_mappings).is_some() {
+			add_sql_type(
+				&mut types,
+				needed_type.to_string(),
+				type_name.to_string(),
+				type_mappings,
+			);
+		}
+	}
+
+	for (id, type_name) in parse_local_primitive_types(file_name, file)? {
+		add_sql_type(&mut types, id, type_name, type_mappings);
+	}
+
+	Ok(types)
+}
+
 pub fn parse_sql_types<R: Read + Seek>(
 	project: &mut ZipArchive<R>,
 	needed_types: &HashSet<&String>,
-) -> Result<HashMap<String, SQLTypeName>> {
-	let mut type_names = HashMap::new();
-
-	let meta_model_file = project.by_name("com.nomagic.ci.metamodel.project")?;
+	type_mappings: &HashMap<String, TypeMapping>,
+) -> Result<ParsedSQLTypes, ParseProjectError> {
+	let meta_model_file = open_zip_entry(project, META_MODEL_FILE_NAME).map_err(|e| match e {
+		ParseProjectError::MissingZipEntry(name) => ParseProjectError::MissingMetaModel(name),
+		e => e,
+	})?;
 	let used_packages = list_used_packages(meta_model_file, needed_types)?;
 
-	let snapshot_files = project
-		.file_names()
-		.filter(|f| is_umodel_snapshot_file(f))
-		.map(|f| f.to_string())
+	// Entries are visited by index, so the names of all the other files don't need to be copied
+	let mut types = vec![];
+	for i in 0..project.len() {
+		let file = project
+			.by_index(i)
+			.map_err(|e| ParseProjectError::InvalidZip(e.to_string()))?;
+		if is_umodel_snapshot_file(file.name()) {
+			let filename = file.name().to_string();
+			types.extend(parse_primitive_types(&filename, file, &used_packages)?);
+		} else if file.name().ends_with(MODEL_FILE_NAME) || is_model_fragment_file(file.name()) {
+			let filename = file.name().to_string();
+			types.extend(parse_local_primitive_types(&filename, file)?);
+		}
+	}
+
+	let mut loaded_packages = types
+		.iter()
+		.filter_map(|(id, _)| get_type_package_name(id))
+		.map(str::to_string)
+		.collect::<HashSet<_>>()
+		.into_iter()
 		.collect::<Vec<_>>();
+	loaded_packages.sort();
+	let missing_packages = needed_types
+		.iter()
+		.filter_map(|type_href| get_type_package_name(type_href))
+		.filter(|package_name| !loaded_packages.iter().any(|loaded| loaded == package_name))
+		.map(str::to_string)
+		.collect();
 
-	for filename in &snapshot_files {
-		let f = project.by_name(filename).unwrap();
-		for (id, type_name) in parse_primitive_types(f, &used_packages)? {
-			type_names.insert(id, type_name);
-		}
+	let mut sql_types = ParsedSQLTypes {
+		type_names: HashMap::new(),
+		default_sizes: HashMap::new(),
+		unknown_type_names: HashMap::new(),
+		loaded_packages,
+		missing_packages,
+	};
+	for (id, type_name) in types {
+		add_sql_type(&mut sql_types, id, type_name, type_mappings);
 	}
 
-	Ok(type_names)
+	Ok(sql_types)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn type_name_synonyms() {
+		use SQLTypeName::*;
+		let expected = [
+			("INTEGER", Int),
+			("bigint", Int),
+			("NUMERIC", Decimal),
+			("number", Decimal),
+			("Character Varying", Varchar),
+			("varchar2", Varchar),
+			("nvarchar", Varchar),
+			("text", Text),
+			("CLOB", Text),
+			("nchar", Char),
+			("double precision", Float),
+			("datetime2", Datetime),
+			("timestamp", Datetime),
+			("bit", Bool),
+			(" date ", Date),
+		];
+		for (name, type_name) in expected {
+			assert_eq!(parse_type_name(name), Some(type_name), "{}", name);
+		}
+		assert_eq!(parse_type_name("geometry"), None);
+	}
 }
@@ -1,7 +1,6 @@
 use std::io::{Read, Seek};
 
-use anyhow::{Context, Result};
-use xml::{attribute::OwnedAttribute, name::OwnedName, reader::XmlEvent, EventReader};
+use xml::{attribute::OwnedAttribute, name::OwnedName, reader::XmlEvent};
 use zip::ZipArchive;
 
 use crate::{unwrap_err_continue, unwrap_opt_continue};
@@ -11,12 +10,19 @@ use super::utils::{
 	MyEventReader, ParseProjectError,
 };
 
+pub const MODEL_FILE_NAME: &str = "com.nomagic.magicdraw.uml_model.model";
+const FRAGMENT_FILE_SUFFIX: &str = ".fragment";
+
 #[derive(Debug)]
 pub struct UMLProperty {
 	pub id: String,
 	pub name: Option<String>,
 	pub is_id: bool,
 	pub type_href: Option<String>,
+	pub type_id: Option<String>,
+	pub association_id: Option<String>,
+	pub lower_value: Option<String>,
+	pub upper_value: Option<String>,
 }
 
 // TODO: Make this an enum? Because from what I have seen there were only 2 cases,
@@ -25,6 +31,7 @@ pub struct UMLProperty {
 #[derive(Debug)]
 pub struct UMLConstraint {
 	pub id: String,
+	pub name: Option<String>,
 	pub class_id: Option<String>,
 	pub property_id: Option<String>,
 	pub property_name: Option<String>,
@@ -35,15 +42,32 @@ pub struct UMLConstraint {
 pub struct UMLClass {
 	pub id: String,
 	pub name: Option<String>,
+	pub is_abstract: bool,
 	pub properties: Vec<UMLProperty>,
 	pub constraints: Vec<UMLConstraint>,
 }
 
+#[derive(Debug)]
+pub struct UMLAssociation {
+	pub id: String,
+	pub member_end_ids: Vec<String>,
+	pub owned_ends: Vec<UMLProperty>,
+}
+
+#[derive(Debug)]
+pub struct UMLEnumeration {
+	pub id: String,
+	pub name: Option<String>,
+	pub literals: Vec<String>,
+}
+
 #[derive(Debug)]
 pub struct UMLPackage {
 	pub id: String,
 	pub name: Option<String>,
 	pub classess: Vec<UMLClass>,
+	pub associations: Vec<UMLAssociation>,
+	pub enumerations: Vec<UMLEnumeration>,
 }
 
 #[derive(Debug)]
@@ -64,15 +88,31 @@ pub struct UMLNullableModifier {
 	pub nullable: bool,
 }
 
+#[derive(Debug)]
+pub struct UMLAutoIncrementModifier {
+	pub property_id: String,
+	pub auto_increment: bool,
+}
+
 #[derive(Debug)]
 pub struct UMLForeignKeyModifier {
 	pub from_property_id: String,
 	pub to_property_id: String,
+	/// Name of the constraint the stereotype is applied to, like `fk_order_customer`
+	pub name: Option<String>,
 }
 
 #[derive(Debug)]
 pub struct UMLUniqueModifier {
-	pub property_id: String,
+	/// Columns whose combination is unique, in the order they are listed
+	pub property_ids: Vec<String>,
+}
+
+#[derive(Debug)]
+pub struct UMLIndexModifier {
+	pub name: Option<String>,
+	pub property_ids: Vec<String>,
+	pub unique: bool,
 }
 
 #[derive(Debug)]
@@ -81,6 +121,22 @@ pub struct UMLTypeModifier {
 	pub modifier: String,
 }
 
+#[derive(Debug)]
+pub struct UMLSchemaModifier {
+	pub package_id: String,
+}
+
+/// Applied by stereotypes like «transient», which mark classes that aren't stored
+#[derive(Debug)]
+pub struct UMLTransientModifier {
+	pub class_id: String,
+}
+
+#[derive(Debug)]
+pub struct UMLViewModifier {
+	pub class_id: String,
+}
+
 #[derive(Debug)]
 pub enum UMLModifier {
 	Unique(UMLUniqueModifier),
@@ -88,24 +144,49 @@ pub enum UMLModifier {
 	Nullable(UMLNullableModifier),
 	ForeignKey(UMLForeignKeyModifier),
 	Type(UMLTypeModifier),
+	Schema(UMLSchemaModifier),
+	Transient(UMLTransientModifier),
+	AutoIncrement(UMLAutoIncrementModifier),
+	View(UMLViewModifier),
+	Index(UMLIndexModifier),
 }
 
 fn parse_property<R: Read>(
 	parser: &mut MyEventReader<R>,
 	attrs: &[OwnedAttribute],
-) -> Result<UMLProperty> {
+) -> Result<UMLProperty, ParseProjectError> {
 	let id = get_attribute(attrs, Some("xmi"), "id")?.into();
 	let name = get_attribute(attrs, None, "name").ok().map(str::to_string);
 	let is_id = get_attribute(attrs, None, "isID")
 		.unwrap_or("false")
 		.eq("true");
+	let type_id = get_attribute(attrs, None, "type").ok().map(str::to_string);
+	let association_id = get_attribute(attrs, None, "association")
+		.ok()
+		.map(str::to_string);
 	let mut type_href = None;
+	let mut lower_value = None;
+	let mut upper_value = None;
 
 	parse_element(parser, &mut |p, name, attrs| {
 		if check_name(&name, None, "type") && type_href.is_none() {
 			if let Ok(value) = get_attribute(&attrs, None, "href") {
-				type_href = Some(value.to_string());
+				// Types defined in the same project are referenced without a project name
+				type_href = Some(value.strip_prefix('#').unwrap_or(value).to_string());
 			}
+		} else if check_name(&name, None, "lowerValue") {
+			// A literal without a value attribute has the default value of 0
+			lower_value = Some(
+				get_attribute(&attrs, None, "value")
+					.unwrap_or("0")
+					.to_string(),
+			);
+		} else if check_name(&name, None, "upperValue") {
+			upper_value = Some(
+				get_attribute(&attrs, None, "value")
+					.unwrap_or("0")
+					.to_string(),
+			);
 		}
 		Ok(())
 	})?;
@@ -115,14 +196,52 @@ fn parse_property<R: Read>(
 		name,
 		is_id,
 		type_href,
+		type_id,
+		association_id,
+		lower_value,
+		upper_value,
+	})
+}
+
+fn parse_association<R: Read>(
+	parser: &mut MyEventReader<R>,
+	attrs: &[OwnedAttribute],
+) -> Result<UMLAssociation, ParseProjectError> {
+	let id = get_attribute(attrs, Some("xmi"), "id")?.into();
+	let mut member_end_ids = get_attribute(attrs, None, "memberEnd")
+		.map(|ids| ids.split_whitespace().map(str::to_string).collect())
+		.unwrap_or(vec![]);
+	let mut owned_ends = vec![];
+
+	fn is_owned_end_element(name: &OwnedName, attrs: &[OwnedAttribute]) -> bool {
+		check_name(name, None, "ownedEnd")
+			&& check_attribute(attrs, Some("xmi"), "type", "uml:Property")
+	}
+
+	parse_element(parser, &mut |p, name, attrs| {
+		if check_name(&name, None, "memberEnd") {
+			if let Ok(member_end_id) = get_attribute(&attrs, Some("xmi"), "idref") {
+				member_end_ids.push(member_end_id.to_string());
+			}
+		} else if is_owned_end_element(&name, &attrs) {
+			owned_ends.push(parse_property(p, &attrs)?);
+		}
+		Ok(())
+	})?;
+
+	Ok(UMLAssociation {
+		id,
+		member_end_ids,
+		owned_ends,
 	})
 }
 
 fn parse_constraint<R: Read>(
 	parser: &mut MyEventReader<R>,
 	attrs: &[OwnedAttribute],
-) -> Result<Option<UMLConstraint>> {
+) -> Result<Option<UMLConstraint>, ParseProjectError> {
 	let id = get_attribute(attrs, Some("xmi"), "id")?.into();
+	let name = get_attribute(attrs, None, "name").ok().map(str::to_string);
 	let mut constrainted_element_id = None;
 	let mut language = None;
 	let mut body = None;
@@ -134,21 +253,25 @@ fn parse_constraint<R: Read>(
 				.map(str::to_string);
 		} else if check_name(&name, None, "body") && body.is_none() {
 			let contents = get_element_characters(p)?;
-			if contents.len() > 0 {
+			if !contents.trim().is_empty() {
 				body = Some(contents);
 			}
 		} else if check_name(&name, None, "language") && language.is_none() {
-			language = Some(get_element_characters(p)?);
+			language = Some(get_element_characters(p)?.trim().to_string());
 		}
 		Ok(())
 	})?;
 
-	if language.eq(&Some("SQL".into())) && body.is_some() {
-		if let Some((prop_name, check_body)) = body.unwrap().split_once(" in ") {
+	if let (Some("SQL"), Some(body)) = (language.as_deref(), &body) {
+		if let Some((prop_name, check_body)) = body.trim().split_once(char::is_whitespace) {
 			return Ok(Some(UMLConstraint {
 				id,
-				class_id: Some(constrainted_element_id.context("Missing constraint class id")?),
-				body: Some(format!("in {}", check_body)),
+				name,
+				class_id: Some(
+					constrainted_element_id
+						.ok_or(ParseProjectError::MissingElement("constraint class id"))?,
+				),
+				body: Some(check_body.trim().to_string()),
 				property_id: None,
 				property_name: Some(prop_name.into()),
 			}));
@@ -161,6 +284,7 @@ fn parse_constraint<R: Read>(
 
 	return Ok(Some(UMLConstraint {
 		id,
+		name,
 		property_id: Some(constrainted_element_id.unwrap()),
 		body: None,
 		class_id: None,
@@ -171,11 +295,12 @@ fn parse_constraint<R: Read>(
 fn parse_class<R: Read>(
 	parser: &mut MyEventReader<R>,
 	attrs: &[OwnedAttribute],
-) -> Result<UMLClass> {
+) -> Result<UMLClass, ParseProjectError> {
 	let mut properties = vec![];
 	let mut consraints = vec![];
 	let id = get_attribute(attrs, Some("xmi"), "id")?.into();
 	let name = get_attribute(attrs, None, "name").ok().map(str::to_string);
+	let is_abstract = check_attribute(attrs, None, "isAbstract", "true");
 
 	fn is_property_element(name: &OwnedName, attrs: &[OwnedAttribute]) -> bool {
 		check_name(name, None, "ownedAttribute")
@@ -201,54 +326,123 @@ fn parse_class<R: Read>(
 	Ok(UMLClass {
 		id,
 		name,
+		is_abstract,
 		properties,
 		constraints: consraints,
 	})
 }
 
-fn parse_package<R: Read>(
+fn parse_enumeration<R: Read>(
 	parser: &mut MyEventReader<R>,
 	attrs: &[OwnedAttribute],
-) -> Result<UMLPackage> {
-	let mut classess = vec![];
+) -> Result<UMLEnumeration, ParseProjectError> {
+	let mut literals = vec![];
 	let id = get_attribute(attrs, Some("xmi"), "id")?.into();
 	let name = get_attribute(attrs, None, "name").ok().map(str::to_string);
 
-	fn is_class_element(name: &OwnedName, attrs: &[OwnedAttribute]) -> bool {
-		check_name(name, None, "packagedElement")
-			&& check_attribute(&attrs, Some("xmi"), "type", "uml:Class")
+	fn is_literal_element(name: &OwnedName, attrs: &[OwnedAttribute]) -> bool {
+		check_name(name, None, "ownedLiteral")
+			&& check_attribute(attrs, Some("xmi"), "type", "uml:EnumerationLiteral")
 	}
 
+	parse_element(parser, &mut |_, name, attrs| {
+		if is_literal_element(&name, &attrs) {
+			if let Ok(literal) = get_attribute(&attrs, None, "name") {
+				literals.push(literal.to_string());
+			}
+		}
+		Ok(())
+	})?;
+
+	Ok(UMLEnumeration { id, name, literals })
+}
+
+fn is_class_element(name: &OwnedName, attrs: &[OwnedAttribute]) -> bool {
+	check_name(name, None, "packagedElement")
+		&& check_attribute(attrs, Some("xmi"), "type", "uml:Class")
+}
+
+fn is_association_element(name: &OwnedName, attrs: &[OwnedAttribute]) -> bool {
+	check_name(name, None, "packagedElement")
+		&& check_attribute(attrs, Some("xmi"), "type", "uml:Association")
+}
+
+fn is_enumeration_element(name: &OwnedName, attrs: &[OwnedAttribute]) -> bool {
+	check_name(name, None, "packagedElement")
+		&& check_attribute(attrs, Some("xmi"), "type", "uml:Enumeration")
+}
+
+fn is_package_element(name: &OwnedName, attrs: &[OwnedAttribute]) -> bool {
+	check_name(name, None, "packagedElement")
+		&& check_attribute(attrs, Some("xmi"), "type", "uml:Package")
+}
+
+fn parse_package<R: Read>(
+	parser: &mut MyEventReader<R>,
+	attrs: &[OwnedAttribute],
+) -> Result<UMLPackage, ParseProjectError> {
+	let mut classess = vec![];
+	let mut associations = vec![];
+	let mut enumerations = vec![];
+	let id = get_attribute(attrs, Some("xmi"), "id")?.into();
+	let name = get_attribute(attrs, None, "name").ok().map(str::to_string);
+
 	parse_element(parser, &mut |p, name, attrs| {
 		if is_class_element(&name, &attrs) {
 			classess.push(parse_class(p, &attrs)?);
+		} else if is_association_element(&name, &attrs) {
+			associations.push(parse_association(p, &attrs)?);
+		} else if is_enumeration_element(&name, &attrs) {
+			enumerations.push(parse_enumeration(p, &attrs)?);
 		}
 		Ok(())
 	})?;
 
-	Ok(UMLPackage { id, name, classess })
+	Ok(UMLPackage {
+		id,
+		name,
+		classess,
+		associations,
+		enumerations,
+	})
 }
 
 fn parse_model<R: Read>(
 	parser: &mut MyEventReader<R>,
 	attrs: &[OwnedAttribute],
-) -> Result<UMLModel> {
+) -> Result<UMLModel, ParseProjectError> {
 	let mut packages = vec![];
-	let id = get_attribute(attrs, Some("xmi"), "id")?.into();
+	let id: String = get_attribute(attrs, Some("xmi"), "id")?.into();
 	let name = get_attribute(attrs, None, "name")?.into();
 
-	fn is_package_element(name: &OwnedName, attrs: &[OwnedAttribute]) -> bool {
-		check_name(name, None, "packagedElement")
-			&& check_attribute(&attrs, Some("xmi"), "type", "uml:Package")
-	}
+	// Exported models can have elements directly inside of the model, without a package
+	let mut root_classess = vec![];
+	let mut root_associations = vec![];
+	let mut root_enumerations = vec![];
 
 	parse_element(parser, &mut |p, name, attrs| {
 		if is_package_element(&name, &attrs) {
 			packages.push(parse_package(p, &attrs)?);
+		} else if is_class_element(&name, &attrs) {
+			root_classess.push(parse_class(p, &attrs)?);
+		} else if is_association_element(&name, &attrs) {
+			root_associations.push(parse_association(p, &attrs)?);
+		} else if is_enumeration_element(&name, &attrs) {
+			root_enumerations.push(parse_enumeration(p, &attrs)?);
 		}
 		Ok(())
 	})?;
 
+	if !root_classess.is_empty() || !root_enumerations.is_empty() {
+		packages.push(UMLPackage {
+			id: id.clone(),
+			name: None,
+			classess: root_classess,
+			associations: root_associations,
+			enumerations: root_enumerations,
+		});
+	}
+
 	Ok(UMLModel { id, name, packages })
 }
 
@@ -268,14 +462,73 @@ fn find_constraint_by_id<'a>(models: &'a [UMLModel], id: &str) -> Option<&'a UML
 	None
 }
 
+/// Model files of projects saved from Teamwork Cloud, which split the model into fragments
+pub fn is_model_fragment_file(file_name: &str) -> bool {
+	file_name.ends_with(FRAGMENT_FILE_SUFFIX)
+}
+
+/// Reads the model file and all model fragments. A project needs to have at least one of them.
 pub fn parse_uml_model<R: Read + Seek>(
 	project: &mut ZipArchive<R>,
-) -> Result<(Vec<UMLModel>, Vec<UMLModifier>)> {
+) -> Result<(Vec<UMLModel>, Vec<UMLModifier>), ParseProjectError> {
 	let mut models = vec![];
 	let mut modifiers = vec![];
 
-	let file = project.by_name("com.nomagic.magicdraw.uml_model.model")?;
-	let mut parser: MyEventReader<_> = EventReader::new(file).into();
+	let mut found_model_file = false;
+	for i in 0..project.len() {
+		let file = project
+			.by_index(i)
+			.map_err(|e| ParseProjectError::InvalidZip(e.to_string()))?;
+		if file.name() == MODEL_FILE_NAME || is_model_fragment_file(file.name()) {
+			let file_name = file.name().to_string();
+			parse_uml_model_into(&file_name, file, &mut models, &mut modifiers)?;
+			found_model_file = true;
+		}
+	}
+
+	if !found_model_file {
+		return Err(ParseProjectError::MissingUMLModel(MODEL_FILE_NAME.into()));
+	}
+	Ok((models, modifiers))
+}
+
+// Stereotypes from any profile count, since there is no standard one for non-persistent classes
+fn is_transient_stereotype(name: &OwnedName) -> bool {
+	["transient", "nonpersistent", "notpersistent"]
+		.iter()
+		.any(|stereotype| name.local_name.eq_ignore_ascii_case(stereotype))
+}
+
+/// Parses a single UML model file, like the one inside of a project or a plain XML export
+pub fn parse_uml_model_file<R: Read>(
+	file_name: &str,
+	file: R,
+) -> Result<(Vec<UMLModel>, Vec<UMLModifier>), ParseProjectError> {
+	let mut models = vec![];
+	let mut modifiers = vec![];
+	parse_uml_model_into(file_name, file, &mut models, &mut modifiers)?;
+	Ok((models, modifiers))
+}
+
+// Stereotypes may be applied to elements from files parsed before, so everything is collected
+// into the same lists
+fn parse_uml_model_into<R: Read>(
+	file_name: &str,
+	file: R,
+	models: &mut Vec<UMLModel>,
+	modifiers: &mut Vec<UMLModifier>,
+) -> Result<(), ParseProjectError> {
+	let mut parser = MyEventReader::new(file_name, file);
+
+	// Fragments contain packaged elements without a surrounding model
+	let mut fragment = UMLPackage {
+		id: file_name.into(),
+		name: None,
+		classess: vec![],
+		associations: vec![],
+		enumerations: vec![],
+	};
+	let mut fragment_packages = vec![];
 
 	loop {
 		match parser.next()? {
@@ -284,11 +537,25 @@ pub fn parse_uml_model<R: Read + Seek>(
 			} => {
 				if check_name(&name, Some("uml"), "Model") {
 					models.push(parse_model(&mut parser, &attributes)?);
+				} else if is_package_element(&name, &attributes) {
+					fragment_packages.push(parse_package(&mut parser, &attributes)?);
+				} else if is_class_element(&name, &attributes) {
+					fragment
+						.classess
+						.push(parse_class(&mut parser, &attributes)?);
+				} else if is_association_element(&name, &attributes) {
+					fragment
+						.associations
+						.push(parse_association(&mut parser, &attributes)?);
+				} else if is_enumeration_element(&name, &attributes) {
+					fragment
+						.enumerations
+						.push(parse_enumeration(&mut parser, &attributes)?);
 				} else if check_name(&name, Some("SQLProfile"), "PrimaryKey") {
 					let constraint_id =
 						unwrap_err_continue!(get_attribute(&attributes, None, "base_Constraint"));
 					let constraint =
-						unwrap_opt_continue!(find_constraint_by_id(&models, constraint_id));
+						unwrap_opt_continue!(find_constraint_by_id(models, constraint_id));
 					let property_id = unwrap_opt_continue!(&constraint.property_id).clone();
 					modifiers.push(UMLModifier::PirmaryKey(UMLPrimaryKeyModifier {
 						property_id,
@@ -304,6 +571,12 @@ pub fn parse_uml_model<R: Read + Seek>(
 					let property_id =
 						unwrap_err_continue!(get_attribute(&attributes, None, "base_Property"))
 							.to_string();
+					if let Ok(auto_increment) = get_attribute(&attributes, None, "autoIncrement") {
+						modifiers.push(UMLModifier::AutoIncrement(UMLAutoIncrementModifier {
+							property_id: property_id.clone(),
+							auto_increment: auto_increment.eq("true"),
+						}));
+					}
 					let nullable =
 						unwrap_err_continue!(get_attribute(&attributes, None, "nullable"))
 							.eq("true");
@@ -328,10 +601,79 @@ pub fn parse_uml_model<R: Read + Seek>(
 					let to_property_id =
 						unwrap_err_continue!(get_attribute(&attributes, None, "referencedMembers"))
 							.into();
+					let name = get_attribute(&attributes, None, "base_Constraint")
+						.ok()
+						.and_then(|constraint_id| find_constraint_by_id(models, constraint_id))
+						.and_then(|constraint| constraint.name.clone());
 					modifiers.push(UMLModifier::ForeignKey(UMLForeignKeyModifier {
 						from_property_id,
 						to_property_id,
+						name,
 					}));
+				} else if check_name(&name, Some("SQLProfile"), "Schema") {
+					let package_id =
+						unwrap_err_continue!(get_attribute(&attributes, None, "base_Package"))
+							.into();
+					modifiers.push(UMLModifier::Schema(UMLSchemaModifier { package_id }));
+				} else if check_name(&name, Some("SQLProfile"), "Unique") {
+					let property_ids = match get_attribute(&attributes, None, "members") {
+						Ok(members) => members.split_whitespace().map(str::to_string).collect(),
+						Err(_) => {
+							let constraint_id = unwrap_err_continue!(get_attribute(
+								&attributes,
+								None,
+								"base_Constraint"
+							));
+							let constraint =
+								unwrap_opt_continue!(find_constraint_by_id(models, constraint_id));
+							vec![unwrap_opt_continue!(&constraint.property_id).clone()]
+						}
+					};
+					if !property_ids.is_empty() {
+						modifiers.push(UMLModifier::Unique(UMLUniqueModifier { property_ids }));
+					}
+				} else if check_name(&name, Some("SQLProfile"), "Index") {
+					let constraint = get_attribute(&attributes, None, "base_Constraint")
+						.ok()
+						.and_then(|constraint_id| find_constraint_by_id(models, constraint_id));
+					let property_ids: Vec<String> =
+						match get_attribute(&attributes, None, "members") {
+							Ok(members) => members.split_whitespace().map(str::to_string).collect(),
+							Err(_) => constraint
+								.and_then(|constraint| constraint.property_id.clone())
+								.into_iter()
+								.collect(),
+						};
+					let unique = get_attribute(&attributes, None, "unique")
+						.map(|unique| unique.eq("true"))
+						.unwrap_or(false);
+					if !property_ids.is_empty() {
+						modifiers.push(UMLModifier::Index(UMLIndexModifier {
+							name: constraint.and_then(|constraint| constraint.name.clone()),
+							property_ids,
+							unique,
+						}));
+					}
+				} else if check_name(&name, Some("SQLProfile"), "Identity")
+					|| check_name(&name, Some("SQLProfile"), "AutoIncrement")
+				{
+					let property_id =
+						unwrap_err_continue!(get_attribute(&attributes, None, "base_Property"))
+							.to_string();
+					modifiers.push(UMLModifier::AutoIncrement(UMLAutoIncrementModifier {
+						property_id,
+						auto_increment: true,
+					}));
+				} else if check_name(&name, Some("SQLProfile"), "View")
+					|| check_name(&name, Some("SQLProfile"), "ViewTable")
+				{
+					let class_id =
+						unwrap_err_continue!(get_attribute(&attributes, None, "base_Class")).into();
+					modifiers.push(UMLModifier::View(UMLViewModifier { class_id }));
+				} else if is_transient_stereotype(&name) {
+					let class_id =
+						unwrap_err_continue!(get_attribute(&attributes, None, "base_Class")).into();
+					modifiers.push(UMLModifier::Transient(UMLTransientModifier { class_id }));
 				}
 			}
 			XmlEvent::EndDocument => {
@@ -341,5 +683,16 @@ pub fn parse_uml_model<R: Read + Seek>(
 		}
 	}
 
-	Ok((models, modifiers))
+	if !fragment.classess.is_empty() || !fragment.enumerations.is_empty() {
+		fragment_packages.push(fragment);
+	}
+	if !fragment_packages.is_empty() {
+		models.push(UMLModel {
+			id: file_name.into(),
+			name: String::new(),
+			packages: fragment_packages,
+		});
+	}
+
+	Ok(())
 }
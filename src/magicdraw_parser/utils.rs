@@ -1,17 +1,46 @@
-use std::io::Read;
+use std::{
+	future::Future,
+	io::{BufReader, Read, Seek},
+	pin::pin,
+	ptr,
+	task::{Context, Poll, RawWaker, RawWakerVTable, Waker},
+};
 
-use anyhow::Result;
 use thiserror::Error;
-use xml::{attribute::OwnedAttribute, name::OwnedName, reader::XmlEvent, EventReader};
+use xml::{
+	attribute::OwnedAttribute,
+	common::{Position, TextPosition},
+	name::OwnedName,
+	reader::XmlEvent,
+	EventReader,
+};
+use zip::{read::ZipFile, result::ZipError, ZipArchive};
 
 pub struct MyEventReader<R: Read> {
 	depth: u32,
-	event_reader: EventReader<R>,
+	file_name: String,
+	event_reader: EventReader<BufReader<R>>,
 }
 
 impl<R: Read> MyEventReader<R> {
-	pub fn next(&mut self) -> Result<XmlEvent> {
-		let event = self.event_reader.next()?;
+	pub fn new(file_name: &str, reader: R) -> Self {
+		MyEventReader {
+			depth: 0,
+			file_name: file_name.into(),
+			// The xml reader pulls one byte at a time, which is very slow straight from a zip entry
+			event_reader: EventReader::new(BufReader::new(reader)),
+		}
+	}
+
+	pub fn next(&mut self) -> Result<XmlEvent, ParseProjectError> {
+		let event = self
+			.event_reader
+			.next()
+			.map_err(|e| ParseProjectError::MalformedXml {
+				file: self.file_name.clone(),
+				position: e.position(),
+				message: e.msg().to_string(),
+			})?;
 		if let XmlEvent::StartElement { .. } = event {
 			self.depth += 1;
 		} else if let XmlEvent::EndElement { .. } = event {
@@ -26,22 +55,69 @@ impl<R: Read> MyEventReader<R> {
 	}
 }
 
-impl<R: Read> From<EventReader<R>> for MyEventReader<R> {
-	fn from(event_reader: EventReader<R>) -> Self {
-		MyEventReader {
-			depth: 0,
-			event_reader,
-		}
-	}
-}
-
 #[derive(Error, Debug, PartialEq)]
 pub enum ParseProjectError {
+	#[error("File is not a valid zip archive: {0}")]
+	InvalidZip(String),
+
+	#[error("Project file '{0}' not found")]
+	MissingZipEntry(String),
+
+	#[error("This doesn't look like a MagicDraw project, expected it to contain: {}", .0.join(", "))]
+	NotAMagicDrawProject(Vec<String>),
+
+	#[error("This project has no DDL script configured, create one in MagicDraw under Code Engineering Sets")]
+	MissingDDLScript,
+
+	#[error("This project has no UML model ('{0}'), make sure it was saved with MagicDraw")]
+	MissingUMLModel(String),
+
+	#[error("This project has no meta-model ('{0}'), so used type libraries can't be resolved")]
+	MissingMetaModel(String),
+
+	#[error("Malformed XML in '{file}' at {position}: {message}")]
+	MalformedXml {
+		file: String,
+		position: TextPosition,
+		message: String,
+	},
+
 	#[error("XML attribute '{}' not found", format_name_from_parts(.0, .1))]
 	AttributeNotFound(Option<String>, String),
 
 	#[error("Unexpected end of XML document")]
 	EndOfDocument,
+
+	#[error("Missing {0}")]
+	MissingElement(&'static str),
+
+	#[error("UML class '{0}' has no name")]
+	MissingClassName(String),
+
+	#[error("UML property '{0}' not found")]
+	PropertyNotFound(String),
+
+	#[error("UML property '{0}' has no name")]
+	MissingPropertyName(String),
+
+	#[error("Share point mount ids not found for used project '{0}'")]
+	MissingSharePoint(String),
+
+	#[error("Invalid type mapping '{0}', expected something like 'Money = DECIMAL(19,4)'")]
+	InvalidTypeMapping(String),
+
+	#[error("Strict mode found {} problem(s):\n{}", .0.len(), .0.join("\n"))]
+	StrictModeViolations(Vec<String>),
+}
+
+pub fn open_zip_entry<'a, R: Read + Seek>(
+	project: &'a mut ZipArchive<R>,
+	name: &str,
+) -> Result<ZipFile<'a>, ParseProjectError> {
+	project.by_name(name).map_err(|e| match e {
+		ZipError::FileNotFound => ParseProjectError::MissingZipEntry(name.into()),
+		e => ParseProjectError::InvalidZip(e.to_string()),
+	})
 }
 
 fn format_name_from_parts(prefix: &Option<String>, local_name: &str) -> String {
@@ -65,13 +141,13 @@ pub fn get_attribute<'a>(
 	prefix: Option<&str>,
 	name: &str,
 ) -> Result<&'a str, ParseProjectError> {
-	Ok(attributes
+	attributes
 		.iter()
 		.find(|attr| check_name(&attr.name, prefix, name))
 		.map(|attr| &attr.value[..])
 		.ok_or_else(|| {
 			ParseProjectError::AttributeNotFound(prefix.map(|s| s.to_owned()), name.to_owned())
-		})?)
+		})
 }
 
 #[inline(always)]
@@ -87,13 +163,16 @@ pub fn check_attribute(
 	false
 }
 
-pub fn get_element_characters<R: Read>(parser: &mut MyEventReader<R>) -> Result<String> {
-	let mut parts = vec![];
+pub fn get_element_characters<R: Read>(
+	parser: &mut MyEventReader<R>,
+) -> Result<String, ParseProjectError> {
+	let mut text = String::new();
 
+	// Spacing is kept as is, so CDATA sections and text split across lines stay intact
 	loop {
 		match parser.next()? {
-			XmlEvent::Characters(text) => {
-				parts.push(text);
+			XmlEvent::Characters(part) | XmlEvent::CData(part) | XmlEvent::Whitespace(part) => {
+				text.push_str(&part);
 			}
 			XmlEvent::EndElement { name } => {
 				break;
@@ -102,15 +181,19 @@ pub fn get_element_characters<R: Read>(parser: &mut MyEventReader<R>) -> Result<
 		}
 	}
 
-	Ok(parts.join(" "))
+	Ok(text)
 }
 
 pub fn parse_element<R: Read, F>(
 	parser: &mut MyEventReader<R>,
 	process_element: &mut F,
-) -> Result<()>
+) -> Result<(), ParseProjectError>
 where
-	F: FnMut(&mut MyEventReader<R>, OwnedName, Vec<OwnedAttribute>) -> Result<()>,
+	F: FnMut(
+		&mut MyEventReader<R>,
+		OwnedName,
+		Vec<OwnedAttribute>,
+	) -> Result<(), ParseProjectError>,
 {
 	let starting_depth = parser.depth();
 	loop {
@@ -136,6 +219,27 @@ where
 	return Ok(());
 }
 
+// `Waker::noop` would need Rust 1.85
+const NOOP_WAKER_VTABLE: RawWakerVTable = RawWakerVTable::new(
+	|_| RawWaker::new(ptr::null(), &NOOP_WAKER_VTABLE),
+	|_| {},
+	|_| {},
+	|_| {},
+);
+
+// Only meant for futures which never wait on anything external, like the parser with a no-op
+// progress callback. Such a future finishes on its first poll, anything else is a bug.
+pub fn block_on_ready<F: Future>(future: F) -> F::Output {
+	let mut future = pin!(future);
+	// SAFETY: none of the functions of the vtable use the data pointer
+	let waker = unsafe { Waker::from_raw(RawWaker::new(ptr::null(), &NOOP_WAKER_VTABLE)) };
+	let mut context = Context::from_waker(&waker);
+	match future.as_mut().poll(&mut context) {
+		Poll::Ready(output) => output,
+		Poll::Pending => unreachable!("block_on_ready was given a future which is not ready"),
+	}
+}
+
 #[macro_export]
 macro_rules! unwrap_err_continue {
 	($res:expr) => {
@@ -159,3 +263,45 @@ macro_rules! unwrap_opt_continue {
 		}
 	};
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn read_body(xml: &str) -> String {
+		let mut parser = MyEventReader::new("test.xml", xml.as_bytes());
+		loop {
+			if let XmlEvent::StartElement { .. } = parser.next().unwrap() {
+				break;
+			}
+		}
+		get_element_characters(&mut parser).unwrap()
+	}
+
+	#[test]
+	fn element_characters_keep_cdata_and_spacing() {
+		assert_eq!(
+			read_body("<body>status in (\n\t'new',\n\t'paid')</body>"),
+			"status in (\n\t'new',\n\t'paid')"
+		);
+		assert_eq!(
+			read_body("<body><![CDATA[status in ('a', 'b')]]></body>"),
+			"status in ('a', 'b')"
+		);
+		assert_eq!(
+			read_body("<body>status <![CDATA[in ('<', '>')]]></body>"),
+			"status in ('<', '>')"
+		);
+	}
+
+	#[test]
+	fn block_on_ready_returns_the_output() {
+		assert_eq!(block_on_ready(async { 42 }), 42);
+	}
+
+	#[test]
+	#[should_panic(expected = "not ready")]
+	fn block_on_ready_stops_on_pending_futures() {
+		block_on_ready(std::future::pending::<()>());
+	}
+}
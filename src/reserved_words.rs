@@ -0,0 +1,232 @@
+use crate::generate_sql::SQLDialect;
+
+// Reserved in every supported database, these can't be used as names without quotes
+const COMMON: &[&str] = &[
+	"ALL",
+	"ALTER",
+	"AND",
+	"ANY",
+	"AS",
+	"ASC",
+	"BETWEEN",
+	"BY",
+	"CASE",
+	"CHECK",
+	"COLUMN",
+	"CONSTRAINT",
+	"CREATE",
+	"CROSS",
+	"CURRENT_DATE",
+	"CURRENT_TIME",
+	"CURRENT_TIMESTAMP",
+	"DEFAULT",
+	"DELETE",
+	"DESC",
+	"DISTINCT",
+	"DROP",
+	"ELSE",
+	"EXISTS",
+	"FOR",
+	"FOREIGN",
+	"FROM",
+	"GRANT",
+	"GROUP",
+	"HAVING",
+	"IN",
+	"INNER",
+	"INSERT",
+	"INTO",
+	"IS",
+	"JOIN",
+	"LEFT",
+	"LIKE",
+	"NOT",
+	"NULL",
+	"ON",
+	"OR",
+	"ORDER",
+	"PRIMARY",
+	"REFERENCES",
+	"RIGHT",
+	"SELECT",
+	"SET",
+	"TABLE",
+	"THEN",
+	"TO",
+	"UNION",
+	"UNIQUE",
+	"UPDATE",
+	"USER",
+	"VALUES",
+	"WHEN",
+	"WHERE",
+	"WITH",
+];
+
+const MYSQL: &[&str] = &[
+	"CHANGE",
+	"CONDITION",
+	"DATABASE",
+	"DATABASES",
+	"DIV",
+	"DUAL",
+	"FULLTEXT",
+	"INDEX",
+	"INTERVAL",
+	"KEY",
+	"KEYS",
+	"KILL",
+	"LIMIT",
+	"LOCK",
+	"MATCH",
+	"MOD",
+	"OPTION",
+	"RANGE",
+	"READ",
+	"RELEASE",
+	"RENAME",
+	"REPLACE",
+	"RETURN",
+	"SCHEMA",
+	"SHOW",
+	"STATUS",
+	"TRIGGER",
+	"USAGE",
+	"WRITE",
+];
+
+const POSTGRES: &[&str] = &[
+	"ANALYSE",
+	"ANALYZE",
+	"ARRAY",
+	"ASYMMETRIC",
+	"BOTH",
+	"CAST",
+	"COLLATE",
+	"DO",
+	"END",
+	"FETCH",
+	"LATERAL",
+	"LEADING",
+	"LIMIT",
+	"OFFSET",
+	"ONLY",
+	"PLACING",
+	"RETURNING",
+	"SYMMETRIC",
+	"TRAILING",
+	"VARIADIC",
+	"WINDOW",
+];
+
+const SQLITE: &[&str] = &[
+	"ABORT",
+	"ACTION",
+	"AFTER",
+	"ATTACH",
+	"AUTOINCREMENT",
+	"COLLATE",
+	"CONFLICT",
+	"DETACH",
+	"END",
+	"ESCAPE",
+	"EXCEPT",
+	"GLOB",
+	"INDEX",
+	"LIMIT",
+	"OFFSET",
+	"PRAGMA",
+	"RAISE",
+	"REGEXP",
+	"REINDEX",
+	"RENAME",
+	"REPLACE",
+	"TEMP",
+	"TRANSACTION",
+	"TRIGGER",
+	"VACUUM",
+	"VIEW",
+];
+
+const SQL_SERVER: &[&str] = &[
+	"BACKUP",
+	"BEGIN",
+	"BREAK",
+	"BROWSE",
+	"BULK",
+	"CLUSTERED",
+	"DATABASE",
+	"DENY",
+	"DUMP",
+	"END",
+	"EXEC",
+	"EXECUTE",
+	"FILE",
+	"IDENTITY",
+	"INDEX",
+	"KEY",
+	"KILL",
+	"OPEN",
+	"PERCENT",
+	"PLAN",
+	"PRINT",
+	"PROC",
+	"PROCEDURE",
+	"PUBLIC",
+	"READ",
+	"RULE",
+	"SCHEMA",
+	"TOP",
+	"TRAN",
+	"TRANSACTION",
+	"TRIGGER",
+	"VIEW",
+];
+
+const ORACLE: &[&str] = &[
+	"ACCESS",
+	"AUDIT",
+	"CLUSTER",
+	"COMMENT",
+	"COMPRESS",
+	"DATE",
+	"EXCLUSIVE",
+	"FILE",
+	"IDENTIFIED",
+	"INDEX",
+	"LEVEL",
+	"LOCK",
+	"MODE",
+	"NUMBER",
+	"OPTION",
+	"PUBLIC",
+	"RAW",
+	"RENAME",
+	"RESOURCE",
+	"ROW",
+	"ROWID",
+	"ROWNUM",
+	"ROWS",
+	"SESSION",
+	"SIZE",
+	"START",
+	"SYSDATE",
+	"TRIGGER",
+	"UID",
+	"VIEW",
+];
+
+/// Whether the name has to be quoted to be used as a table or column name
+pub fn is_reserved_word(name: &str, dialect: SQLDialect) -> bool {
+	let dialect_words = match dialect {
+		SQLDialect::MySQL => MYSQL,
+		SQLDialect::Postgres => POSTGRES,
+		SQLDialect::SQLite => SQLITE,
+		SQLDialect::SQLServer => SQL_SERVER,
+		SQLDialect::Oracle => ORACLE,
+	};
+	COMMON
+		.iter()
+		.chain(dialect_words)
+		.any(|word| word.eq_ignore_ascii_case(name))
+}
@@ -0,0 +1,172 @@
+use gloo::storage::{LocalStorage, Storage};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use thiserror::Error;
+
+/// Bump this whenever the shape of stored data changes, and handle the previous version in `load`
+const STORAGE_VERSION: u32 = 1;
+
+#[derive(Serialize, Deserialize)]
+struct VersionedData<T> {
+	version: u32,
+	data: T,
+}
+
+#[derive(Deserialize)]
+struct StoredVersion {
+	version: u32,
+}
+
+#[derive(Error, Debug, PartialEq)]
+pub enum LoadError {
+	#[error("Stored data is from an older version of the app, please upload the project again")]
+	Outdated,
+
+	#[error("Stored data is from a newer version of the app, please update the page")]
+	FromNewerVersion,
+}
+
+pub fn store<T: Serialize>(key: &str, data: &T) {
+	let versioned = VersionedData {
+		version: STORAGE_VERSION,
+		data,
+	};
+	LocalStorage::set(key, &versioned).unwrap();
+}
+
+pub fn delete(key: &str) {
+	LocalStorage::delete(key);
+}
+
+enum Decoded<T> {
+	Current(T),
+	/// Stored by version 0, which needs to be stored again in an envelope
+	Unversioned(T),
+}
+
+fn decode<T: DeserializeOwned>(json: &str) -> Result<Decoded<T>, LoadError> {
+	if let Ok(versioned) = serde_json::from_str::<VersionedData<T>>(json) {
+		if versioned.version == STORAGE_VERSION {
+			return Ok(Decoded::Current(versioned.data));
+		}
+	}
+
+	if let Ok(StoredVersion { version }) = serde_json::from_str(json) {
+		if version > STORAGE_VERSION {
+			return Err(LoadError::FromNewerVersion);
+		}
+		return Err(LoadError::Outdated);
+	}
+
+	// Version 0 stored the data as is, without an envelope
+	serde_json::from_str(json)
+		.map(Decoded::Unversioned)
+		.map_err(|_| LoadError::Outdated)
+}
+
+/// Returns `Ok(None)` if nothing is stored under the key. Outdated data is deleted, so the
+/// error is only reported once.
+pub fn load<T: DeserializeOwned + Serialize>(key: &str) -> Result<Option<T>, LoadError> {
+	let Some(json) = LocalStorage::raw().get_item(key).ok().flatten() else {
+		return Ok(None);
+	};
+
+	match decode(&json) {
+		Ok(Decoded::Current(data)) => Ok(Some(data)),
+		Ok(Decoded::Unversioned(data)) => {
+			store(key, &data);
+			Ok(Some(data))
+		}
+		Err(LoadError::Outdated) => {
+			delete(key);
+			Err(LoadError::Outdated)
+		}
+		Err(e) => Err(e),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use magic_sql_gen::magicdraw_parser::{SQLTableCollection, SQLType};
+
+	use super::*;
+
+	// A collection the way version 0 stored it, before tables had kinds, unique constraints
+	// and indexes, and before columns were marked as auto incremented
+	const VERSION_0_COLLECTION: &str = r#"{
+		"name": "shop.sql",
+		"tables": [{
+			"schema": null,
+			"name": "customer",
+			"columns": [{
+				"ordinal": 0,
+				"name": "id",
+				"sql_type": "Int",
+				"primary_key": true,
+				"nullable": false,
+				"foreign_key": null,
+				"check_constraints": []
+			}],
+			"literals": null
+		}]
+	}"#;
+
+	// Version 0 stored columns without their ordinals, these can't be read anymore
+	const OUTDATED_COLLECTION: &str = r#"{
+		"name": null,
+		"tables": [{
+			"schema": null,
+			"name": "customer",
+			"columns": [{ "name": "id", "sql_type": "Int", "primary_key": true }],
+			"literals": null
+		}]
+	}"#;
+
+	fn check_collection(collection: &SQLTableCollection) {
+		assert_eq!(collection.name.as_deref(), Some("shop.sql"));
+		let table = &collection.tables[0];
+		assert_eq!(table.name, "customer");
+		assert_eq!(table.columns[0].sql_type, SQLType::Int);
+		assert!(table.columns[0].primary_key);
+	}
+
+	#[test]
+	fn decodes_unversioned_data() {
+		let Ok(Decoded::Unversioned(collection)) = decode(VERSION_0_COLLECTION) else {
+			panic!("version 0 collection was not decoded");
+		};
+		check_collection(&collection);
+	}
+
+	#[test]
+	fn decodes_current_version() {
+		let json = format!(
+			r#"{{ "version": {}, "data": {} }}"#,
+			STORAGE_VERSION, VERSION_0_COLLECTION
+		);
+		let Ok(Decoded::Current(collection)) = decode(&json) else {
+			panic!("version {} collection was not decoded", STORAGE_VERSION);
+		};
+		check_collection(&collection);
+	}
+
+	#[test]
+	fn reports_data_which_cant_be_read() {
+		let outdated = decode::<SQLTableCollection>(OUTDATED_COLLECTION);
+		assert!(matches!(outdated, Err(LoadError::Outdated)));
+
+		let wrong_shape = format!(
+			r#"{{ "version": {}, "data": {} }}"#,
+			STORAGE_VERSION, OUTDATED_COLLECTION
+		);
+		let wrong_shape = decode::<SQLTableCollection>(&wrong_shape);
+		assert!(matches!(wrong_shape, Err(LoadError::Outdated)));
+
+		let newer = format!(
+			r#"{{ "version": {}, "data": {} }}"#,
+			STORAGE_VERSION + 1,
+			VERSION_0_COLLECTION
+		);
+		let newer = decode::<SQLTableCollection>(&newer);
+		assert!(matches!(newer, Err(LoadError::FromNewerVersion)));
+	}
+}
@@ -0,0 +1,126 @@
+use std::{collections::HashMap, io::Cursor, rc::Rc};
+
+use js_sys::Uint8Array;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
+use crate::{
+	generate_sql::{
+		generate_guess, generate_rows, generate_table_guessess, to_sql, GenerateOptions,
+		SQLOptions, SQLValueGuess,
+	},
+	magicdraw_parser::{parse_project_file, ParseOptions, ProjectMetadata, SQLTableCollection},
+};
+
+/// What promises are rejected with, so callers can tell bad input apart from a failed parse
+#[derive(Serialize)]
+struct ApiError {
+	kind: &'static str,
+	message: String,
+}
+
+#[derive(Serialize)]
+struct ParsedProjectJs {
+	metadata: ProjectMetadata,
+	collections: Vec<SQLTableCollection>,
+	warnings: Vec<String>,
+}
+
+/// Options of `generateSql`, generation and SQL output options are given in one object
+#[derive(Default, Deserialize)]
+struct GenerateSqlOptions {
+	#[serde(flatten)]
+	generate: GenerateOptions,
+	#[serde(flatten)]
+	sql: SQLOptions,
+}
+
+fn api_error(kind: &'static str, message: impl ToString) -> JsValue {
+	let error = ApiError {
+		kind,
+		message: message.to_string(),
+	};
+	serde_wasm_bindgen::to_value(&error).unwrap_or_else(|e| JsValue::from_str(&e.to_string()))
+}
+
+fn from_js<T: DeserializeOwned>(value: JsValue, argument: &str) -> Result<T, JsValue> {
+	serde_wasm_bindgen::from_value(value)
+		.map_err(|e| api_error("invalid-argument", format!("{}: {}", argument, e)))
+}
+
+fn to_js<T: Serialize>(value: &T) -> Result<JsValue, JsValue> {
+	// Maps as plain objects, so the result can be passed to `JSON.stringify`
+	let serializer = serde_wasm_bindgen::Serializer::json_compatible();
+	value
+		.serialize(&serializer)
+		.map_err(|e| api_error("serialization", e))
+}
+
+/// Resolves to `{ metadata, collections, warnings }`, one collection per DDL script
+#[wasm_bindgen(js_name = parseProject)]
+pub async fn parse_project(bytes: Uint8Array) -> Result<JsValue, JsValue> {
+	let bytes = bytes.to_vec();
+	let project = parse_project_file(Cursor::new(bytes), &ParseOptions::default())
+		.map_err(|e| api_error("parse", e))?;
+
+	to_js(&ParsedProjectJs {
+		metadata: project.metadata,
+		collections: project.collections,
+		warnings: project.warnings.iter().map(ToString::to_string).collect(),
+	})
+}
+
+/// Guesses are a list with a `column -> guess` map for each table. When left out, or for
+/// tables which are not in the list, guesses are made from the column types.
+#[wasm_bindgen(js_name = generateSql)]
+pub async fn generate_sql(
+	collection: JsValue,
+	guessess: JsValue,
+	options: JsValue,
+) -> Result<String, JsValue> {
+	let collection: SQLTableCollection = from_js(collection, "collection")?;
+	let mut guessess: Vec<HashMap<String, SQLValueGuess>> =
+		if guessess.is_undefined() || guessess.is_null() {
+			vec![]
+		} else {
+			from_js(guessess, "guesses")?
+		};
+	let options: GenerateSqlOptions = if options.is_undefined() || options.is_null() {
+		GenerateSqlOptions::default()
+	} else {
+		from_js(options, "options")?
+	};
+
+	if let Some(problem) = options.generate.check() {
+		return Err(api_error(
+			"invalid-argument",
+			format!("options: {}", problem),
+		));
+	}
+
+	let tables = collection
+		.tables
+		.into_iter()
+		.map(Rc::new)
+		.collect::<Vec<_>>();
+	// Columns left out of a given map are guessed, foreign keys work without a guess
+	for (table, table_guessess) in tables.iter().zip(&mut guessess) {
+		for column in table
+			.columns
+			.iter()
+			.filter(|column| column.foreign_key.is_none())
+		{
+			table_guessess
+				.entry(column.name.clone())
+				.or_insert_with(|| generate_guess(column));
+		}
+	}
+	for table in tables.iter().skip(guessess.len()) {
+		guessess.push(generate_table_guessess(table));
+	}
+	let guessess = guessess.iter().collect::<Vec<_>>();
+
+	let generated = generate_rows(&tables, &guessess, &options.generate)
+		.map_err(|e| api_error("generate", e))?;
+	Ok(to_sql(&generated, &options.sql))
+}
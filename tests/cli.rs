@@ -0,0 +1,67 @@
+//! Runs the `magic-sql-gen` binary the way a CI pipeline would
+
+use std::{
+	env, fs,
+	process::{Command, Output},
+};
+
+const PROJECT: &str = "tests/fixtures/shop.mdzip";
+
+fn run(args: &[&str]) -> Output {
+	Command::new(env!("CARGO_BIN_EXE_magic-sql-gen"))
+		.args(args)
+		.output()
+		.unwrap()
+}
+
+#[test]
+fn seeded_output_matches_the_golden_file() {
+	let output = run(&[
+		"generate", PROJECT, "--rows", "3", "--rows", "orders=5", "--seed", "42",
+	]);
+	assert!(
+		output.status.success(),
+		"{}",
+		String::from_utf8_lossy(&output.stderr)
+	);
+	let expected = fs::read_to_string("tests/fixtures/shop_seed_42.sql").unwrap();
+	assert_eq!(String::from_utf8(output.stdout).unwrap(), expected);
+}
+
+#[test]
+fn writes_to_the_output_file() {
+	let out = env::temp_dir().join(format!("magic-sql-gen-{}.sql", std::process::id()));
+	let output = run(&[
+		"generate",
+		PROJECT,
+		"--rows",
+		"3",
+		"--rows",
+		"orders=5",
+		"--seed",
+		"42",
+		"--out",
+		out.to_str().unwrap(),
+	]);
+	assert!(output.status.success());
+	assert!(output.stdout.is_empty());
+	let written = fs::read_to_string(&out).unwrap();
+	fs::remove_file(&out).unwrap();
+	let expected = fs::read_to_string("tests/fixtures/shop_seed_42.sql").unwrap();
+	assert_eq!(written, expected);
+}
+
+#[test]
+fn rejects_unknown_options() {
+	let output = run(&["generate", PROJECT, "--bogus"]);
+	assert!(!output.status.success());
+	assert!(String::from_utf8_lossy(&output.stderr).contains("--bogus"));
+}
+
+#[test]
+fn rejects_create_tables_with_json() {
+	let output = run(&["generate", PROJECT, "--create-tables", "--json"]);
+	assert!(!output.status.success());
+	assert!(String::from_utf8_lossy(&output.stderr)
+		.contains("--create-tables can't be used with --json"));
+}
@@ -0,0 +1,15 @@
+INSERT INTO customer
+  (id, email)
+VALUES
+  (1, 'julianne_dolore@yahoo.com'),
+  (2, 'yessenia_voluptate@gmail.com'),
+  (3, 'myrl_amet@hotmail.com');
+
+INSERT INTO orders
+  (id, customer_id)
+VALUES
+  (1, 2),
+  (2, 2),
+  (3, 3),
+  (4, 2),
+  (5, 1);
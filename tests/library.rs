@@ -0,0 +1,57 @@
+//! Uses the library the way a native service would, without any of the web frontend
+
+use std::{fs::File, rc::Rc};
+
+use magic_sql_gen::{
+	generate_fake_entries, generate_sql::GenerateOptions, generate_table_guessess,
+	magicdraw_parser::ParseOptions, parse_project, SQLTableCollection, SQLValueGuess,
+};
+
+fn load_shop() -> SQLTableCollection {
+	let file = File::open("tests/fixtures/shop.mdzip").unwrap();
+	let mut project = parse_project(file, &ParseOptions::default()).unwrap();
+	assert!(project.warnings.is_empty(), "{:?}", project.warnings);
+	project.collections.remove(0)
+}
+
+#[test]
+fn parses_a_project() {
+	let collection = load_shop();
+	assert_eq!(collection.name.as_deref(), Some("shop.sql"));
+	let names = collection
+		.tables
+		.iter()
+		.map(|table| table.name.as_str())
+		.collect::<Vec<_>>();
+	assert_eq!(names, vec!["customer", "orders"]);
+
+	let customer_id = &collection.tables[1].columns[1];
+	assert_eq!(customer_id.name, "customer_id");
+	assert_eq!(customer_id.foreign_key.as_ref().unwrap().table, "customer");
+}
+
+#[test]
+fn generates_inserts_for_a_project() {
+	let tables = load_shop()
+		.tables
+		.into_iter()
+		.map(Rc::new)
+		.collect::<Vec<_>>();
+	let guessess = tables
+		.iter()
+		.map(|table| Box::new(generate_table_guessess(table)))
+		.collect::<Vec<_>>();
+	assert!(matches!(
+		guessess[1].get("customer_id"),
+		Some(SQLValueGuess::ForeignKey(_))
+	));
+
+	let options = GenerateOptions {
+		rows_per_table: 5,
+		seed: Some(1),
+		..Default::default()
+	};
+	let sql = generate_fake_entries(&tables, &guessess, &options).unwrap();
+	assert!(sql.contains("INSERT INTO customer"), "{}", sql);
+	assert!(sql.contains("INSERT INTO orders"), "{}", sql);
+}